@@ -0,0 +1,70 @@
+// Bedienung ohne Maus und Unterstützung für Screenreader: Buchstabentasten schalten gleichnamige Eingänge, Pfeiltasten
+// bewegen den Fokus in Tabellen und Diagrammen. Gezeichnete Anzeigen wie Pegel, LEDs und KV-Felder erhalten eine
+// Beschreibung, die egui über AccessKit an den Screenreader weitergibt.
+
+use egui::text_edit::TextEditState;
+use egui::{Context, Id, Key, Modifiers, Rect, Response, Sense, Ui, WidgetInfo, WidgetType};
+
+const LETTERS: [(Key, char); 26] = [
+    (Key::A, 'A'), (Key::B, 'B'), (Key::C, 'C'), (Key::D, 'D'), (Key::E, 'E'), (Key::F, 'F'), (Key::G, 'G'),
+    (Key::H, 'H'), (Key::I, 'I'), (Key::J, 'J'), (Key::K, 'K'), (Key::L, 'L'), (Key::M, 'M'), (Key::N, 'N'),
+    (Key::O, 'O'), (Key::P, 'P'), (Key::Q, 'Q'), (Key::R, 'R'), (Key::S, 'S'), (Key::T, 'T'), (Key::U, 'U'),
+    (Key::V, 'V'), (Key::W, 'W'), (Key::X, 'X'), (Key::Y, 'Y'), (Key::Z, 'Z'),
+];
+
+/// Ob gerade ein Textfeld bearbeitet wird. `Context::wants_keyboard_input` gilt schon, wenn irgendein Widget den
+/// Fokus hat, z.B. ein per Tab erreichter Schalter, dann sollen die Tastenkürzel aber weiter funktionieren.
+pub fn typing(ctx: &Context) -> bool {
+    let focus = ctx.memory().focus();
+    focus.is_some_and(|id| TextEditState::load(ctx, id).is_some())
+}
+
+/// Ohne Modifikator gedrückte Buchstaben als Großbuchstaben; solange ein Textfeld den Fokus hat, keine
+pub fn letters_pressed(ui: &Ui) -> Vec<char> {
+    if typing(ui.ctx()) {
+        return Vec::new();
+    }
+    let mut input = ui.ctx().input_mut();
+    LETTERS.iter().filter(|(key, _)| input.consume_key(Modifiers::NONE, *key)).map(|(_, c)| *c).collect()
+}
+
+/// Ob die Taste ohne Modifikator gedrückt wurde und nicht für ein Textfeld bestimmt ist
+pub fn key_pressed(ui: &Ui, key: Key) -> bool {
+    !typing(ui.ctx()) && ui.ctx().input_mut().consume_key(Modifiers::NONE, key)
+}
+
+pub fn level_name(high: bool) -> &'static str {
+    if high { "High" } else { "Low" }
+}
+
+/// Ersetzt die vorgelesene Beschriftung eines Widgets
+pub fn describe(response: &Response, typ: WidgetType, text: impl ToString) {
+    response.widget_info(|| WidgetInfo::labeled(typ, text.to_string()));
+}
+
+/// Beschreibung für einen nur gezeichneten Bereich ohne eigenes Widget
+pub fn describe_area(ui: &Ui, rect: Rect, id: Id, text: impl ToString) {
+    describe(&ui.interact(rect, id, Sense::hover()), WidgetType::Label, text);
+}
+
+/// Pfeiltasten bewegen den Fokus innerhalb eines Rasters von Widgets mit den IDs `ids[zeile][spalte]`
+pub fn grid_focus(ui: &Ui, ids: &[Vec<Id>]) {
+    let Some(focus) = ui.memory().focus() else { return };
+    let Some((row, col)) = ids.iter().enumerate().find_map(|(r, row)| row.iter().position(|&id| id == focus).map(|c| (r, c))) else {
+        return;
+    };
+    let target = if key_pressed(ui, Key::ArrowUp) {
+        (row.saturating_sub(1), col)
+    } else if key_pressed(ui, Key::ArrowDown) {
+        ((row + 1).min(ids.len() - 1), col)
+    } else if key_pressed(ui, Key::ArrowLeft) {
+        (row, col.saturating_sub(1))
+    } else if key_pressed(ui, Key::ArrowRight) {
+        (row, col + 1)
+    } else {
+        return;
+    };
+    if let Some(&id) = ids[target.0].get(target.1.min(ids[target.0].len() - 1)) {
+        ui.memory().request_focus(id);
+    }
+}
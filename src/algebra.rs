@@ -0,0 +1,410 @@
+// Schrittweise Umformung boolescher Ausdrücke mit den Gesetzen der booleschen Algebra.
+// In jedem Schritt wird genau ein Gesetz an genau einer Stelle angewendet, damit sich die
+// Herleitung wie eine Rechnung von Hand nachvollziehen lässt.
+
+use crate::expr::{and_of, or_of, Expr};
+
+/// Obergrenze, damit auch ungünstige Eingaben schnell ein Ergebnis liefern
+const MAX_STEPS: usize = 100;
+/// So weit wird vorausgerechnet, ob sich das Auflösen eines XOR lohnt
+const LOOKAHEAD_STEPS: usize = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Law {
+    DoubleNegation,
+    NegatedConstant,
+    Complement,
+    Neutral,
+    Extremal,
+    Idempotence,
+    Absorption,
+    ComplementAbsorption,
+    Associative,
+    XorDefinition,
+    DeMorgan,
+    Distributive,
+}
+
+/// Reihenfolge, in der die Gesetze probiert werden: erst alles, was den Ausdruck sofort
+/// verkürzt, dann Umformungen, die weitere Vereinfachungen erst möglich machen
+const LAWS: [Law; 12] = [
+    Law::DoubleNegation,
+    Law::NegatedConstant,
+    Law::Extremal,
+    Law::Neutral,
+    Law::Complement,
+    Law::Idempotence,
+    Law::Associative,
+    Law::Absorption,
+    Law::ComplementAbsorption,
+    Law::XorDefinition,
+    Law::DeMorgan,
+    Law::Distributive,
+];
+
+impl Law {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Law::DoubleNegation => "Doppelte Negation",
+            Law::NegatedConstant => "Negation einer Konstanten",
+            Law::Complement => "Komplementärgesetz",
+            Law::Neutral => "Neutralitätsgesetz",
+            Law::Extremal => "Extremalgesetz",
+            Law::Idempotence => "Idempotenzgesetz",
+            Law::Absorption => "Absorptionsgesetz",
+            Law::ComplementAbsorption => "Absorption mit Komplement",
+            Law::Associative => "Assoziativgesetz",
+            Law::XorDefinition => "Definition XOR",
+            Law::DeMorgan => "De Morgansche Regel",
+            Law::Distributive => "Distributivgesetz",
+        }
+    }
+
+    pub fn formula(&self) -> &'static str {
+        match self {
+            Law::DoubleNegation => "¬¬A = A",
+            Law::NegatedConstant => "¬0 = 1, ¬1 = 0",
+            Law::Complement => "A ∧ ¬A = 0, A ∨ ¬A = 1",
+            Law::Neutral => "A ∧ 1 = A, A ∨ 0 = A",
+            Law::Extremal => "A ∧ 0 = 0, A ∨ 1 = 1",
+            Law::Idempotence => "A ∧ A = A, A ∨ A = A",
+            Law::Absorption => "A ∨ (A ∧ B) = A, A ∧ (A ∨ B) = A",
+            Law::ComplementAbsorption => "A ∨ (¬A ∧ B) = A ∨ B, A ∧ (¬A ∨ B) = A ∧ B",
+            Law::Associative => "(A ∧ B) ∧ C = A ∧ B ∧ C, (A ∨ B) ∨ C = A ∨ B ∨ C",
+            Law::XorDefinition => "A ⊕ B = (A ∧ ¬B) ∨ (¬A ∧ B)",
+            Law::DeMorgan => "¬(A ∧ B) = ¬A ∨ ¬B, ¬(A ∨ B) = ¬A ∧ ¬B",
+            Law::Distributive => "(A ∧ B) ∨ (A ∧ C) = A ∧ (B ∨ C), (A ∨ B) ∧ (A ∨ C) = A ∨ (B ∧ C)",
+        }
+    }
+
+    /// Wendet das Gesetz an der Wurzel von `e` an, falls möglich
+    fn apply(&self, e: &Expr) -> Option<Expr> {
+        match (self, e) {
+            (Law::DoubleNegation, Expr::Not(inner)) => match inner.as_ref() {
+                Expr::Not(x) => Some(x.as_ref().clone()),
+                _ => None,
+            },
+            (Law::NegatedConstant, Expr::Not(inner)) => match inner.as_ref() {
+                Expr::Const(c) => Some(Expr::Const(!c)),
+                _ => None,
+            },
+            (Law::Extremal, Expr::And(es)) => es.contains(&Expr::Const(false)).then_some(Expr::Const(false)),
+            (Law::Extremal, Expr::Or(es)) => es.contains(&Expr::Const(true)).then_some(Expr::Const(true)),
+            (Law::Neutral, Expr::And(es)) => remove_all(es, &Expr::Const(true)).map(and_of),
+            (Law::Neutral, Expr::Or(es)) => remove_all(es, &Expr::Const(false)).map(or_of),
+            (Law::Complement, Expr::And(es)) => replace_complements(es, false).map(and_of),
+            (Law::Complement, Expr::Or(es)) => replace_complements(es, true).map(or_of),
+            (Law::Idempotence, Expr::And(es)) => remove_duplicates(es).map(and_of),
+            (Law::Idempotence, Expr::Or(es)) => remove_duplicates(es).map(or_of),
+            (Law::Associative, Expr::And(es)) => flatten(es, |e| matches!(e, Expr::And(_))).map(Expr::And),
+            (Law::Associative, Expr::Or(es)) => flatten(es, |e| matches!(e, Expr::Or(_))).map(Expr::Or),
+            (Law::Absorption, Expr::And(es)) => absorb(es, disjuncts).map(and_of),
+            (Law::Absorption, Expr::Or(es)) => absorb(es, conjuncts).map(or_of),
+            (Law::ComplementAbsorption, Expr::And(es)) => absorb_complement(es, disjuncts, or_of).map(and_of),
+            (Law::ComplementAbsorption, Expr::Or(es)) => absorb_complement(es, conjuncts, and_of).map(or_of),
+            (Law::XorDefinition, Expr::Xor(a, b)) => {
+                let (a, b) = (a.as_ref().clone(), b.as_ref().clone());
+                let expanded = Expr::Or(vec![
+                    Expr::And(vec![a.clone(), negate(&b)]),
+                    Expr::And(vec![negate(&a), b]),
+                ]);
+                // Aufgelöst wird nur, wenn der Ausdruck danach kürzer wird, sonst wächst z.B. A ⊕ B ⊕ C ⊕ D
+                // mit jedem Schritt, ohne einfacher zu werden
+                let laws: Vec<Law> = LAWS.into_iter().filter(|&law| law != Law::XorDefinition).collect();
+                let (steps, _) = run(&expanded, &laws, LOOKAHEAD_STEPS);
+                let result = steps.last().map_or(&expanded, |step| &step.expr);
+                (literals(result) < literals(e)).then_some(expanded)
+            }
+            (Law::DeMorgan, Expr::Not(inner)) => match inner.as_ref() {
+                Expr::And(es) => Some(Expr::Or(es.iter().map(negate).collect())),
+                Expr::Or(es) => Some(Expr::And(es.iter().map(negate).collect())),
+                _ => None,
+            },
+            (Law::Distributive, Expr::Or(es)) => factor_out(es, conjuncts, and_of, or_of).map(or_of),
+            (Law::Distributive, Expr::And(es)) => factor_out(es, disjuncts, or_of, and_of).map(and_of),
+            _ => None,
+        }
+    }
+}
+
+pub struct Step {
+    pub law: Law,
+    pub expr: Expr,
+}
+
+pub struct Derivation {
+    pub steps: Vec<Step>,
+    /// Falsch, wenn nach `MAX_STEPS` Schritten abgebrochen wurde, obwohl noch ein Gesetz gegriffen hätte
+    pub complete: bool,
+}
+
+/// Formt `expr` so lange um, bis keines der Gesetze den Ausdruck weiter vereinfacht
+pub fn derive(expr: &Expr) -> Derivation {
+    let (steps, complete) = run(expr, &LAWS, MAX_STEPS);
+    Derivation { steps, complete }
+}
+
+/// Wendet die Gesetze in der gegebenen Reihenfolge an, höchstens `max_steps` Mal; gibt zusätzlich zurück, ob danach
+/// keines mehr greift
+fn run(expr: &Expr, laws: &[Law], max_steps: usize) -> (Vec<Step>, bool) {
+    let mut steps: Vec<Step> = Vec::new();
+    let mut current = expr.clone();
+    'outer: loop {
+        for &law in laws {
+            if let Some(next) = apply_anywhere(law, &current) {
+                if steps.len() == max_steps {
+                    return (steps, false);
+                }
+                current = next;
+                steps.push(Step { law, expr: current.clone() });
+                continue 'outer;
+            }
+        }
+        return (steps, true);
+    }
+}
+
+/// Anzahl der Variablen und Konstanten als Maß für die Länge eines Ausdrucks
+fn literals(e: &Expr) -> usize {
+    match e {
+        Expr::Const(_) | Expr::Var(_) => 1,
+        Expr::Not(x) => literals(x),
+        Expr::And(es) | Expr::Or(es) => es.iter().map(literals).sum(),
+        Expr::Xor(a, b) => literals(a) + literals(b),
+    }
+}
+
+/// Sucht die erste Stelle (von außen nach innen), an der das Gesetz greift
+fn apply_anywhere(law: Law, e: &Expr) -> Option<Expr> {
+    if let Some(result) = law.apply(e) {
+        return Some(result);
+    }
+    let in_list = |es: &Vec<Expr>| {
+        es.iter().enumerate().find_map(|(i, child)| {
+            apply_anywhere(law, child).map(|new| {
+                let mut es = es.clone();
+                es[i] = new;
+                es
+            })
+        })
+    };
+    match e {
+        Expr::Not(x) => apply_anywhere(law, x).map(|x| Expr::Not(Box::new(x))),
+        Expr::And(es) => in_list(es).map(Expr::And),
+        Expr::Or(es) => in_list(es).map(Expr::Or),
+        Expr::Xor(a, b) => match apply_anywhere(law, a) {
+            Some(a) => Some(Expr::Xor(Box::new(a), b.clone())),
+            None => apply_anywhere(law, b).map(|b| Expr::Xor(a.clone(), Box::new(b))),
+        },
+        Expr::Const(_) | Expr::Var(_) => None,
+    }
+}
+
+/// Form, in der Ausdrücke unabhängig von der Reihenfolge der Operanden verglichen werden (Kommutativgesetz)
+fn canonical(e: &Expr) -> Expr {
+    match e {
+        Expr::And(es) | Expr::Or(es) => {
+            let mut es: Vec<Expr> = es.iter().map(canonical).collect();
+            es.sort();
+            if matches!(e, Expr::And(_)) {
+                Expr::And(es)
+            } else {
+                Expr::Or(es)
+            }
+        }
+        Expr::Not(x) => Expr::Not(Box::new(canonical(x))),
+        Expr::Xor(a, b) => {
+            let (a, b) = (canonical(a), canonical(b));
+            Expr::Xor(Box::new(a.clone().min(b.clone())), Box::new(a.max(b)))
+        }
+        _ => e.clone(),
+    }
+}
+
+fn same(a: &Expr, b: &Expr) -> bool {
+    a == b || canonical(a) == canonical(b)
+}
+
+fn negate(e: &Expr) -> Expr {
+    Expr::Not(Box::new(e.clone()))
+}
+
+fn complement_of(a: &Expr, b: &Expr) -> bool {
+    match (a, b) {
+        (Expr::Not(x), y) | (y, Expr::Not(x)) => same(x, y),
+        _ => false,
+    }
+}
+
+fn conjuncts(e: &Expr) -> Vec<Expr> {
+    match e {
+        Expr::And(es) => es.clone(),
+        _ => vec![e.clone()],
+    }
+}
+
+fn disjuncts(e: &Expr) -> Vec<Expr> {
+    match e {
+        Expr::Or(es) => es.clone(),
+        _ => vec![e.clone()],
+    }
+}
+
+fn remove_all(es: &[Expr], x: &Expr) -> Option<Vec<Expr>> {
+    es.contains(x).then(|| es.iter().filter(|e| *e != x).cloned().collect())
+}
+
+/// Ersetzt ein Paar `X`, `¬X` durch das Ergebnis des Komplementärgesetzes
+fn replace_complements(es: &[Expr], value: bool) -> Option<Vec<Expr>> {
+    for i in 0..es.len() {
+        for j in i + 1..es.len() {
+            if complement_of(&es[i], &es[j]) {
+                let mut result: Vec<Expr> = es.to_vec();
+                result.remove(j);
+                result[i] = Expr::Const(value);
+                return Some(result);
+            }
+        }
+    }
+    None
+}
+
+fn remove_duplicates(es: &[Expr]) -> Option<Vec<Expr>> {
+    let mut result: Vec<Expr> = Vec::new();
+    for e in es {
+        if !result.iter().any(|r| same(r, e)) {
+            result.push(e.clone());
+        }
+    }
+    (result.len() < es.len()).then_some(result)
+}
+
+fn flatten(es: &[Expr], is_same_op: impl Fn(&Expr) -> bool) -> Option<Vec<Expr>> {
+    if !es.iter().any(&is_same_op) {
+        return None;
+    }
+    Some(
+        es.iter()
+            .flat_map(|e| match e {
+                Expr::And(inner) | Expr::Or(inner) if is_same_op(e) => inner.clone(),
+                _ => vec![e.clone()],
+            })
+            .collect(),
+    )
+}
+
+/// `A ∨ (A ∧ B) = A`: ein Term fällt weg, wenn ein anderer Term aus einem Teil seiner Faktoren besteht
+fn absorb(es: &[Expr], parts: fn(&Expr) -> Vec<Expr>) -> Option<Vec<Expr>> {
+    for i in 0..es.len() {
+        for j in 0..es.len() {
+            let (big, small) = (parts(&es[i]), parts(&es[j]));
+            if i != j && small.len() < big.len() && small.iter().all(|s| big.iter().any(|b| same(b, s))) {
+                let mut result = es.to_vec();
+                result.remove(i);
+                return Some(result);
+            }
+        }
+    }
+    None
+}
+
+/// `A ∨ (¬A ∧ B) = A ∨ B`: das Komplement eines anderen Terms fällt aus einem Term heraus
+fn absorb_complement(
+    es: &[Expr],
+    parts: fn(&Expr) -> Vec<Expr>,
+    rebuild: fn(Vec<Expr>) -> Expr,
+) -> Option<Vec<Expr>> {
+    for i in 0..es.len() {
+        let big = parts(&es[i]);
+        if big.len() < 2 {
+            continue;
+        }
+        for (j, small) in es.iter().enumerate() {
+            if let Some(k) = big.iter().position(|b| i != j && complement_of(b, small)) {
+                let mut rest = big.clone();
+                rest.remove(k);
+                let mut result = es.to_vec();
+                result[i] = rebuild(rest);
+                return Some(result);
+            }
+        }
+    }
+    None
+}
+
+/// `(A ∧ B) ∨ (A ∧ C) = A ∧ (B ∨ C)`: klammert den Faktor aus, der in den meisten Termen vorkommt
+fn factor_out(
+    es: &[Expr],
+    parts: fn(&Expr) -> Vec<Expr>,
+    inner_of: fn(Vec<Expr>) -> Expr,
+    outer_of: fn(Vec<Expr>) -> Expr,
+) -> Option<Vec<Expr>> {
+    let terms: Vec<Vec<Expr>> = es.iter().map(parts).collect();
+    let containing = |f: &Expr| -> Vec<usize> {
+        (0..terms.len()).filter(|&t| terms[t].len() > 1 && terms[t].iter().any(|x| same(x, f))).collect()
+    };
+    // Bei Gleichstand gewinnt der zuerst vorkommende Faktor, damit die Umformung der Schreibweise folgt
+    let mut best: Option<(usize, &Expr)> = None;
+    for f in terms.iter().flatten() {
+        let count = containing(f).len();
+        if count >= 2 && best.is_none_or(|(c, _)| count > c) {
+            best = Some((count, f));
+        }
+    }
+    let factor = best?.1.clone();
+    let group = containing(&factor);
+    let rests: Vec<Expr> = group
+        .iter()
+        .map(|&t| {
+            let mut rest = terms[t].clone();
+            let k = rest.iter().position(|x| same(x, &factor)).unwrap();
+            rest.remove(k);
+            inner_of(rest)
+        })
+        .collect();
+    let factored = inner_of(vec![factor, outer_of(rests)]);
+    let mut result = Vec::new();
+    for (t, e) in es.iter().enumerate() {
+        if t == group[0] {
+            result.push(factored.clone());
+        } else if !group.contains(&t) {
+            result.push(e.clone());
+        }
+    }
+    Some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::equivalence::row_inputs;
+    use crate::test_support::{random_expr, Rng};
+
+    fn table(expr: &Expr, vars: usize) -> Vec<bool> {
+        (0..1 << vars).map(|row| expr.eval(&row_inputs(row, vars))).collect()
+    }
+
+    #[test]
+    fn every_step_keeps_the_function() {
+        let mut rng = Rng(0x0a1e_b7a5_1234_5678);
+        for _ in 0..2000 {
+            let expr = random_expr(&mut rng, 4, 4, true);
+            let expected = table(&expr, 4);
+            for step in derive(&expr).steps {
+                assert_eq!(table(&step.expr, 4), expected, "{:?} nach {} aus {:?}", step.expr, step.law.name(), expr);
+            }
+        }
+    }
+
+    #[test]
+    fn stops_after_max_steps() {
+        // Jede doppelte Negation kostet einen Schritt
+        let nested = |count: usize| (0..2 * count).fold(Expr::Var(0), |e, _| Expr::Not(Box::new(e)));
+        let derivation = derive(&nested(MAX_STEPS + 1));
+        assert!(!derivation.complete);
+        assert_eq!(derivation.steps.len(), MAX_STEPS);
+        let derivation = derive(&nested(MAX_STEPS));
+        assert!(derivation.complete);
+        assert_eq!(derivation.steps.last().map(|step| &step.expr), Some(&Expr::Var(0)));
+    }
+}
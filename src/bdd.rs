@@ -0,0 +1,120 @@
+// Reduzierte geordnete binäre Entscheidungsdiagramme (ROBDD) für Äquivalenzprüfungen.
+// Zwei Funktionen sind genau dann gleich, wenn sie auf denselben Knoten abgebildet werden.
+
+use std::collections::HashMap;
+
+pub type BddRef = usize;
+
+pub const FALSE: BddRef = 0;
+pub const TRUE: BddRef = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Op {
+    And,
+    Or,
+    Xor,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct BddNode {
+    var: usize,
+    low: BddRef,
+    high: BddRef,
+}
+
+pub struct Bdd {
+    nodes: Vec<BddNode>,
+    unique: HashMap<BddNode, BddRef>,
+    cache: HashMap<(Op, BddRef, BddRef), BddRef>,
+}
+
+impl Default for Bdd {
+    fn default() -> Self {
+        // Die Terminale bekommen eine Variable hinter allen echten Variablen
+        let terminal = BddNode { var: usize::MAX, low: FALSE, high: FALSE };
+        Self {
+            nodes: vec![terminal, terminal],
+            unique: HashMap::new(),
+            cache: HashMap::new(),
+        }
+    }
+}
+
+impl Bdd {
+    fn make(&mut self, var: usize, low: BddRef, high: BddRef) -> BddRef {
+        if low == high {
+            return low;
+        }
+        let node = BddNode { var, low, high };
+        if let Some(&r) = self.unique.get(&node) {
+            return r;
+        }
+        self.nodes.push(node);
+        self.unique.insert(node, self.nodes.len() - 1);
+        self.nodes.len() - 1
+    }
+
+    pub fn var(&mut self, var: usize) -> BddRef {
+        self.make(var, FALSE, TRUE)
+    }
+
+    fn apply(&mut self, op: Op, a: BddRef, b: BddRef) -> BddRef {
+        match (op, a, b) {
+            (Op::And, FALSE, _) | (Op::And, _, FALSE) => return FALSE,
+            (Op::And, TRUE, x) | (Op::And, x, TRUE) => return x,
+            (Op::Or, TRUE, _) | (Op::Or, _, TRUE) => return TRUE,
+            (Op::Or, FALSE, x) | (Op::Or, x, FALSE) => return x,
+            (Op::Xor, FALSE, x) | (Op::Xor, x, FALSE) => return x,
+            (Op::Xor, TRUE, TRUE) => return FALSE,
+            _ => {}
+        }
+        if let Some(&r) = self.cache.get(&(op, a, b)) {
+            return r;
+        }
+        let (na, nb) = (self.nodes[a], self.nodes[b]);
+        let var = na.var.min(nb.var);
+        let (a_low, a_high) = if na.var == var { (na.low, na.high) } else { (a, a) };
+        let (b_low, b_high) = if nb.var == var { (nb.low, nb.high) } else { (b, b) };
+        let low = self.apply(op, a_low, b_low);
+        let high = self.apply(op, a_high, b_high);
+        let r = self.make(var, low, high);
+        self.cache.insert((op, a, b), r);
+        r
+    }
+
+    pub fn and(&mut self, a: BddRef, b: BddRef) -> BddRef {
+        self.apply(Op::And, a, b)
+    }
+
+    pub fn or(&mut self, a: BddRef, b: BddRef) -> BddRef {
+        self.apply(Op::Or, a, b)
+    }
+
+    pub fn xor(&mut self, a: BddRef, b: BddRef) -> BddRef {
+        self.apply(Op::Xor, a, b)
+    }
+
+    pub fn not(&mut self, a: BddRef) -> BddRef {
+        self.apply(Op::Xor, a, TRUE)
+    }
+
+    /// Eine Belegung der Variablen `0..var_count`, für die `f` wahr ist
+    pub fn satisfying_assignment(&self, f: BddRef, var_count: usize) -> Option<Vec<bool>> {
+        if f == FALSE {
+            return None;
+        }
+        let mut assignment = vec![false; var_count];
+        let mut node = f;
+        while node != TRUE {
+            let n = self.nodes[node];
+            // Da reduziert, führt jeder Nicht-FALSE-Zweig irgendwann zu TRUE
+            if n.high != FALSE {
+                assignment[n.var] = true;
+                node = n.high;
+            } else {
+                node = n.low;
+            }
+        }
+        Some(assignment)
+    }
+}
@@ -0,0 +1,95 @@
+// Busse: Anschlüsse, die mehrere Bits bündeln, und die Darstellung ihres Werts als Zahl.
+// Wie bei den Tabellenzeilen steht das höchstwertige Bit vorne.
+
+use serde::{Deserialize, Serialize};
+
+use crate::equivalence::{row_index, row_inputs};
+
+/// Breitester Bus, den Ein- und Ausgänge haben können
+pub const MAX_WIDTH: usize = 16;
+
+/// Benannter Anschluss eines Bauteils aus `width` nebeneinanderliegenden Pins
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Port {
+    pub name: String,
+    pub width: usize,
+}
+
+impl Port {
+    pub fn new(name: impl Into<String>, width: usize) -> Self {
+        Self { name: name.into(), width }
+    }
+
+    /// Namen der einzelnen Bits, z.B. A3 bis A0; ein einzelnes Bit behält den Namen des Anschlusses
+    pub fn bit_names(&self) -> Vec<String> {
+        if self.width == 1 {
+            vec![self.name.clone()]
+        } else {
+            bits(&self.name, self.width)
+        }
+    }
+}
+
+/// Namen `prefix{n-1}` bis `prefix0`
+pub fn bits(prefix: &str, n: usize) -> Vec<String> {
+    (0..n).rev().map(|i| format!("{}{}", prefix, i)).collect()
+}
+
+/// Anschluss, zu dem `pin` gehört, und dessen erster Pin
+pub fn locate(ports: &[Port], pin: usize) -> (usize, usize) {
+    let mut first = 0;
+    for (i, port) in ports.iter().enumerate() {
+        if pin < first + port.width {
+            return (i, first);
+        }
+        first += port.width;
+    }
+    (ports.len(), first)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum NumberFormat {
+    #[default]
+    Binary,
+    Unsigned,
+    /// Zweierkomplement
+    Signed,
+    Hex,
+}
+
+pub const ALL_FORMATS: [NumberFormat; 4] = [NumberFormat::Binary, NumberFormat::Unsigned, NumberFormat::Signed, NumberFormat::Hex];
+
+impl NumberFormat {
+    pub fn label(&self) -> &'static str {
+        match self {
+            NumberFormat::Binary => "Binär",
+            NumberFormat::Unsigned => "Dezimal ohne Vorzeichen",
+            NumberFormat::Signed => "Dezimal mit Vorzeichen (Zweierkomplement)",
+            NumberFormat::Hex => "Hexadezimal",
+        }
+    }
+
+    pub fn format(&self, bits: &[bool]) -> String {
+        match self {
+            NumberFormat::Binary => bits.iter().map(|&b| if b { '1' } else { '0' }).collect(),
+            NumberFormat::Unsigned => row_index(bits).to_string(),
+            NumberFormat::Signed => signed_value(bits).to_string(),
+            NumberFormat::Hex => format!("0x{:0width$X}", row_index(bits), width = bits.len().div_ceil(4)),
+        }
+    }
+}
+
+/// Wert im Zweierkomplement: das höchstwertige Bit zählt negativ
+pub fn signed_value(bits: &[bool]) -> i64 {
+    let value = row_index(bits) as i64;
+    if bits.first() == Some(&true) {
+        value - (1 << bits.len())
+    } else {
+        value
+    }
+}
+
+/// Bits einer Zahl mit der gegebenen Breite; negative Zahlen im Zweierkomplement
+pub fn from_value(value: i64, width: usize) -> Vec<bool> {
+    row_inputs((value & ((1 << width) - 1)) as usize, width)
+}
@@ -0,0 +1,63 @@
+// Seitenleiste mit den kanonischen Normalformen der gerade angezeigten Funktion
+
+use egui::{RichText, Ui};
+
+use crate::equivalence::row_inputs;
+use crate::{gen_bit_label, gen_value_label};
+use crate::truth_table::TruthTable;
+
+/// Darüber werden Tabelle und Normalformen zu lang, um noch etwas daraus abzulesen
+pub const MAX_INPUTS: usize = 6;
+
+fn index_list(rows: &[usize]) -> String {
+    rows.iter().map(|r| r.to_string()).collect::<Vec<_>>().join(", ")
+}
+
+pub fn show(ui: &mut Ui, table: &TruthTable) {
+    egui::ScrollArea::vertical().show(ui, |ui| {
+        for output in &table.outputs {
+            ui.label(RichText::new(format!("Ausgang {}", output.name)).color(ui.visuals().strong_text_color()));
+            // Beliebige Zeilen werden getrennt angegeben, z.B. Y = Σm(2, 3) + Σd(6, 7)
+            let dont_cares = output.dont_cares();
+            let (sum_d, product_d) = if dont_cares.is_empty() {
+                (String::new(), String::new())
+            } else {
+                (format!(" + Σd({})", index_list(&dont_cares)), format!(" · Πd({})", index_list(&dont_cares)))
+            };
+            ui.label(RichText::new(format!("{} = Σm({}){}", output.name, index_list(&output.minterms()), sum_d)).monospace());
+            ui.label(RichText::new(format!("{} = ΠM({}){}", output.name, index_list(&output.maxterms()), product_d)).monospace());
+            ui.label("Disjunktive Normalform (DNF):");
+            ui.label(RichText::new(format!("{} = {}", output.name, table.dnf(output).display(&table.inputs))).monospace());
+            ui.label("Konjunktive Normalform (KNF):");
+            ui.label(RichText::new(format!("{} = {}", output.name, table.cnf(output).display(&table.inputs))).monospace());
+            ui.separator();
+        }
+
+        ui.label(RichText::new("Zeilen der Wahrheitstabelle").color(ui.visuals().strong_text_color()));
+        ui.label("Jede Zeile mit Ausgang 1 liefert ihren Minterm zur DNF, jede Zeile mit Ausgang 0 ihren Maxterm zur KNF.");
+        egui::Grid::new("canonical_rows").striped(true).show(ui, |ui| {
+            ui.strong("Nr.");
+            for input in &table.inputs {
+                ui.strong(input);
+            }
+            for output in &table.outputs {
+                ui.strong(&output.name);
+            }
+            ui.strong("Minterm");
+            ui.strong("Maxterm");
+            ui.end_row();
+            for row in 0..table.row_count() {
+                ui.label(row.to_string());
+                for value in row_inputs(row, table.inputs.len()) {
+                    ui.add(gen_bit_label(value));
+                }
+                for output in &table.outputs {
+                    ui.add(gen_value_label(output.values[row]));
+                }
+                ui.label(RichText::new(format!("m{} = {}", row, table.minterm(row).display(&table.inputs))).monospace());
+                ui.label(RichText::new(format!("M{} = {}", row, table.maxterm(row).display(&table.inputs))).monospace());
+                ui.end_row();
+            }
+        });
+    });
+}
@@ -0,0 +1,602 @@
+// Datenmodell einer Schaltung aus Eingängen, Gattern, Bausteinen und Ausgängen
+
+use egui::{Pos2, Rect, Vec2};
+use serde::{Deserialize, Serialize};
+
+use crate::bus::{locate, NumberFormat, Port, MAX_WIDTH};
+use crate::components::Component;
+use crate::equivalence::{row_inputs, EXHAUSTIVE_MAX_INPUTS};
+use crate::expr::Expr;
+use crate::layout;
+use crate::level::{self, Level, Tristate};
+use crate::peripherals::{InputDevice, OutputDevice, SEGMENTS};
+use crate::timing::{DEFAULT_DELAY, MAX_DELAY};
+use crate::LogicGates;
+
+/// Ein- und Ausgänge mit mehr als einem Bit sind Busse; ihr Wert wird im gewählten Format als Zahl angezeigt.
+/// Das Gerät bestimmt, wie sie bedient bzw. angezeigt werden.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum NodeKind {
+    Input { name: String, value: Vec<bool>, format: NumberFormat, device: InputDevice },
+    Output { name: String, width: usize, format: NumberFormat, device: OutputDevice },
+    Gate(LogicGates),
+    Component(Component),
+    Tristate(Tristate),
+}
+
+impl NodeKind {
+    /// Eingang mit einem Bit
+    pub fn input(name: impl Into<String>) -> Self {
+        Self::bus_input(name, 1)
+    }
+
+    /// Ausgang mit einem Bit
+    pub fn output(name: impl Into<String>) -> Self {
+        Self::bus_output(name, 1)
+    }
+
+    pub fn bus_input(name: impl Into<String>, width: usize) -> Self {
+        NodeKind::Input { name: name.into(), value: vec![false; width], format: NumberFormat::default(), device: InputDevice::default() }
+    }
+
+    pub fn bus_output(name: impl Into<String>, width: usize) -> Self {
+        NodeKind::Output { name: name.into(), width, format: NumberFormat::default(), device: OutputDevice::default() }
+    }
+
+    /// Eingang, der als Taster, DIP-Schalter oder Hex-Tastatur bedient wird
+    pub fn device_input(name: impl Into<String>, device: InputDevice) -> Self {
+        NodeKind::Input { name: name.into(), value: vec![false; device.default_width()], format: NumberFormat::default(), device }
+    }
+
+    /// Ausgang, der als LED oder 7-Segment-Anzeige erscheint
+    pub fn device_output(name: impl Into<String>, device: OutputDevice) -> Self {
+        NodeKind::Output { name: name.into(), width: device.default_width(), format: NumberFormat::default(), device }
+    }
+
+    /// Ob sich die Breite ändern lässt; Taster, Tastatur, LED und 7-Segment-Anzeige haben eine feste
+    pub fn resizable(&self) -> bool {
+        matches!(
+            self,
+            NodeKind::Input { device: InputDevice::Switch | InputDevice::Dip, .. } | NodeKind::Output { device: OutputDevice::Lamp, .. }
+        )
+    }
+
+    pub fn is_io(&self) -> bool {
+        matches!(self, NodeKind::Input { .. } | NodeKind::Output { .. })
+    }
+
+    /// Zahl der Bits eines Ein- oder Ausgangs
+    pub fn width(&self) -> usize {
+        match self {
+            NodeKind::Input { value, .. } => value.len(),
+            NodeKind::Output { width, .. } => *width,
+            NodeKind::Gate(_) | NodeKind::Component(_) | NodeKind::Tristate(_) => 1,
+        }
+    }
+
+    /// Anschlüsse auf der linken Seite; ihre Pins sind fortlaufend nummeriert
+    pub fn input_ports(&self) -> Vec<Port> {
+        match self {
+            NodeKind::Input { .. } => Vec::new(),
+            NodeKind::Output { name, device: OutputDevice::SevenSegment(_), .. } => {
+                SEGMENTS.iter().map(|segment| Port::new(format!("{}{}", name, segment), 1)).collect()
+            }
+            NodeKind::Output { name, .. } => vec![Port::new(name.clone(), self.width())],
+            NodeKind::Gate(gate) => vec![Port::new("", 1); gate.input_count()],
+            NodeKind::Component(component) => component.input_ports(),
+            NodeKind::Tristate(tristate) => tristate.input_ports(),
+        }
+    }
+
+    /// Anschlüsse auf der rechten Seite
+    pub fn output_ports(&self) -> Vec<Port> {
+        match self {
+            // Jeder DIP-Schalter ist ein eigener Anschluss
+            NodeKind::Input { name, device: InputDevice::Dip, .. } => {
+                Port::new(name.clone(), self.width()).bit_names().into_iter().map(|bit| Port::new(bit, 1)).collect()
+            }
+            NodeKind::Input { name, .. } => vec![Port::new(name.clone(), self.width())],
+            NodeKind::Output { .. } => Vec::new(),
+            NodeKind::Gate(_) | NodeKind::Tristate(_) => vec![Port::new("", 1)],
+            NodeKind::Component(component) => component.output_ports(),
+        }
+    }
+
+    pub fn input_count(&self) -> usize {
+        self.input_ports().iter().map(|p| p.width).sum()
+    }
+
+    pub fn output_count(&self) -> usize {
+        self.output_ports().iter().map(|p| p.width).sum()
+    }
+}
+
+/// Ausgang `pin` des Knotens `node`, an dem eine Leitung beginnt
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct Source {
+    pub node: usize,
+    pub pin: usize,
+}
+
+/// Knoten mit nur einem Ausgang
+impl From<usize> for Source {
+    fn from(node: usize) -> Self {
+        Self { node, pin: 0 }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Node {
+    pub kind: NodeKind,
+    /// Mittelpunkt des Bauteils auf der Zeichenfläche
+    pub pos: Pos2,
+    /// Quelle je Eingang, `None` = nicht verbunden
+    pub inputs: Vec<Option<Source>>,
+    /// Knickpunkte je Eingangsleitung von der Quelle zum Eingang, wie sie `layout::arrange` festlegt.
+    /// Passen sie nicht mehr zu den Anschlüssen, wird die Leitung mit einem Knick in der Mitte gezeichnet.
+    pub routes: Vec<Vec<Pos2>>,
+    /// Laufzeit eines Gatters oder Bausteins in Zeiteinheiten, nur für die Simulation des Zeitverhaltens
+    pub delay: u32,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Circuit {
+    pub nodes: Vec<Node>,
+}
+
+impl Circuit {
+    pub fn add_node(&mut self, kind: NodeKind, pos: Pos2) -> usize {
+        let inputs = vec![None; kind.input_count()];
+        let routes = vec![Vec::new(); inputs.len()];
+        self.nodes.push(Node { kind, pos, inputs, routes, delay: DEFAULT_DELAY });
+        self.nodes.len() - 1
+    }
+
+    /// Entfernt einen Knoten samt aller Leitungen, die von ihm ausgehen
+    pub fn remove_node(&mut self, idx: usize) {
+        self.nodes.remove(idx);
+        for node in &mut self.nodes {
+            for input in &mut node.inputs {
+                *input = match *input {
+                    Some(src) if src.node == idx => None,
+                    Some(src) if src.node > idx => Some(Source { node: src.node - 1, ..src }),
+                    other => other,
+                };
+            }
+        }
+    }
+
+    /// Prüft, ob das Signal von `node` (direkt oder indirekt) von `target` abhängt
+    pub fn depends_on(&self, node: usize, target: usize) -> bool {
+        let mut stack = vec![node];
+        let mut visited = vec![false; self.nodes.len()];
+        while let Some(n) = stack.pop() {
+            if n == target {
+                return true;
+            }
+            if !std::mem::replace(&mut visited[n], true) {
+                stack.extend(self.nodes[n].inputs.iter().flatten().map(|src| src.node));
+            }
+        }
+        false
+    }
+
+    /// Verbindet den Ausgang `from` mit Eingang `pin` von `to`.
+    /// Rückkopplungen werden abgelehnt, da nur Schaltnetze simuliert werden.
+    pub fn connect(&mut self, from: Source, to: usize, pin: usize) -> bool {
+        if from.pin >= self.nodes[from.node].kind.output_count() || self.depends_on(from.node, to) {
+            return false;
+        }
+        self.nodes[to].inputs[pin] = Some(from);
+        self.nodes[to].routes[pin].clear();
+        true
+    }
+
+    /// Verbindet den Anschluss mit dem Ausgang `from` bitweise mit dem Anschluss, zu dem Eingang `pin` von `to` gehört.
+    /// Beide müssen gleich breit sein.
+    pub fn connect_port(&mut self, from: Source, to: usize, pin: usize) -> bool {
+        let outputs = self.nodes[from.node].kind.output_ports();
+        let inputs = self.nodes[to].kind.input_ports();
+        let ((out_port, out_first), (in_port, in_first)) = (locate(&outputs, from.pin), locate(&inputs, pin));
+        if out_port >= outputs.len() || in_port >= inputs.len() || outputs[out_port].width != inputs[in_port].width {
+            return false;
+        }
+        (0..inputs[in_port].width).all(|i| self.connect(Source { node: from.node, pin: out_first + i }, to, in_first + i))
+    }
+
+    /// Trennt alle Bits des Anschlusses, zu dem Eingang `pin` von `to` gehört. Gibt zurück, ob eines verbunden war.
+    pub fn disconnect_port(&mut self, to: usize, pin: usize) -> bool {
+        let inputs = self.nodes[to].kind.input_ports();
+        let (port, first) = locate(&inputs, pin);
+        let mut connected = false;
+        for input in &mut self.nodes[to].inputs[first..first + inputs[port].width] {
+            connected |= input.take().is_some();
+        }
+        connected
+    }
+
+    /// Ändert die Breite eines Ein- oder Ausgangs; Leitungen von wegfallenden Bits werden getrennt
+    pub fn set_width(&mut self, n: usize, new_width: usize) {
+        match &mut self.nodes[n].kind {
+            NodeKind::Input { value, .. } => value.resize(new_width, false),
+            NodeKind::Output { width, .. } => *width = new_width,
+            _ => return,
+        }
+        let count = self.nodes[n].kind.input_count();
+        self.nodes[n].inputs.resize(count, None);
+        self.nodes[n].routes.resize(count, Vec::new());
+        let outputs = self.nodes[n].kind.output_count();
+        for node in &mut self.nodes {
+            for input in &mut node.inputs {
+                if input.is_some_and(|src| src.node == n && src.pin >= outputs) {
+                    *input = None;
+                }
+            }
+        }
+    }
+
+    pub fn input_nodes(&self) -> Vec<usize> {
+        (0..self.nodes.len())
+            .filter(|&i| matches!(self.nodes[i].kind, NodeKind::Input { .. }))
+            .collect()
+    }
+
+    pub fn output_nodes(&self) -> Vec<usize> {
+        (0..self.nodes.len())
+            .filter(|&i| matches!(self.nodes[i].kind, NodeKind::Output { .. }))
+            .collect()
+    }
+
+    /// Die einzelnen Bits aller Eingänge in Knotenreihenfolge; sie sind die Variablen der Schaltung
+    pub fn input_bits(&self) -> Vec<Source> {
+        self.input_nodes()
+            .into_iter()
+            .flat_map(|node| (0..self.nodes[node].kind.output_count()).map(move |pin| Source { node, pin }))
+            .collect()
+    }
+
+    /// Die einzelnen Bits aller Ausgänge als (Knoten, Eingang)
+    pub fn output_bits(&self) -> Vec<(usize, usize)> {
+        self.output_nodes().into_iter().flat_map(|n| (0..self.nodes[n].inputs.len()).map(move |pin| (n, pin))).collect()
+    }
+
+    /// Namen der Eingangsbits, Busse z.B. als A3 bis A0
+    pub fn input_names(&self) -> Vec<String> {
+        self.input_nodes().into_iter().flat_map(|n| self.nodes[n].kind.output_ports()).flat_map(|port| port.bit_names()).collect()
+    }
+
+    pub fn output_names(&self) -> Vec<String> {
+        self.output_nodes().into_iter().flat_map(|n| self.nodes[n].kind.input_ports()).flat_map(|port| port.bit_names()).collect()
+    }
+
+    /// Aktuell eingestellte Pegel der Eingangsbits (in Knotenreihenfolge)
+    pub fn input_values(&self) -> Vec<bool> {
+        self.nodes
+            .iter()
+            .flat_map(|n| match &n.kind {
+                NodeKind::Input { value, .. } => value.clone(),
+                _ => Vec::new(),
+            })
+            .collect()
+    }
+
+    pub fn set_input_values(&mut self, values: &[bool]) {
+        let bits = self
+            .nodes
+            .iter_mut()
+            .filter_map(|n| match &mut n.kind {
+                NodeKind::Input { value, .. } => Some(value),
+                _ => None,
+            })
+            .flatten();
+        for (bit, new) in bits.zip(values) {
+            *bit = *new;
+        }
+    }
+
+    /// Knoten so sortiert, dass jeder Knoten nach all seinen Quellen kommt
+    pub fn topological_order(&self) -> Vec<usize> {
+        fn visit(circuit: &Circuit, n: usize, done: &mut Vec<bool>, order: &mut Vec<usize>) {
+            if std::mem::replace(&mut done[n], true) {
+                return;
+            }
+            for src in circuit.nodes[n].inputs.iter().flatten() {
+                visit(circuit, src.node, done, order);
+            }
+            order.push(n);
+        }
+        let mut done = vec![false; self.nodes.len()];
+        let mut order = Vec::with_capacity(self.nodes.len());
+        for n in 0..self.nodes.len() {
+            visit(self, n, &mut done, &mut order);
+        }
+        order
+    }
+
+    /// Erstes Eingangsbit jedes Knotens in der Reihenfolge von `input_values`
+    fn input_offsets(&self) -> Vec<usize> {
+        let mut next_input = 0;
+        self.nodes
+            .iter()
+            .map(|n| {
+                let idx = next_input;
+                if let NodeKind::Input { value, .. } = &n.kind {
+                    next_input += value.len();
+                }
+                idx
+            })
+            .collect()
+    }
+
+    /// Pegel an den Ausgängen jedes Knotens für die gegebene Eingangsbelegung; ein Ausgang der Schaltung
+    /// erhält den Pegel, den er anzeigt. Offene Gattereingänge gelten als Low.
+    pub fn node_values(&self, inputs: &[bool]) -> Vec<Vec<bool>> {
+        let mut values = vec![Vec::new(); self.nodes.len()];
+        let input_index = self.input_offsets();
+        for n in self.topological_order() {
+            values[n] = match &self.nodes[n].kind {
+                NodeKind::Input { value, .. } => inputs[input_index[n]..input_index[n] + value.len()].to_vec(),
+                _ => self.compute_node(n, &values),
+            };
+        }
+        values
+    }
+
+    /// Pegel an den Ausgängen des Knotens `n` aus den Pegeln seiner Quellen; ein Eingang behält seinen Wert
+    pub fn compute_node(&self, n: usize, values: &[Vec<bool>]) -> Vec<bool> {
+        let node = &self.nodes[n];
+        let pin = |i: usize| node.inputs[i].is_some_and(|src| values[src.node][src.pin]);
+        let pins = || (0..node.inputs.len()).map(pin).collect::<Vec<_>>();
+        match &node.kind {
+            NodeKind::Input { .. } => values[n].clone(),
+            NodeKind::Output { .. } => pins(),
+            NodeKind::Gate(gate) => vec![gate.compute(pin(0), node.inputs.len() > 1 && pin(1))],
+            NodeKind::Component(component) => component.compute(&pins()),
+            NodeKind::Tristate(tristate) => vec![tristate.compute_bit(&pins())],
+        }
+    }
+
+    /// Pegel der Ausgangsbits (in Knotenreihenfolge der Ausgänge)
+    pub fn evaluate(&self, inputs: &[bool]) -> Vec<bool> {
+        let values = self.node_values(inputs);
+        self.output_bits().into_iter().map(|(n, pin)| values[n][pin]).collect()
+    }
+
+    /// Wie `node_values`, aber vierwertig: offene Eingänge sind hochohmig (Z), und Tri-State-Treiber können
+    /// ihren Ausgang abschalten
+    pub fn node_levels(&self, inputs: &[Level]) -> Vec<Vec<Level>> {
+        let mut levels = vec![Vec::new(); self.nodes.len()];
+        let input_index = self.input_offsets();
+        for n in self.topological_order() {
+            levels[n] = match &self.nodes[n].kind {
+                NodeKind::Input { value, .. } => inputs[input_index[n]..input_index[n] + value.len()].to_vec(),
+                _ => self.compute_levels(n, &levels),
+            };
+        }
+        levels
+    }
+
+    /// Vierwertige Pegel an den Ausgängen des Knotens `n`
+    pub fn compute_levels(&self, n: usize, levels: &[Vec<Level>]) -> Vec<Level> {
+        let node = &self.nodes[n];
+        let pin = |i: usize| node.inputs[i].map_or(Level::Z, |src| levels[src.node][src.pin]);
+        let pins = || (0..node.inputs.len()).map(pin).collect::<Vec<_>>();
+        match &node.kind {
+            NodeKind::Input { .. } => levels[n].clone(),
+            NodeKind::Output { .. } => pins(),
+            NodeKind::Gate(gate) => vec![level::gate(*gate, pin(0), if node.inputs.len() > 1 { pin(1) } else { Level::Low })],
+            NodeKind::Component(component) => component.compute_levels(&pins()),
+            NodeKind::Tristate(tristate) => vec![tristate.compute(&pins())],
+        }
+    }
+
+    /// Vierwertige Pegel der Ausgangsbits
+    pub fn evaluate_levels(&self, inputs: &[Level]) -> Vec<Level> {
+        let levels = self.node_levels(inputs);
+        self.output_bits().into_iter().map(|(n, pin)| levels[n][pin]).collect()
+    }
+
+    /// Zeilen der Wahrheitstabelle, in denen Treiber auf einer Sammelleitung gegeneinander arbeiten. Der Editor
+    /// zeigt dort X, Wahrheitstabellen und Vergleiche rechnen zweiwertig mit `Tristate::compute_bit`.
+    /// Wertet jede Zeile aus, daher nur bis `EXHAUSTIVE_MAX_INPUTS` Eingänge; eine einzelne Belegung mit Konflikt
+    /// findet `equivalence::conflict` auch bei mehr Eingängen.
+    pub fn conflict_rows(&self) -> Vec<usize> {
+        let input_count = self.input_names().len();
+        if input_count > EXHAUSTIVE_MAX_INPUTS {
+            return Vec::new();
+        }
+        let circuit = self.flattened();
+        let buses: Vec<usize> =
+            (0..circuit.nodes.len()).filter(|&n| matches!(circuit.nodes[n].kind, NodeKind::Tristate(Tristate::Bus(_)))).collect();
+        if buses.is_empty() {
+            return Vec::new();
+        }
+        (0..1 << input_count)
+            .filter(|&row| {
+                let inputs: Vec<Level> = row_inputs(row, input_count).into_iter().map(Level::from).collect();
+                let levels = circuit.node_levels(&inputs);
+                buses.iter().any(|&n| {
+                    let drivers: Vec<Level> = circuit.nodes[n].inputs.iter().flatten().map(|src| levels[src.node][src.pin]).collect();
+                    drivers.contains(&Level::High) && drivers.contains(&Level::Low)
+                })
+            })
+            .collect()
+    }
+
+    /// Vierwertige Pegel aller Knoten mit den aktuell eingestellten Eingängen
+    pub fn current_levels(&self) -> Vec<Vec<Level>> {
+        self.node_levels(&self.input_values().into_iter().map(Level::from).collect::<Vec<_>>())
+    }
+
+    /// Ersetzt den Baustein `n` durch die Gatter seiner Innenschaltung und hängt die Leitungen um
+    pub fn expand(&mut self, n: usize) {
+        let NodeKind::Component(component) = &self.nodes[n].kind else { return };
+        let inner = component.circuit();
+        let (ins, outs) = (inner.input_bits(), inner.output_bits());
+        let center = inner.nodes.iter().fold(Rect::NOTHING, |r, node| r.union(Rect::from_center_size(node.pos, Vec2::ZERO))).center();
+        let offset = self.nodes[n].pos - center;
+        let mut map = vec![0; inner.nodes.len()];
+        for (i, node) in inner.nodes.iter().enumerate() {
+            if let NodeKind::Gate(_) | NodeKind::Component(_) | NodeKind::Tristate(_) = node.kind {
+                map[i] = self.add_node(node.kind.clone(), node.pos + offset);
+                self.nodes[map[i]].delay = node.delay;
+            }
+        }
+        // Quelle eines inneren Signals in der äußeren Schaltung; innere Eingänge führen zur Quelle am Bausteineingang
+        let outer = |src: Source| match inner.nodes[src.node].kind {
+            NodeKind::Input { .. } => self.nodes[n].inputs[ins.iter().position(|&i| i == src).unwrap()],
+            _ => Some(Source { node: map[src.node], ..src }),
+        };
+        let rewired: Vec<(usize, Vec<Option<Source>>)> = (0..inner.nodes.len())
+            .filter(|&i| matches!(inner.nodes[i].kind, NodeKind::Gate(_) | NodeKind::Component(_) | NodeKind::Tristate(_)))
+            .map(|i| (map[i], inner.nodes[i].inputs.iter().map(|src| src.and_then(outer)).collect()))
+            .collect();
+        let results: Vec<Option<Source>> = outs.iter().map(|&(o, pin)| inner.nodes[o].inputs[pin].and_then(outer)).collect();
+        for (g, inputs) in rewired {
+            self.nodes[g].inputs = inputs;
+        }
+        for node in &mut self.nodes {
+            for (input, route) in node.inputs.iter_mut().zip(&mut node.routes) {
+                if let Some(src) = *input {
+                    if src.node == n {
+                        *input = results[src.pin];
+                        route.clear();
+                    }
+                }
+            }
+        }
+        self.remove_node(n);
+    }
+
+    /// Kopie, in der alle Bausteine durch Gatter ersetzt sind
+    pub fn flattened(&self) -> Circuit {
+        let mut circuit = self.clone();
+        while let Some(n) = circuit.nodes.iter().position(|node| matches!(node.kind, NodeKind::Component(_))) {
+            circuit.expand(n);
+        }
+        circuit
+    }
+
+    /// Kopie der Knoten `nodes` in dieser Reihenfolge; Leitungen von Knoten außerhalb fallen weg
+    pub fn subset(&self, nodes: &[usize]) -> Circuit {
+        let mut circuit = Circuit { nodes: nodes.iter().map(|&n| self.nodes[n].clone()).collect() };
+        for node in &mut circuit.nodes {
+            for (input, route) in node.inputs.iter_mut().zip(&mut node.routes) {
+                *input = input.and_then(|src| nodes.iter().position(|&n| n == src.node).map(|node| Source { node, ..src }));
+                if input.is_none() {
+                    route.clear();
+                }
+            }
+        }
+        circuit
+    }
+
+    /// Hängt die Knoten von `other` um `offset` verschoben an und gibt ihre neuen Nummern zurück
+    pub fn insert(&mut self, other: &Circuit, offset: Vec2) -> Vec<usize> {
+        let first = self.nodes.len();
+        for node in &other.nodes {
+            let mut node = node.clone();
+            node.pos += offset;
+            for input in node.inputs.iter_mut().flatten() {
+                input.node += first;
+            }
+            for point in node.routes.iter_mut().flatten() {
+                *point += offset;
+            }
+            self.nodes.push(node);
+        }
+        (first..self.nodes.len()).collect()
+    }
+
+    /// Prüft, ob Breiten und Bausteingrößen zulässig sind, Anschlüsse und Leitungen zusammenpassen und es keine
+    /// Rückkopplung gibt, z.B. nach dem Einlesen einer Schaltung aus der Zwischenablage
+    pub fn is_valid(&self) -> bool {
+        let wired = self.nodes.iter().all(|node| {
+            let kind = match &node.kind {
+                // Geräte mit fester Breite verlassen sich darauf, z.B. der Taster auf genau ein Bit
+                NodeKind::Input { value, device, .. } => {
+                    (1..=MAX_WIDTH).contains(&value.len()) && (node.kind.resizable() || value.len() == device.default_width())
+                }
+                NodeKind::Output { width, device, .. } => {
+                    (1..=MAX_WIDTH).contains(width) && (node.kind.resizable() || *width == device.default_width())
+                }
+                NodeKind::Gate(_) | NodeKind::Tristate(Tristate::Buffer | Tristate::Inverter) => true,
+                NodeKind::Tristate(Tristate::Bus(n)) => (2..=4).contains(n),
+                NodeKind::Component(component) => component.is_valid(),
+            };
+            kind
+                && node.delay <= MAX_DELAY
+                && node.inputs.len() == node.kind.input_count()
+                && node.routes.len() == node.inputs.len()
+                && node.inputs.iter().flatten().all(|src| src.node < self.nodes.len() && src.pin < self.nodes[src.node].kind.output_count())
+        });
+        if !wired {
+            return false;
+        }
+        let mut rank = vec![0; self.nodes.len()];
+        for (i, n) in self.topological_order().into_iter().enumerate() {
+            rank[n] = i;
+        }
+        self.nodes.iter().enumerate().all(|(n, node)| node.inputs.iter().flatten().all(|src| rank[src.node] < rank[n]))
+    }
+
+    /// Ein einzelnes Gatter mit den Eingängen A (und B) und dem Ausgang Y
+    pub fn from_gate(gate: LogicGates) -> Self {
+        let mut circuit = Circuit::default();
+        let inputs: Vec<usize> = ["A", "B"][..gate.input_count()]
+            .iter()
+            .map(|name| circuit.add_node(NodeKind::input(*name), Pos2::ZERO))
+            .collect();
+        let g = circuit.add_node(NodeKind::Gate(gate), Pos2::ZERO);
+        circuit.nodes[g].inputs = inputs.into_iter().map(|i| Some(i.into())).collect();
+        let y = circuit.add_node(NodeKind::output("Y"), Pos2::ZERO);
+        circuit.nodes[y].inputs[0] = Some(g.into());
+        layout::arrange(&mut circuit);
+        circuit
+    }
+
+    /// Baut je Ausdruck einen Ausgang aus Gattern mit höchstens zwei Eingängen auf.
+    /// Konstanten gibt es nicht als Bauteil, sie führen daher zu einem Fehler.
+    pub fn from_exprs(vars: &[String], outputs: &[(String, Expr)]) -> Result<Self, String> {
+        fn build(circuit: &mut Circuit, expr: &Expr) -> Result<usize, String> {
+            let gate = |circuit: &mut Circuit, gate: LogicGates, inputs: Vec<usize>| {
+                let g = circuit.add_node(NodeKind::Gate(gate), Pos2::ZERO);
+                circuit.nodes[g].inputs = inputs.into_iter().map(|i| Some(i.into())).collect();
+                g
+            };
+            Ok(match expr {
+                Expr::Const(_) => return Err("Konstanten (0 und 1) lassen sich nicht als Gatter darstellen.".to_string()),
+                // Die Eingänge wurden als erste Knoten angelegt
+                Expr::Var(i) => *i,
+                Expr::Not(e) => {
+                    let e = build(circuit, e)?;
+                    gate(circuit, LogicGates::NOT, vec![e])
+                }
+                Expr::And(es) | Expr::Or(es) => {
+                    let op = if matches!(expr, Expr::And(_)) { LogicGates::AND } else { LogicGates::OR };
+                    let mut acc = build(circuit, &es[0])?;
+                    for e in &es[1..] {
+                        let e = build(circuit, e)?;
+                        acc = gate(circuit, op, vec![acc, e]);
+                    }
+                    acc
+                }
+                Expr::Xor(a, b) => {
+                    let (a, b) = (build(circuit, a)?, build(circuit, b)?);
+                    gate(circuit, LogicGates::XOR, vec![a, b])
+                }
+            })
+        }
+        let mut circuit = Circuit::default();
+        for name in vars {
+            circuit.add_node(NodeKind::input(name.clone()), Pos2::ZERO);
+        }
+        for (name, expr) in outputs {
+            let src = build(&mut circuit, expr)?;
+            let y = circuit.add_node(NodeKind::output(name.clone()), Pos2::ZERO);
+            circuit.nodes[y].inputs[0] = Some(src.into());
+        }
+        layout::arrange(&mut circuit);
+        Ok(circuit)
+    }
+}
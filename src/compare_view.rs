@@ -0,0 +1,195 @@
+// Fenster, das zwei Schaltungen nebeneinander zeigt, z.B. vor und nach einer Umformung
+// oder die eigene Lösung neben einer aus der Wahrheitstabelle erzeugten Schaltung
+
+use egui::{Color32, RichText, Ui};
+
+use crate::circuit::Circuit;
+use crate::editor::show_schematic;
+use crate::equivalence::{self, EXHAUSTIVE_MAX_INPUTS};
+use crate::synthesis::Form;
+use crate::truth_table::{TruthTable, Value};
+use crate::universal::{self, gate_summary};
+use crate::LogicGates;
+
+/// Funktion, die beide Schaltungen erfüllen sollen
+enum Reference {
+    /// X-Zeilen werden nicht geprüft
+    Table(TruthTable),
+    /// Schaltung vor einer Umformung; verglichen wird wie in den Übungen, bei vielen Eingängen symbolisch
+    Circuit(Circuit),
+}
+
+struct Side {
+    title: String,
+    circuit: Circuit,
+    /// Ergebnis der Prüfung gegen die Referenz, einmal beim Anlegen berechnet
+    check: Option<Result<(), String>>,
+}
+
+impl Side {
+    fn new(title: &str, circuit: Circuit, reference: Option<&Reference>) -> Self {
+        let check = reference.map(|reference| check(&circuit, reference));
+        Self { title: title.to_string(), circuit, check }
+    }
+}
+
+pub struct Comparison {
+    title: String,
+    reference: Option<Reference>,
+    left: Option<Side>,
+    right: Result<Side, String>,
+    /// Hinweis zur rechten Schaltung, z.B. auf weggelassene Ausgänge
+    note: Option<String>,
+}
+
+/// Zeile der Wahrheitstabelle oder, bei sehr vielen Eingängen, die Belegung selbst
+fn describe_inputs(inputs: &[bool]) -> String {
+    if inputs.len() <= EXHAUSTIVE_MAX_INPUTS {
+        format!("In Zeile {}", equivalence::row_index(inputs))
+    } else {
+        format!("Bei der Belegung {}", inputs.iter().map(|&b| if b { '1' } else { '0' }).collect::<String>())
+    }
+}
+
+/// Prüft die Schaltung gegen die Referenz und beschreibt das Ergebnis in einem Satz. Zuerst werden die Namen
+/// verglichen, damit eine fremde Schaltung mit vielen Eingängen gar nicht erst ausgewertet wird.
+fn check(circuit: &Circuit, reference: &Reference) -> Result<(), String> {
+    let (inputs, outputs) = match reference {
+        Reference::Table(table) => (table.inputs.clone(), table.outputs.iter().map(|o| o.name.clone()).collect()),
+        Reference::Circuit(before) => (before.input_names(), before.output_names()),
+    };
+    if circuit.input_names() != inputs || circuit.output_names() != outputs {
+        return Err("Ein- und Ausgänge passen nicht zur Wahrheitstabelle.".to_string());
+    }
+    let table = match reference {
+        Reference::Table(table) => table,
+        Reference::Circuit(before) => {
+            let verdict = equivalence::check_circuit(circuit, before);
+            return match (verdict.counterexample, verdict.conflict) {
+                (Some(c), _) => {
+                    let output = (0..outputs.len()).find(|&o| c.expected[o] != c.actual[o]).unwrap();
+                    Err(format!(
+                        "{} ist {} = {} statt {}.",
+                        describe_inputs(&c.inputs),
+                        outputs[output],
+                        c.actual[output] as u8,
+                        c.expected[output] as u8
+                    ))
+                }
+                (None, Some(inputs)) => {
+                    Err(format!("{} arbeiten Treiber auf einer Sammelleitung gegeneinander (X).", describe_inputs(&inputs)))
+                }
+                (None, None) => Ok(()),
+            };
+        }
+    };
+    // Die Tabelle ist klein, denn sie wurde selbst vollständig aufgestellt
+    for row in 0..table.row_count() {
+        let actual = circuit.evaluate(&equivalence::row_inputs(row, inputs.len()));
+        for (expected, actual) in table.outputs.iter().zip(actual) {
+            if expected.values[row] != Value::DontCare && expected.values[row] != Value::from(actual) {
+                return Err(format!(
+                    "In Zeile {} ist {} = {} statt {}.",
+                    row,
+                    expected.name,
+                    Value::from(actual).symbol(),
+                    expected.values[row].symbol()
+                ));
+            }
+        }
+    }
+    if let Some(inputs) = equivalence::conflict(circuit) {
+        return Err(format!("{} arbeiten Treiber auf einer Sammelleitung gegeneinander (X).", describe_inputs(&inputs)));
+    }
+    Ok(())
+}
+
+impl Comparison {
+    /// Umformung in reine NAND- bzw. NOR-Logik
+    pub fn universal(before: Circuit, target: LogicGates) -> Self {
+        let after = universal::convert(&before, target);
+        let reference = Reference::Circuit(before.clone());
+        Self {
+            title: format!("Umformung in {}-Gatter", target),
+            left: Some(Side::new("Vorher", before, Some(&reference))),
+            right: after.map(|after| Side::new(&format!("Nur {}", target), after, Some(&reference))),
+            reference: Some(reference),
+            note: None,
+        }
+    }
+
+    /// Aus einer Wahrheitstabelle erzeugte zweistufige Schaltung
+    pub fn synthesized(mut reference: TruthTable, form: Form, result: Result<Circuit, String>) -> Self {
+        // Konstante Ausgänge brauchen keine Gatter und fehlen daher in der erzeugten Schaltung
+        let constant: Vec<String> =
+            reference.outputs.iter().filter_map(|o| o.constant().map(|c| format!("{} = {}", o.name, c as u8))).collect();
+        reference.outputs.retain(|o| o.constant().is_none());
+        let note = (!constant.is_empty()).then(|| format!("Ohne Gatter, da konstant: {}", constant.join(", ")));
+        let reference = Reference::Table(reference);
+        Self {
+            title: format!("Erzeugte Schaltung: {}", form.name()),
+            left: None,
+            right: result.map(|circuit| Side::new("Erzeugt", circuit, Some(&reference))),
+            reference: Some(reference),
+            note,
+        }
+    }
+
+    /// Stellt eine weitere Schaltung daneben, z.B. die eigene Lösung aus dem Editor
+    pub fn set_left(&mut self, title: &str, circuit: Circuit) {
+        self.left = Some(Side::new(title, circuit, self.reference.as_ref()));
+    }
+
+    fn show_side(&self, ui: &mut Ui, side: &Side, note: Option<&str>) {
+        ui.label(RichText::new(&side.title).color(ui.visuals().strong_text_color()));
+        ui.label(gate_summary(&side.circuit));
+        match &side.check {
+            Some(Ok(())) => {
+                ui.label(RichText::new("✔ Erfüllt die Wahrheitstabelle").color(Color32::GREEN));
+            }
+            Some(Err(e)) => {
+                ui.label(RichText::new(format!("✖ {}", e)).color(Color32::RED));
+            }
+            None => {}
+        }
+        if let Some(note) = note {
+            ui.label(note);
+        }
+        egui::ScrollArea::both().id_source(&side.title).show(ui, |ui| show_schematic(ui, &side.circuit));
+    }
+
+    fn show_right(&self, ui: &mut Ui) {
+        match &self.right {
+            Ok(side) => self.show_side(ui, side, self.note.as_deref()),
+            Err(e) => {
+                ui.label(RichText::new(e).color(Color32::RED));
+            }
+        }
+    }
+
+    /// Zeigt die Schaltungen nebeneinander. Gibt die rechte Schaltung zurück,
+    /// wenn sie in den Schaltungseditor übernommen werden soll.
+    pub fn show(&self, ctx: &egui::Context, open: &mut bool) -> Option<Circuit> {
+        let mut apply = None;
+        egui::Window::new(&self.title)
+            .open(open)
+            .default_size(egui::vec2(800.0, 420.0))
+            .show(ctx, |ui| {
+                if let Ok(Side { circuit, .. }) = &self.right {
+                    if ui.button("Im Schaltungseditor öffnen").on_hover_text("Ersetzt die Schaltung im Editor").clicked() {
+                        apply = Some(circuit.clone());
+                    }
+                }
+                match &self.left {
+                    Some(side) => {
+                        ui.columns(2, |columns| {
+                            self.show_side(&mut columns[0], side, None);
+                            self.show_right(&mut columns[1]);
+                        });
+                    }
+                    None => self.show_right(ui),
+                }
+            });
+        apply
+    }
+}
@@ -0,0 +1,353 @@
+// Bausteinbibliothek: Addierer, Multiplexer, Decoder, Encoder, Komparatoren und Busverteiler als Blackbox mit
+// hinterlegter Innenschaltung aus Gattern, dazu selbst erstellte Teilschaltungen. Mehrbitige Anschlüsse
+// sind Busse, wie bei den Tabellenzeilen kommt das höchstwertige Bit zuerst.
+
+use std::rc::Rc;
+
+use egui::Pos2;
+use serde::{Deserialize, Serialize};
+
+use crate::bus::{bits, Port};
+use crate::circuit::{Circuit, NodeKind, Source};
+use crate::equivalence::{row_index, row_inputs};
+use crate::level::Level;
+use crate::subcircuit::Subcircuit;
+use crate::{layout, LogicGates};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Component {
+    HalfAdder,
+    FullAdder,
+    /// n-Bit-Addierer aus hintereinandergeschalteten Volladdierern
+    RippleAdder(usize),
+    /// 2^n:1-Multiplexer mit n Steuereingängen
+    Mux(usize),
+    /// 1:2^n-Demultiplexer mit n Steuereingängen
+    Demux(usize),
+    /// n:2^n-Decoder: genau der Ausgang mit der anliegenden Nummer ist High
+    Decoder(usize),
+    /// 2^n:n-Prioritätsencoder: Nummer des höchsten aktiven Eingangs, V zeigt an, ob überhaupt einer aktiv ist
+    PriorityEncoder(usize),
+    /// Vergleicht zwei n-Bit-Zahlen
+    Comparator(usize),
+    /// Teilt einen n-Bit-Bus in einzelne Leitungen auf
+    Split(usize),
+    /// Fasst n einzelne Leitungen zu einem Bus zusammen
+    Join(usize),
+    /// Aus einem Teil einer Schaltung erstellt
+    Custom(Rc<Subcircuit>),
+}
+
+/// Einzelne Anschlüsse `prefix0` bis `prefix{count-1}`
+fn numbered(prefix: &str, count: usize) -> Vec<Port> {
+    (0..count).map(|i| Port::new(format!("{}{}", prefix, i), 1)).collect()
+}
+
+/// Einzelne Anschlüsse für die Bits eines Busses, `prefix{n-1}` bis `prefix0`
+fn bit_ports(prefix: &str, n: usize) -> Vec<Port> {
+    bits(prefix, n).into_iter().map(|name| Port::new(name, 1)).collect()
+}
+
+fn single(name: &str) -> Port {
+    Port::new(name, 1)
+}
+
+impl Component {
+    /// Bausteine für das Menü im Schaltungseditor, nach Art gruppiert
+    pub fn library() -> Vec<(&'static str, Vec<Component>)> {
+        use Component::*;
+        vec![
+            ("Addierer", vec![HalfAdder, FullAdder, RippleAdder(2), RippleAdder(4), RippleAdder(8)]),
+            ("Multiplexer", vec![Mux(1), Mux(2), Mux(3)]),
+            ("Demultiplexer", vec![Demux(1), Demux(2), Demux(3)]),
+            ("Decoder", vec![Decoder(1), Decoder(2), Decoder(3), Decoder(4)]),
+            ("Prioritätsencoder", vec![PriorityEncoder(1), PriorityEncoder(2), PriorityEncoder(3)]),
+            ("Komparator", vec![Comparator(1), Comparator(2), Comparator(4)]),
+            ("Busse", vec![Split(2), Split(4), Split(8), Join(2), Join(4), Join(8)]),
+        ]
+    }
+
+    /// Ob die Größe im Bereich der Bibliothek liegt; eingefügte Bausteine könnten sonst z.B. mit `Decoder(64)`
+    /// Anschlüsse und Tabellen ohne Grenze anlegen
+    pub fn is_valid(&self) -> bool {
+        match *self {
+            Component::Custom(ref subcircuit) => subcircuit.circuit.is_valid(),
+            Component::HalfAdder | Component::FullAdder => true,
+            Component::RippleAdder(n) | Component::Split(n) | Component::Join(n) => (2..=8).contains(&n),
+            Component::Mux(n) | Component::Demux(n) | Component::PriorityEncoder(n) => (1..=3).contains(&n),
+            Component::Decoder(n) | Component::Comparator(n) => (1..=4).contains(&n),
+        }
+    }
+
+    pub fn name(&self) -> String {
+        match *self {
+            Component::Custom(ref subcircuit) => subcircuit.name.clone(),
+            Component::HalfAdder => "Halbaddierer".to_string(),
+            Component::FullAdder => "Volladdierer".to_string(),
+            Component::RippleAdder(n) => format!("{}-Bit-Addierer", n),
+            Component::Mux(n) => format!("{}:1-Multiplexer", 1 << n),
+            Component::Demux(n) => format!("1:{}-Demultiplexer", 1 << n),
+            Component::Decoder(n) => format!("{}:{}-Decoder", n, 1 << n),
+            Component::PriorityEncoder(n) => format!("{}:{}-Prioritätsencoder", 1 << n, n),
+            Component::Comparator(n) => format!("{}-Bit-Komparator", n),
+            Component::Split(n) => format!("{}-Bit-Bus aufteilen", n),
+            Component::Join(n) => format!("{}-Bit-Bus bündeln", n),
+        }
+    }
+
+    /// Kurzzeichen im Schaltsymbol
+    pub fn symbol(&self) -> &str {
+        match self {
+            Component::Custom(subcircuit) => &subcircuit.symbol,
+            Component::HalfAdder => "HA",
+            Component::FullAdder => "VA",
+            Component::RippleAdder(_) => "Σ",
+            Component::Mux(_) => "MUX",
+            Component::Demux(_) => "DEMUX",
+            Component::Decoder(_) => "DEC",
+            Component::PriorityEncoder(_) => "PRI",
+            Component::Comparator(_) => "COMP",
+            Component::Split(_) => "SPLIT",
+            Component::Join(_) => "JOIN",
+        }
+    }
+
+    pub fn input_ports(&self) -> Vec<Port> {
+        match *self {
+            Component::Custom(ref subcircuit) => subcircuit.input_ports(),
+            Component::HalfAdder => vec![single("A"), single("B")],
+            Component::FullAdder => vec![single("A"), single("B"), single("Cin")],
+            Component::RippleAdder(n) => vec![Port::new("A", n), Port::new("B", n), single("Cin")],
+            Component::Mux(n) => [numbered("D", 1 << n), vec![Port::new("S", n)]].concat(),
+            Component::Demux(n) => vec![single("D"), Port::new("S", n)],
+            Component::Decoder(n) => vec![Port::new("A", n)],
+            Component::PriorityEncoder(n) => numbered("D", 1 << n),
+            Component::Comparator(n) => vec![Port::new("A", n), Port::new("B", n)],
+            Component::Split(n) => vec![Port::new("D", n)],
+            Component::Join(n) => bit_ports("D", n),
+        }
+    }
+
+    pub fn output_ports(&self) -> Vec<Port> {
+        match *self {
+            Component::Custom(ref subcircuit) => subcircuit.output_ports(),
+            Component::HalfAdder => vec![single("S"), single("C")],
+            Component::FullAdder => vec![single("S"), single("Cout")],
+            Component::RippleAdder(n) => vec![Port::new("S", n), single("Cout")],
+            Component::Mux(_) => vec![single("Y")],
+            Component::Demux(n) | Component::Decoder(n) => numbered("Y", 1 << n),
+            Component::PriorityEncoder(n) => vec![Port::new("Y", n), single("V")],
+            Component::Comparator(_) => vec![single("A>B"), single("A=B"), single("A<B")],
+            Component::Split(n) => bit_ports("D", n),
+            Component::Join(n) => vec![Port::new("D", n)],
+        }
+    }
+
+    /// Ausgangspegel für die gegebenen Eingangspegel
+    pub fn compute(&self, inputs: &[bool]) -> Vec<bool> {
+        match *self {
+            Component::Custom(ref subcircuit) => subcircuit.circuit.evaluate(inputs),
+            Component::HalfAdder => vec![inputs[0] ^ inputs[1], inputs[0] && inputs[1]],
+            Component::FullAdder => {
+                let ones = inputs.iter().filter(|&&b| b).count();
+                vec![ones % 2 == 1, ones >= 2]
+            }
+            Component::RippleAdder(n) => {
+                let sum = row_index(&inputs[..n]) + row_index(&inputs[n..2 * n]) + inputs[2 * n] as usize;
+                row_inputs(sum, n + 1).into_iter().skip(1).chain([sum >> n == 1]).collect()
+            }
+            Component::Mux(n) => vec![inputs[row_index(&inputs[1 << n..])]],
+            Component::Demux(n) => {
+                let selected = row_index(&inputs[1..]);
+                (0..1 << n).map(|i| inputs[0] && i == selected).collect()
+            }
+            Component::Decoder(n) => {
+                let selected = row_index(inputs);
+                (0..1 << n).map(|i| i == selected).collect()
+            }
+            Component::PriorityEncoder(n) => match inputs.iter().rposition(|&b| b) {
+                Some(i) => row_inputs(i, n).into_iter().chain([true]).collect(),
+                None => vec![false; n + 1],
+            },
+            Component::Comparator(n) => {
+                let (a, b) = (row_index(&inputs[..n]), row_index(&inputs[n..]));
+                vec![a > b, a == b, a < b]
+            }
+            Component::Split(_) | Component::Join(_) => inputs.to_vec(),
+        }
+    }
+
+    /// Vierwertige Ausgangspegel: Busverteiler und Teilschaltungen reichen Z und X weiter, bei den übrigen Bausteinen
+    /// sind alle Ausgänge unbestimmt, sobald ein Eingang nicht eindeutig 0 oder 1 ist
+    pub fn compute_levels(&self, inputs: &[Level]) -> Vec<Level> {
+        match self {
+            Component::Custom(subcircuit) => subcircuit.circuit.evaluate_levels(inputs),
+            Component::Split(_) | Component::Join(_) => inputs.to_vec(),
+            _ => match inputs.iter().map(|level| level.bit()).collect::<Option<Vec<bool>>>() {
+                Some(bits) => self.compute(&bits).into_iter().map(Level::from).collect(),
+                None => vec![Level::X; self.output_ports().iter().map(|port| port.width).sum()],
+            },
+        }
+    }
+
+    /// Innenschaltung; Ein- und Ausgänge heißen wie die Anschlüsse. Die Bausteine der Bibliothek
+    /// bestehen aus Gattern mit höchstens zwei Eingängen, Teilschaltungen können weitere Bausteine enthalten.
+    pub fn circuit(&self) -> Circuit {
+        if let Component::Custom(subcircuit) = self {
+            return subcircuit.circuit.clone();
+        }
+        let mut b = Builder::default();
+        let inputs: Vec<Source> = self.input_ports().into_iter().flat_map(|port| b.input(port)).collect();
+        let outputs = match *self {
+            Component::Custom(_) => unreachable!(),
+            Component::HalfAdder => {
+                let (s, c) = b.half_adder(inputs[0], inputs[1]);
+                vec![s, c]
+            }
+            Component::FullAdder => {
+                let (s, c) = b.full_adder(inputs[0], inputs[1], inputs[2]);
+                vec![s, c]
+            }
+            Component::RippleAdder(n) => {
+                // Vom niederwertigsten Bit an, der Übertrag wandert nach oben
+                let mut carry = inputs[2 * n];
+                let mut sums = Vec::new();
+                for i in (0..n).rev() {
+                    let (s, c) = b.full_adder(inputs[i], inputs[n + i], carry);
+                    sums.insert(0, s);
+                    carry = c;
+                }
+                [sums, vec![carry]].concat()
+            }
+            Component::Mux(n) => {
+                let (data, select) = inputs.split_at(1 << n);
+                let lines = b.decode(select);
+                let terms: Vec<Source> = data.iter().zip(lines).map(|(&d, line)| b.chain(LogicGates::AND, &[vec![d], line].concat())).collect();
+                vec![b.chain(LogicGates::OR, &terms)]
+            }
+            Component::Demux(_) => {
+                let lines = b.decode(&inputs[1..]);
+                lines.into_iter().map(|line| b.chain(LogicGates::AND, &[vec![inputs[0]], line].concat())).collect()
+            }
+            Component::Decoder(_) => {
+                let lines = b.decode(&inputs);
+                lines.into_iter().map(|line| b.chain(LogicGates::AND, &line)).collect()
+            }
+            Component::PriorityEncoder(n) => {
+                // Eingang i zählt nur, wenn kein höherer Eingang aktiv ist
+                let count = 1 << n;
+                let mut active = vec![inputs[count - 1]; count];
+                let mut any_above = inputs[count - 1];
+                for i in (0..count - 1).rev() {
+                    let none_above = b.gate(LogicGates::NOT, &[any_above]);
+                    active[i] = b.gate(LogicGates::AND, &[inputs[i], none_above]);
+                    any_above = b.gate(LogicGates::OR, &[any_above, inputs[i]]);
+                }
+                let mut outputs: Vec<Source> = (0..n)
+                    .map(|bit| {
+                        let set: Vec<Source> = (0..count).filter(|i| i >> (n - 1 - bit) & 1 == 1).map(|i| active[i]).collect();
+                        b.chain(LogicGates::OR, &set)
+                    })
+                    .collect();
+                outputs.push(any_above);
+                outputs
+            }
+            Component::Comparator(n) => {
+                // Vom höchstwertigen Bit an: das erste Bit, in dem sich A und B unterscheiden, entscheidet
+                let (mut greater, mut less, mut equal) = (Vec::new(), Vec::new(), Vec::new());
+                for i in 0..n {
+                    let (a, bi) = (inputs[i], inputs[n + i]);
+                    let (na, nb) = (b.gate(LogicGates::NOT, &[a]), b.gate(LogicGates::NOT, &[bi]));
+                    greater.push(b.chain(LogicGates::AND, &[equal.clone(), vec![a, nb]].concat()));
+                    less.push(b.chain(LogicGates::AND, &[equal.clone(), vec![na, bi]].concat()));
+                    equal.push(b.gate(LogicGates::XNOR, &[a, bi]));
+                }
+                vec![b.chain(LogicGates::OR, &greater), b.chain(LogicGates::AND, &equal), b.chain(LogicGates::OR, &less)]
+            }
+            // Reine Verdrahtung ohne Gatter
+            Component::Split(_) | Component::Join(_) => inputs,
+        };
+        let mut outputs = outputs.into_iter();
+        for port in self.output_ports() {
+            let sources: Vec<Source> = outputs.by_ref().take(port.width).collect();
+            b.output(port, &sources);
+        }
+        layout::arrange(&mut b.circuit);
+        b.circuit
+    }
+}
+
+#[derive(Default)]
+struct Builder {
+    circuit: Circuit,
+}
+
+impl Builder {
+    /// Eingang für einen Anschluss; gibt die Quellen seiner Bits zurück
+    fn input(&mut self, port: Port) -> Vec<Source> {
+        let node = self.circuit.add_node(NodeKind::bus_input(port.name, port.width), Pos2::ZERO);
+        (0..port.width).map(|pin| Source { node, pin }).collect()
+    }
+
+    fn output(&mut self, port: Port, sources: &[Source]) {
+        let y = self.circuit.add_node(NodeKind::bus_output(port.name, port.width), Pos2::ZERO);
+        self.circuit.nodes[y].inputs = sources.iter().copied().map(Some).collect();
+    }
+
+    fn gate(&mut self, gate: LogicGates, inputs: &[Source]) -> Source {
+        let g = self.circuit.add_node(NodeKind::Gate(gate), Pos2::ZERO);
+        self.circuit.nodes[g].inputs = inputs.iter().copied().map(Some).collect();
+        g.into()
+    }
+
+    /// Kette aus Gattern mit zwei Eingängen; eine einzelne Quelle bleibt ohne Gatter
+    fn chain(&mut self, gate: LogicGates, sources: &[Source]) -> Source {
+        sources[1..].iter().fold(sources[0], |acc, &src| self.gate(gate, &[acc, src]))
+    }
+
+    fn half_adder(&mut self, a: Source, b: Source) -> (Source, Source) {
+        (self.gate(LogicGates::XOR, &[a, b]), self.gate(LogicGates::AND, &[a, b]))
+    }
+
+    /// Volladdierer aus zwei Halbaddierern und einem ODER für die Überträge
+    fn full_adder(&mut self, a: Source, b: Source, carry: Source) -> (Source, Source) {
+        let (s1, c1) = self.half_adder(a, b);
+        let (s, c2) = self.half_adder(s1, carry);
+        (s, self.gate(LogicGates::OR, &[c1, c2]))
+    }
+
+    /// Je Nummer 0 bis 2^n - 1 die Literale, die zusammen genau diese Nummer der Steuereingänge erkennen
+    fn decode(&mut self, select: &[Source]) -> Vec<Vec<Source>> {
+        let negated: Vec<Source> = select.iter().map(|&s| self.gate(LogicGates::NOT, &[s])).collect();
+        (0..1 << select.len())
+            .map(|i| {
+                row_inputs(i, select.len())
+                    .into_iter()
+                    .enumerate()
+                    .map(|(bit, v)| if v { select[bit] } else { negated[bit] })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Die Innenschaltung jedes Bausteins der Bibliothek rechnet wie `compute`; bei mehr als zwölf Eingängen
+    /// (Ripple-Carry-Addierer mit acht Bit) nur für eine Auswahl von Zeilen
+    #[test]
+    fn circuit_matches_compute() {
+        for component in Component::library().into_iter().flat_map(|(_, components)| components) {
+            let circuit = component.circuit();
+            let input_count: usize = component.input_ports().iter().map(|port| port.width).sum();
+            let rows = 1usize << input_count;
+            let step = if input_count > 12 { 97 } else { 1 };
+            for row in (0..rows).step_by(step) {
+                let inputs = row_inputs(row, input_count);
+                assert_eq!(circuit.evaluate(&inputs), component.compute(&inputs), "{}, Zeile {row}", component.name());
+            }
+        }
+    }
+}
@@ -0,0 +1,1243 @@
+// Schaltungseditor: Bauteile platzieren, verschieben, verdrahten und Eingänge umschalten
+
+use std::collections::BTreeMap;
+use std::rc::Rc;
+
+use egui::{Align2, Color32, Event, FontId, Key, Painter, Pos2, Rect, RichText, Sense, Shape, Stroke, Ui, Vec2, WidgetType};
+use serde::{Deserialize, Serialize};
+
+use crate::accessibility;
+use crate::bus::{self, locate, NumberFormat, Port, ALL_FORMATS};
+use crate::circuit::{Circuit, Node, NodeKind, Source};
+use crate::components::Component;
+use crate::history::History;
+use crate::layout;
+use crate::level::{Level, Tristate};
+use crate::peripherals::{self, InputDevice, LedColor, OutputDevice, ALL_LED_COLORS, SEGMENTS};
+use crate::subcircuit::{Extraction, Subcircuit};
+use crate::theme::{self, level_color};
+use crate::timing::{TimingView, MAX_DELAY};
+use crate::universal::gate_summary;
+use crate::{LogicGates, ALL_GATES};
+
+const GATE_SIZE: Vec2 = Vec2::new(40.0, 50.0);
+const INPUT_SIZE: Vec2 = Vec2::new(56.0, 26.0);
+const OUTPUT_RADIUS: f32 = 13.0;
+const BUBBLE_RADIUS: f32 = 4.0;
+const PIN_RADIUS: f32 = 7.0;
+const COMPONENT_WIDTH: f32 = 80.0;
+/// Abstand der Anschlüsse eines Bausteins; darüber steht das Kurzzeichen
+const COMPONENT_PIN_SPACING: f32 = 20.0;
+const COMPONENT_TITLE: f32 = 16.0;
+/// Busleitungen sind dicker und haben eine eigene Farbe, da sie keinen einzelnen Pegel haben
+const BUS_COLOR: Color32 = Color32::from_rgb(80, 140, 220);
+const BUS_STROKE: f32 = 4.0;
+/// Breite des Balkens einer Sammelleitung
+const BUS_BAR_WIDTH: f32 = 12.0;
+/// Kennung kopierter Schaltungen in der Zwischenablage
+const CLIPBOARD_FORMAT: &str = "logikgatter-schaltung";
+/// Eingefügte Bauteile liegen versetzt, damit sie sich vom Original abheben
+const PASTE_OFFSET: Vec2 = Vec2::new(30.0, 30.0);
+
+/// Inhalt der Zwischenablage beim Kopieren von Bauteilen, als JSON
+#[derive(Serialize, Deserialize)]
+struct Clipboard {
+    format: String,
+    circuit: Circuit,
+}
+
+/// Schaltzeichen nach DIN EN 60617 und ob der Ausgang negiert ist
+fn gate_symbol(gate: LogicGates) -> (&'static str, bool) {
+    match gate {
+        LogicGates::AND => ("&", false),
+        LogicGates::OR => ("≥1", false),
+        LogicGates::XOR => ("=1", false),
+        LogicGates::NOT => ("1", true),
+        LogicGates::NAND => ("&", true),
+        LogicGates::NOR => ("≥1", true),
+        LogicGates::XNOR => ("=1", true),
+    }
+}
+
+/// Breite eines Ein- oder Ausgangs mit Bus: Platz für Name und Wert in Binärdarstellung
+fn bus_label_size(name: &str, width: usize) -> Vec2 {
+    Vec2::new(((name.chars().count() + 3 + width) as f32 * 8.0 + 12.0).max(INPUT_SIZE.x), INPUT_SIZE.y)
+}
+
+pub fn node_rect(node: &Node) -> Rect {
+    match &node.kind {
+        NodeKind::Input { device: InputDevice::Button { .. }, .. } => Rect::from_center_size(node.pos, peripherals::BUTTON_SIZE),
+        NodeKind::Input { device: InputDevice::Dip, value, .. } => Rect::from_center_size(node.pos, peripherals::dip_size(value.len())),
+        NodeKind::Input { device: InputDevice::HexKeypad, .. } => Rect::from_center_size(node.pos, peripherals::KEYPAD_SIZE),
+        NodeKind::Input { name, value, .. } => Rect::from_center_size(node.pos, bus_label_size(name, value.len())),
+        NodeKind::Output { device: OutputDevice::SevenSegment(_), .. } => Rect::from_center_size(node.pos, peripherals::SEVEN_SEGMENT_SIZE),
+        NodeKind::Output { width: 1, .. } => Rect::from_center_size(node.pos, Vec2::splat(OUTPUT_RADIUS * 2.0)),
+        NodeKind::Output { name, width, .. } => Rect::from_center_size(node.pos, bus_label_size(name, *width)),
+        NodeKind::Gate(_) => Rect::from_center_size(node.pos, GATE_SIZE),
+        NodeKind::Component(component) => {
+            let pins = component.input_ports().len().max(component.output_ports().len());
+            let height = (pins + 1) as f32 * COMPONENT_PIN_SPACING + COMPONENT_TITLE;
+            Rect::from_center_size(node.pos, Vec2::new(COMPONENT_WIDTH, height))
+        }
+        NodeKind::Tristate(Tristate::Bus(n)) => Rect::from_center_size(node.pos, Vec2::new(BUS_BAR_WIDTH, (n + 1) as f32 * COMPONENT_PIN_SPACING)),
+        NodeKind::Tristate(_) => Rect::from_center_size(node.pos, GATE_SIZE),
+    }
+}
+
+/// Position des Anschlusses, zu dem Eingang `pin` gehört; alle Bits eines Busses liegen an derselben Stelle
+pub fn input_pin(node: &Node, pin: usize) -> Pos2 {
+    let rect = node_rect(node);
+    let ports = node.kind.input_ports();
+    let step = rect.height() / (ports.len() + 1) as f32;
+    Pos2::new(rect.left(), rect.top() + step * (locate(&ports, pin).0 + 1) as f32)
+}
+
+pub fn output_pin(node: &Node, pin: usize) -> Option<Pos2> {
+    let rect = node_rect(node);
+    match &node.kind {
+        _ if pin >= node.kind.output_count() => None,
+        NodeKind::Gate(gate) if gate_symbol(*gate).1 => Some(rect.right_center() + Vec2::new(BUBBLE_RADIUS * 2.0, 0.0)),
+        NodeKind::Tristate(Tristate::Inverter) => Some(rect.right_center() + Vec2::new(BUBBLE_RADIUS * 2.0, 0.0)),
+        NodeKind::Component(component) => {
+            let ports = component.output_ports();
+            let step = rect.height() / (ports.len() + 1) as f32;
+            Some(Pos2::new(rect.right(), rect.top() + step * (locate(&ports, pin).0 + 1) as f32))
+        }
+        NodeKind::Input { device: InputDevice::Dip, .. } => Some(Pos2::new(rect.right(), peripherals::dip_row_y(rect, pin))),
+        _ => Some(rect.right_center()),
+    }
+}
+
+/// Wert eines Busses im gewählten Format; ein einzelnes Bit als 0 oder 1
+fn bus_text(name: &str, bits: &[bool], format: NumberFormat) -> String {
+    let format = if bits.len() == 1 { NumberFormat::Binary } else { format };
+    format!("{} = {}", name, format.format(bits))
+}
+
+/// Wie `bus_text`; sobald ein Bit Z oder X ist, werden die Bits einzeln angezeigt
+fn level_text(name: &str, levels: &[Level], format: NumberFormat) -> String {
+    match levels.iter().map(|level| level.bit()).collect::<Option<Vec<bool>>>() {
+        Some(bits) => bus_text(name, &bits, format),
+        None => format!("{} = {}", name, levels.iter().map(|level| level.symbol()).collect::<String>()),
+    }
+}
+
+/// Der ganze Bus an einem Eingangsanschluss, wenn er vollständig von einem gleich breiten Ausgangsanschluss kommt
+fn bus_source(circuit: &Circuit, node: &Node, first: usize, width: usize) -> Option<Source> {
+    let start = node.inputs[first]?;
+    let ports = circuit.nodes[start.node].kind.output_ports();
+    let (port, port_first) = locate(&ports, start.pin);
+    let complete = port_first == start.pin
+        && ports[port].width == width
+        && (0..width).all(|i| node.inputs[first + i] == Some(Source { pin: start.pin + i, ..start }));
+    complete.then_some(start)
+}
+
+/// Eckpunkte einer Leitung: die gespeicherte Führung, solange sie noch zu den Anschlüssen passt, sonst ein Knick in der Mitte
+pub fn wire_points(from: Pos2, to: Pos2, route: &[Pos2]) -> Vec<Pos2> {
+    let fits = match (route.first(), route.last()) {
+        (Some(first), Some(last)) => {
+            (first.y - from.y).abs() < 0.5 && (last.y - to.y).abs() < 0.5 && route.iter().all(|p| p.x >= from.x && p.x <= to.x)
+        }
+        _ => false,
+    };
+    let mut points = vec![from];
+    if fits {
+        points.extend_from_slice(route);
+    } else {
+        let mid_x = (from.x + to.x) / 2.0;
+        points.extend([Pos2::new(mid_x, from.y), Pos2::new(mid_x, to.y)]);
+    }
+    points.push(to);
+    points
+}
+
+/// Verzweigungen der Leitungen einer Quelle: Punkte, von denen aus in mindestens drei Richtungen Leitung abgeht
+fn junctions(wires: &[Vec<Pos2>]) -> Vec<Pos2> {
+    let segments: Vec<(Pos2, Pos2)> = wires
+        .iter()
+        .flat_map(|w| w.windows(2).map(|s| (s[0], s[1])))
+        .filter(|(a, b)| a.distance(*b) > 0.5)
+        .collect();
+    let mut result: Vec<Pos2> = Vec::new();
+    for &p in segments.iter().flat_map(|(a, b)| [a, b]) {
+        let mut directions: Vec<Vec2> = Vec::new();
+        for &(a, b) in &segments {
+            let on_segment = (p - a).length() + (b - p).length() < (b - a).length() + 0.5;
+            for end in [a, b] {
+                let direction = (end - p).normalized();
+                if on_segment && p.distance(end) > 0.5 && !directions.iter().any(|d| (*d - direction).length() < 0.1) {
+                    directions.push(direction);
+                }
+            }
+        }
+        if directions.len() >= 3 && !result.iter().any(|q| q.distance(p) < 0.5) {
+            result.push(p);
+        }
+    }
+    result
+}
+
+/// Zeichnet die Schaltung mit den Pegeln der aktuell eingestellten Eingänge. Mit `delays` stehen unter Gattern und
+/// Bausteinen ihre Nummer und Laufzeit, wie sie das Zeitdiagramm verwendet.
+fn draw(ui: &Ui, painter: &Painter, canvas: Rect, circuit: &Circuit, highlighted: &[usize], selected: &[usize], delays: bool) {
+    let origin = canvas.min.to_vec2();
+    painter.rect_filled(canvas, 2.0, ui.visuals().extreme_bg_color);
+    let levels = circuit.current_levels();
+    let text_color = ui.visuals().text_color();
+    // Leitungen je Quelle; ein vollständig verbundener Bus wird als eine Leitung gezeichnet, die an seinem ersten Bit hängt
+    let mut wires: BTreeMap<Source, (usize, Vec<Vec<Pos2>>)> = BTreeMap::new();
+    for node in &circuit.nodes {
+        let mut first = 0;
+        for port in node.kind.input_ports() {
+            let bus = bus_source(circuit, node, first, port.width).filter(|_| port.width > 1);
+            let pins = if bus.is_some() { first..first + 1 } else { first..first + port.width };
+            for pin in pins {
+                if let Some(src) = node.inputs[pin] {
+                    let from = output_pin(&circuit.nodes[src.node], src.pin).unwrap();
+                    let route = node.routes.get(pin).map_or(&[][..], |r| &r[..]);
+                    let width = if bus.is_some() { port.width } else { 1 };
+                    wires.entry(src).or_insert((width, Vec::new())).1.push(wire_points(from, input_pin(node, pin), route));
+                }
+            }
+            first += port.width;
+        }
+    }
+    for (src, (width, wires)) in &wires {
+        let level = levels[src.node][src.pin];
+        let (color, stroke) = if *width > 1 { (BUS_COLOR, BUS_STROKE) } else { (level.color(), 2.0) };
+        let dashes = level.dashes().filter(|_| *width == 1 && theme::patterns());
+        for wire in wires {
+            let points: Vec<Pos2> = wire.iter().map(|&p| p + origin).collect();
+            match dashes {
+                Some((dash, gap)) => painter.extend(Shape::dashed_line(&points, Stroke::new(stroke, color), dash, gap)),
+                None => {
+                    painter.add(Shape::line(points, Stroke::new(stroke, color)));
+                }
+            }
+            if *width > 1 {
+                // Schrägstrich mit der Zahl der Bits kurz vor dem Ziel
+                let mark = *wire.last().unwrap() + origin - Vec2::new(14.0, 0.0);
+                painter.line_segment([mark + Vec2::new(-4.0, 6.0), mark + Vec2::new(4.0, -6.0)], Stroke::new(1.5, text_color));
+                painter.text(mark + Vec2::new(2.0, -8.0), Align2::CENTER_BOTTOM, width.to_string(), FontId::proportional(10.0), text_color);
+            }
+        }
+        for p in junctions(wires) {
+            painter.circle_filled(p + origin, stroke + 2.0, color);
+        }
+    }
+    for node in &circuit.nodes {
+        let mut pin = 0;
+        for port in node.kind.input_ports() {
+            painter.circle_stroke(input_pin(node, pin) + origin, 3.0, Stroke::new(1.0, text_color));
+            pin += port.width;
+        }
+    }
+    for (i, node) in circuit.nodes.iter().enumerate() {
+        let rect = node_rect(node).translate(origin);
+        let stroke = if highlighted.contains(&i) {
+            Stroke::new(3.0, Color32::YELLOW)
+        } else if selected.contains(&i) {
+            Stroke::new(2.5, Color32::LIGHT_BLUE)
+        } else {
+            Stroke::new(1.5, text_color)
+        };
+        match &node.kind {
+            NodeKind::Input { name, value, device: InputDevice::Button { momentary }, .. } => {
+                peripherals::paint_button(painter, rect, value[0], *momentary, stroke);
+                painter.text(rect.center_top() - Vec2::new(0.0, 2.0), Align2::CENTER_BOTTOM, name, FontId::proportional(14.0), text_color);
+            }
+            NodeKind::Input { name, value, device: InputDevice::Dip, .. } => {
+                peripherals::paint_dip(painter, rect, value, stroke);
+                painter.text(rect.center_top() - Vec2::new(0.0, 2.0), Align2::CENTER_BOTTOM, name, FontId::proportional(14.0), text_color);
+            }
+            NodeKind::Input { name, value, device: InputDevice::HexKeypad, .. } => {
+                peripherals::paint_keypad(painter, rect, value, ui.visuals().extreme_bg_color, stroke);
+                painter.text(rect.center_top() - Vec2::new(0.0, 2.0), Align2::CENTER_BOTTOM, name, FontId::proportional(14.0), text_color);
+            }
+            NodeKind::Input { name, value, format, .. } => {
+                let fill = if value.len() == 1 { level_color(value[0]).linear_multiply(0.4) } else { BUS_COLOR.linear_multiply(0.3) };
+                painter.rect(rect, 4.0, fill, stroke);
+                painter.text(rect.center(), Align2::CENTER_CENTER, bus_text(name, value, *format), FontId::proportional(14.0), text_color);
+            }
+            NodeKind::Output { name, device: OutputDevice::SevenSegment(color), .. } => {
+                let segments: Vec<bool> = levels[i].iter().map(|&level| level == Level::High).collect();
+                peripherals::paint_seven_segment(painter, rect, &segments, *color, ui.visuals().extreme_bg_color, stroke);
+                let font = FontId::proportional(10.0);
+                for (pin, segment) in SEGMENTS.iter().enumerate() {
+                    painter.text(input_pin(node, pin) + origin + Vec2::new(4.0, 0.0), Align2::LEFT_CENTER, segment, font.clone(), text_color);
+                }
+                painter.text(rect.center_bottom() + Vec2::new(0.0, 2.0), Align2::CENTER_TOP, name, FontId::proportional(14.0), text_color);
+            }
+            NodeKind::Output { name, width: 1, device, .. } => {
+                match device {
+                    OutputDevice::Led(color) => peripherals::paint_led(painter, rect.center(), *color, levels[i][0] == Level::High, stroke),
+                    _ => {
+                        painter.circle(rect.center(), OUTPUT_RADIUS, levels[i][0].color(), stroke);
+                        if theme::patterns() {
+                            painter.text(rect.center(), Align2::CENTER_CENTER, levels[i][0].symbol(), FontId::proportional(14.0), Color32::BLACK);
+                        }
+                    }
+                }
+                painter.text(rect.right_center() + Vec2::new(6.0, 0.0), Align2::LEFT_CENTER, name, FontId::proportional(14.0), text_color);
+            }
+            NodeKind::Output { name, format, .. } => {
+                painter.rect(rect, 4.0, ui.visuals().extreme_bg_color, stroke);
+                painter.text(rect.center(), Align2::CENTER_CENTER, level_text(name, &levels[i], *format), FontId::monospace(14.0), text_color);
+            }
+            NodeKind::Gate(gate) => {
+                let (symbol, inverted) = gate_symbol(*gate);
+                painter.rect(rect, 0.0, ui.visuals().extreme_bg_color, stroke);
+                painter.text(rect.center_top() + Vec2::new(0.0, 12.0), Align2::CENTER_CENTER, symbol, FontId::proportional(16.0), text_color);
+                if inverted {
+                    painter.circle_stroke(rect.right_center() + Vec2::new(BUBBLE_RADIUS, 0.0), BUBBLE_RADIUS, stroke);
+                }
+            }
+            NodeKind::Component(component) => {
+                painter.rect(rect, 0.0, ui.visuals().extreme_bg_color, stroke);
+                painter.text(rect.center_top() + Vec2::new(0.0, 10.0), Align2::CENTER_CENTER, component.symbol(), FontId::proportional(14.0), text_color);
+                let font = FontId::proportional(11.0);
+                let mut pin = 0;
+                for port in component.input_ports() {
+                    painter.text(input_pin(node, pin) + origin + Vec2::new(4.0, 0.0), Align2::LEFT_CENTER, &port.name, font.clone(), text_color);
+                    pin += port.width;
+                }
+                pin = 0;
+                for port in component.output_ports() {
+                    let pos = output_pin(node, pin).unwrap() + origin - Vec2::new(4.0, 0.0);
+                    painter.text(pos, Align2::RIGHT_CENTER, &port.name, font.clone(), text_color);
+                    pin += port.width;
+                }
+            }
+            NodeKind::Tristate(Tristate::Bus(_)) => painter.rect(rect, 2.0, levels[i][0].color(), stroke),
+            NodeKind::Tristate(tristate) => {
+                painter.rect(rect, 0.0, ui.visuals().extreme_bg_color, stroke);
+                painter.text(rect.center_top() + Vec2::new(0.0, 12.0), Align2::CENTER_CENTER, "1", FontId::proportional(16.0), text_color);
+                // Dreieck für den abschaltbaren Ausgang
+                let mark = rect.right_center() - Vec2::new(9.0, 0.0);
+                let triangle = vec![mark + Vec2::new(-5.0, -4.0), mark + Vec2::new(5.0, -4.0), mark + Vec2::new(0.0, 4.0)];
+                painter.add(Shape::closed_line(triangle, Stroke::new(1.5, text_color)));
+                let font = FontId::proportional(11.0);
+                for (pin, port) in tristate.input_ports().iter().enumerate() {
+                    painter.text(input_pin(node, pin) + origin + Vec2::new(4.0, 0.0), Align2::LEFT_CENTER, &port.name, font.clone(), text_color);
+                }
+                if *tristate == Tristate::Inverter {
+                    painter.circle_stroke(rect.right_center() + Vec2::new(BUBBLE_RADIUS, 0.0), BUBBLE_RADIUS, stroke);
+                }
+            }
+        }
+        if delays && matches!(node.kind, NodeKind::Gate(_) | NodeKind::Component(_) | NodeKind::Tristate(_)) {
+            let label = format!("#{} · {} t", i, node.delay);
+            painter.text(rect.center_bottom() + Vec2::new(0.0, 2.0), Align2::CENTER_TOP, label, FontId::proportional(10.0), Color32::GRAY);
+        }
+        let mut pin = 0;
+        for port in node.kind.output_ports() {
+            if let Some(pos) = output_pin(node, pin) {
+                let color = if port.width == 1 { levels[i][pin].color() } else { BUS_COLOR };
+                painter.circle_filled(pos + origin, 3.0, color);
+            }
+            pin += port.width;
+        }
+    }
+}
+
+/// Platz, den der Schaltplan einnimmt, mit etwas Rand für die Beschriftungen
+pub fn schematic_size(circuit: &Circuit) -> Vec2 {
+    circuit.nodes.iter().fold(Vec2::new(100.0, 60.0), |size, node| {
+        let corners = node.routes.iter().flatten().copied().chain([node_rect(node).right_bottom()]);
+        corners.fold(size, |size, p| size.max(p.to_vec2() + Vec2::new(40.0, 20.0)))
+    })
+}
+
+/// Zeichnet den Schaltplan ohne Markierungen in `canvas`, z.B. für den Export
+pub fn paint_schematic(ui: &Ui, painter: &Painter, canvas: Rect, circuit: &Circuit) {
+    draw(ui, painter, canvas, circuit, &[], &[], false);
+}
+
+/// Schaltplan ohne Bearbeitungsmöglichkeit, so groß wie die Schaltung selbst. Gibt den angeklickten Knoten zurück.
+pub fn show_schematic(ui: &mut Ui, circuit: &Circuit) -> Option<usize> {
+    let (response, painter) = ui.allocate_painter(schematic_size(circuit), Sense::click());
+    paint_schematic(ui, &painter, response.rect, circuit);
+    let p = response.interact_pointer_pos().filter(|_| response.clicked())? - response.rect.min.to_vec2();
+    (0..circuit.nodes.len()).rev().find(|&i| node_rect(&circuit.nodes[i]).contains(p))
+}
+
+/// Darstellung und Wert eines Ein- oder Ausgangs mit Bus
+fn show_bus_settings(ui: &mut Ui, kind: &mut NodeKind) {
+    let width = kind.width();
+    let (NodeKind::Input { format, device: InputDevice::Switch, .. } | NodeKind::Output { format, device: OutputDevice::Lamp, .. }) = kind
+    else {
+        return;
+    };
+    if width < 2 {
+        return;
+    }
+    ui.menu_button("Darstellung", |ui| {
+        for f in ALL_FORMATS {
+            ui.radio_value(format, f, f.label());
+        }
+    });
+    let NodeKind::Input { value, format, .. } = kind else { return };
+    let mut number = if *format == NumberFormat::Signed { bus::signed_value(value) } else { crate::equivalence::row_index(value) as i64 };
+    let (min, max) = if *format == NumberFormat::Signed { (-(1 << (width - 1)), (1 << (width - 1)) - 1) } else { (0, (1 << width) - 1) };
+    let drag = egui::DragValue::new(&mut number).clamp_range(min..=max);
+    let drag = match format {
+        NumberFormat::Binary => drag.binary(width, false),
+        NumberFormat::Hex => drag.hexadecimal(width.div_ceil(4), false, true).prefix("0x"),
+        NumberFormat::Unsigned | NumberFormat::Signed => drag,
+    };
+    ui.horizontal(|ui| {
+        ui.label("Wert:");
+        ui.add(drag);
+    });
+    *value = bus::from_value(number, width);
+}
+
+/// Verhalten eines Tasters bzw. Farbe einer LED oder 7-Segment-Anzeige
+fn show_device_settings(ui: &mut Ui, kind: &mut NodeKind) {
+    match kind {
+        NodeKind::Input { device: InputDevice::Button { momentary }, .. } => {
+            ui.radio_value(momentary, true, "Tastend").on_hover_text("High nur, solange die Maustaste gedrückt ist");
+            ui.radio_value(momentary, false, "Rastend").on_hover_text("Jeder Druck schaltet um");
+        }
+        NodeKind::Output { device: OutputDevice::Led(color) | OutputDevice::SevenSegment(color), .. } => {
+            ui.menu_button("Farbe", |ui| {
+                for c in ALL_LED_COLORS {
+                    ui.radio_value(color, c, c.label());
+                }
+            });
+        }
+        _ => {}
+    }
+}
+
+/// Name eines Ein- oder Ausgangs
+fn node_name(kind: &NodeKind) -> Option<&str> {
+    match kind {
+        NodeKind::Input { name, .. } | NodeKind::Output { name, .. } => Some(name),
+        NodeKind::Gate(_) | NodeKind::Component(_) | NodeKind::Tristate(_) => None,
+    }
+}
+
+/// Bezeichnung eines Bauteils für den Verlauf
+fn kind_name(kind: &NodeKind) -> String {
+    match kind {
+        NodeKind::Input { name, .. } => format!("Eingang {}", name),
+        NodeKind::Output { name, .. } => format!("Ausgang {}", name),
+        NodeKind::Gate(gate) => gate.to_string(),
+        NodeKind::Component(component) => component.name(),
+        NodeKind::Tristate(tristate) => tristate.name(),
+    }
+}
+
+/// Beschreibt die Änderung der Schaltung für den Verlauf. Das Umschalten von Eingängen gilt nicht als Bearbeitung.
+fn describe_change(old: &Circuit, new: &Circuit) -> Option<String> {
+    let without_values = |kind: &NodeKind| {
+        let mut kind = kind.clone();
+        if let NodeKind::Input { value, .. } = &mut kind {
+            value.fill(false);
+        }
+        kind
+    };
+    if new.nodes.len() != old.nodes.len() {
+        let (more, fewer) = if new.nodes.len() > old.nodes.len() { (new, old) } else { (old, new) };
+        // Eingefügt wird am Ende, beim Entfernen rücken die übrigen Knoten auf
+        let changed = (0..more.nodes.len()).find(|&i| fewer.nodes.get(i).is_none_or(|node| node.kind != more.nodes[i].kind)).unwrap();
+        let name = kind_name(&more.nodes[changed].kind);
+        return Some(if new.nodes.len() > old.nodes.len() { format!("{} eingefügt", name) } else { format!("{} entfernt", name) });
+    }
+    let pairs = || old.nodes.iter().zip(&new.nodes);
+    let wires = |circuit: &Circuit| circuit.nodes.iter().flat_map(|node| node.inputs.iter().flatten()).count();
+    if pairs().any(|(a, b)| a.inputs != b.inputs) {
+        return Some(match wires(new).cmp(&wires(old)) {
+            std::cmp::Ordering::Greater => "Leitung verbunden".to_string(),
+            std::cmp::Ordering::Less => "Leitung getrennt".to_string(),
+            std::cmp::Ordering::Equal => "Leitungen geändert".to_string(),
+        });
+    }
+    if let Some((_, b)) = pairs().find(|(a, b)| without_values(&a.kind) != without_values(&b.kind)) {
+        return Some(format!("{} bearbeitet", kind_name(&b.kind)));
+    }
+    if let Some((_, b)) = pairs().find(|(a, b)| a.delay != b.delay) {
+        return Some(format!("Laufzeit von {} geändert", kind_name(&b.kind)));
+    }
+    pairs().any(|(a, b)| a.pos != b.pos || a.routes != b.routes).then(|| "Bauteile verschoben".to_string())
+}
+
+#[derive(Default)]
+pub struct CircuitEditor {
+    pub circuit: Circuit,
+    /// Knoten, die besonders markiert werden (z.B. falsche Ausgänge eines Gegenbeispiels)
+    pub highlighted: Vec<usize>,
+    /// Übungsmodus: Ein- und Ausgänge sind vorgegeben und können weder gelöscht noch umbenannt werden,
+    /// Bausteine stehen nicht zur Verfügung
+    pub fixed_io: bool,
+    dragged: Option<usize>,
+    wire_start: Option<Source>,
+    context_node: Option<usize>,
+    /// Ausgewählte Knoten; sie werden gemeinsam verschoben und lassen sich zu einer Teilschaltung zusammenfassen
+    selected: Vec<usize>,
+    /// Startpunkt des Auswahlrechtecks
+    selecting: Option<Pos2>,
+    /// Geöffneter Dialog zum Erstellen einer Teilschaltung
+    extraction: Option<Extraction>,
+    /// Selbst erstellte Teilschaltungen, die im Bausteinmenü angeboten werden
+    subcircuits: Vec<Rc<Subcircuit>>,
+    /// Baustein, dessen Innenschaltung in einem eigenen Fenster angezeigt wird, und der Weg zu den
+    /// darin geöffneten Bausteinen: jeder Eintrag ist ein Knoten in der Innenschaltung des vorigen
+    inspected: Vec<usize>,
+    canvas_size: Vec2,
+    spawned: usize,
+    timing: TimingView,
+    history: History<Circuit>,
+}
+
+impl CircuitEditor {
+    pub fn new(circuit: Circuit, fixed_io: bool) -> Self {
+        Self { circuit, fixed_io, ..Default::default() }
+    }
+
+    /// Ersetzt die Schaltung; die eigenen Teilschaltungen, die Einstellungen des Zeitdiagramms und der Verlauf
+    /// bleiben erhalten, sodass sich das Ersetzen rückgängig machen lässt
+    pub fn load(&mut self, circuit: Circuit) {
+        let subcircuits = std::mem::take(&mut self.subcircuits);
+        let timing = std::mem::take(&mut self.timing);
+        let history = std::mem::take(&mut self.history);
+        *self = Self { circuit, fixed_io: self.fixed_io, subcircuits, timing, history, ..Default::default() };
+    }
+
+    /// Stellt einen Zustand aus dem Verlauf wieder her; Auswahl und Markierungen passen nicht mehr dazu
+    fn restore(&mut self, circuit: Circuit) {
+        self.circuit = circuit;
+        self.selected.clear();
+        self.highlighted.clear();
+        self.inspected.clear();
+        self.context_node = None;
+        self.wire_start = None;
+        self.dragged = None;
+    }
+
+    fn hit_node(&self, p: Pos2) -> Option<usize> {
+        (0..self.circuit.nodes.len()).rev().find(|&i| node_rect(&self.circuit.nodes[i]).expand(2.0).contains(p))
+    }
+
+    fn hit_output_pin(&self, p: Pos2) -> Option<Source> {
+        self.circuit.nodes.iter().enumerate().find_map(|(i, node)| {
+            (0..node.kind.output_count())
+                .find(|&pin| output_pin(node, pin).is_some_and(|pos| pos.distance(p) <= PIN_RADIUS))
+                .map(|pin| Source { node: i, pin })
+        })
+    }
+
+    fn hit_input_pin(&self, p: Pos2) -> Option<(usize, usize)> {
+        self.circuit.nodes.iter().enumerate().find_map(|(i, node)| {
+            (0..node.inputs.len()).find(|&pin| input_pin(node, pin).distance(p) <= PIN_RADIUS).map(|pin| (i, pin))
+        })
+    }
+
+    /// Kopiert die ausgewählten Bauteile samt der Leitungen zwischen ihnen in die Zwischenablage. Das Programm merkt
+    /// sich den Text zusätzlich, damit ihn das Kontextmenü ohne Tastenkürzel einfügen kann.
+    fn copy_selection(&self, ctx: &egui::Context) {
+        if self.selected.is_empty() {
+            return;
+        }
+        let clipboard = Clipboard { format: CLIPBOARD_FORMAT.to_string(), circuit: self.circuit.subset(&self.selected) };
+        let text = serde_json::to_string(&clipboard).expect("Schaltungen lassen sich immer serialisieren");
+        ctx.output().copied_text = text.clone();
+        ctx.data().insert_temp(egui::Id::new(CLIPBOARD_FORMAT), text);
+    }
+
+    /// Entfernt die ausgewählten Bauteile; im Übungsmodus bleiben Ein- und Ausgänge stehen
+    fn delete_selection(&mut self) {
+        let mut nodes = std::mem::take(&mut self.selected);
+        nodes.sort_unstable();
+        for n in nodes.into_iter().rev() {
+            if !self.fixed_io || !self.circuit.nodes[n].kind.is_io() {
+                self.circuit.remove_node(n);
+            }
+        }
+        self.context_node = None;
+        self.inspected.clear();
+    }
+
+    /// Fügt eine kopierte Schaltung ein und wählt sie aus. Gibt zurück, ob der Text eine gültige Schaltung enthielt.
+    fn paste(&mut self, text: &str) -> bool {
+        let Ok(Clipboard { format, mut circuit }) = serde_json::from_str(text) else { return false };
+        if format != CLIPBOARD_FORMAT || !circuit.is_valid() {
+            return false;
+        }
+        if self.fixed_io {
+            // In Übungen sind die Ein- und Ausgänge vorgegeben
+            let parts: Vec<usize> = (0..circuit.nodes.len()).filter(|&n| !circuit.nodes[n].kind.is_io()).collect();
+            circuit = circuit.subset(&parts);
+        }
+        let pasted = self.circuit.insert(&circuit, PASTE_OFFSET);
+        // Ein- und Ausgänge, deren Name schon vergeben ist, erhalten einen neuen
+        for &n in &pasted {
+            let taken = |name: &str| self.circuit.nodes.iter().enumerate().any(|(m, node)| m != n && node_name(&node.kind) == Some(name));
+            let (prefix, letters) = match &self.circuit.nodes[n].kind {
+                NodeKind::Input { name, .. } if taken(name) => ("E", "ABCDEFGH"),
+                NodeKind::Output { name, .. } if taken(name) => ("Y", "YXZ"),
+                _ => continue,
+            };
+            if let NodeKind::Input { name, .. } | NodeKind::Output { name, .. } = &mut self.circuit.nodes[n].kind {
+                name.clear();
+            }
+            let new_name = self.next_name(prefix, letters);
+            if let NodeKind::Input { name, .. } | NodeKind::Output { name, .. } = &mut self.circuit.nodes[n].kind {
+                *name = new_name;
+            }
+        }
+        self.selected = pasted;
+        !self.selected.is_empty()
+    }
+
+    fn next_name(&self, prefix: &str, letters: &str) -> String {
+        let names: Vec<&str> = self
+            .circuit
+            .nodes
+            .iter()
+            .filter_map(|n| node_name(&n.kind))
+            .collect();
+        letters
+            .chars()
+            .map(|c| c.to_string())
+            .chain((1..).map(|i| format!("{}{}", prefix, i)))
+            .find(|name| !names.contains(&name.as_str()))
+            .unwrap()
+    }
+
+    fn spawn(&mut self, kind: NodeKind) {
+        let size = self.canvas_size.max(Vec2::new(300.0, 200.0));
+        let count = |f: fn(&NodeKind) -> bool| self.circuit.nodes.iter().filter(|n| f(&n.kind)).count() as f32;
+        let pos = match kind {
+            NodeKind::Input { .. } => Pos2::new(40.0, 30.0 + 40.0 * count(|k| matches!(k, NodeKind::Input { .. }))),
+            NodeKind::Output { .. } => {
+                Pos2::new(size.x - 40.0, 30.0 + 40.0 * count(|k| matches!(k, NodeKind::Output { .. })))
+            }
+            NodeKind::Gate(_) | NodeKind::Component(_) | NodeKind::Tristate(_) => {
+                self.spawned += 1;
+                let offset = 25.0 * (self.spawned % 6) as f32;
+                Pos2::new(size.x / 2.0 - 60.0 + offset, size.y / 2.0 - 60.0 + offset)
+            }
+        };
+        let n = self.circuit.add_node(kind, pos);
+        // Große Peripherie ragt sonst über den Rand der Zeichenfläche
+        let half = node_rect(&self.circuit.nodes[n]).size() / 2.0;
+        self.circuit.nodes[n].pos = pos.clamp(Pos2::ZERO + half, Pos2::ZERO + size.max(2.0 * half) - half);
+    }
+
+    /// Bedient einen Eingang, der an der Stelle `p` angeklickt wurde
+    fn operate(&mut self, n: usize, p: Pos2) {
+        let rect = node_rect(&self.circuit.nodes[n]);
+        match &mut self.circuit.nodes[n].kind {
+            // Tastende Taster folgen der Maustaste, siehe `hold_buttons`
+            NodeKind::Input { device: InputDevice::Button { momentary: true }, .. } => {}
+            NodeKind::Input { device: InputDevice::Dip, value, .. } => {
+                if let Some(i) = peripherals::dip_switch_at(rect, value.len(), p) {
+                    value[i] = !value[i];
+                }
+            }
+            NodeKind::Input { device: InputDevice::HexKeypad, value, .. } => {
+                if let Some(key) = peripherals::keypad_key_at(rect, p) {
+                    *value = key;
+                }
+            }
+            // Ein Bit wird umgeschaltet, ein Bus zählt um eins weiter
+            NodeKind::Input { value, .. } => *value = bus::from_value(bus::signed_value(value) + 1, value.len()),
+            _ => {}
+        }
+    }
+
+    /// Tastende Taster sind gedrückt, solange die Maustaste über ihnen gehalten wird
+    fn hold_buttons(&mut self, ui: &Ui, response: &egui::Response, to_local: impl Fn(Pos2) -> Pos2) {
+        let held = (response.is_pointer_button_down_on() && ui.input().pointer.primary_down())
+            .then(|| ui.input().pointer.interact_pos().map(to_local))
+            .flatten()
+            .and_then(|p| self.hit_node(p));
+        for (i, node) in self.circuit.nodes.iter_mut().enumerate() {
+            if let NodeKind::Input { device: InputDevice::Button { momentary: true }, value, .. } = &mut node.kind {
+                value[0] = held == Some(i);
+            }
+        }
+    }
+
+    /// Buchstabentasten schalten die einzelnen Eingänge mit diesem Namen, z.B. A, B, C
+    fn keyboard_inputs(&mut self, ui: &Ui) {
+        for letter in accessibility::letters_pressed(ui) {
+            for n in 0..self.circuit.nodes.len() {
+                let node = &self.circuit.nodes[n];
+                if matches!(&node.kind, NodeKind::Input { name, value, .. } if value.len() == 1 && name.eq_ignore_ascii_case(&letter.to_string())) {
+                    self.operate(n, node_rect(node).center());
+                }
+            }
+        }
+    }
+
+    /// Bedienung der Zeichenfläche mit der Tastatur, solange sie den Fokus hat: Bild ab/auf wählt das nächste bzw.
+    /// vorige Bauteil, die Pfeiltasten verschieben die Auswahl. Enter beginnt am gewählten Bauteil eine Leitung und
+    /// verbindet sie mit dem ersten freien Eingang des danach gewählten; eine Ziffer wählt dabei den Anschluss.
+    /// Entf löscht die Auswahl, Esc bricht eine Leitung ab. Gibt zurück, ob sich der Aufbau geändert hat.
+    fn keyboard_editing(&mut self, ui: &Ui, response: &egui::Response) -> bool {
+        const DIGITS: [Key; 9] = [Key::Num1, Key::Num2, Key::Num3, Key::Num4, Key::Num5, Key::Num6, Key::Num7, Key::Num8, Key::Num9];
+        const STEP: f32 = 10.0;
+        let count = self.circuit.nodes.len();
+        if !response.has_focus() || count == 0 {
+            return false;
+        }
+        let current = self.selected.last().copied();
+        if accessibility::key_pressed(ui, Key::PageDown) {
+            self.selected = vec![current.map_or(0, |n| (n + 1) % count)];
+        } else if accessibility::key_pressed(ui, Key::PageUp) {
+            self.selected = vec![current.map_or(count - 1, |n| (n + count - 1) % count)];
+        }
+        let moves = [
+            (Key::ArrowLeft, Vec2::new(-STEP, 0.0)),
+            (Key::ArrowRight, Vec2::new(STEP, 0.0)),
+            (Key::ArrowUp, Vec2::new(0.0, -STEP)),
+            (Key::ArrowDown, Vec2::new(0.0, STEP)),
+        ];
+        for (key, delta) in moves {
+            if accessibility::key_pressed(ui, key) {
+                for &n in &self.selected {
+                    let node = &mut self.circuit.nodes[n];
+                    node.pos = (node.pos + delta).clamp(Pos2::ZERO, Pos2::ZERO + self.canvas_size);
+                }
+            }
+        }
+        if accessibility::key_pressed(ui, Key::Escape) {
+            self.wire_start = None;
+        }
+        let mut changed = false;
+        let port = DIGITS.iter().position(|&key| accessibility::key_pressed(ui, key));
+        let enter = accessibility::key_pressed(ui, Key::Enter);
+        if let (Some(n), true) = (self.selected.last().copied(), port.is_some() || enter) {
+            // Erstes Bit des gewählten Anschlusses
+            let first_pin = |ports: Vec<Port>, k: usize| (k < ports.len()).then(|| ports[..k].iter().map(|p| p.width).sum::<usize>());
+            let kind = &self.circuit.nodes[n].kind;
+            match self.wire_start {
+                None => self.wire_start = first_pin(kind.output_ports(), port.unwrap_or(0)).map(|pin| Source { node: n, pin }),
+                Some(from) => {
+                    let pin = match port {
+                        Some(k) => first_pin(kind.input_ports(), k),
+                        None => self.circuit.nodes[n].inputs.iter().position(Option::is_none),
+                    };
+                    if let Some(pin) = pin {
+                        changed |= self.circuit.connect_port(from, n, pin);
+                        self.wire_start = None;
+                    }
+                }
+            }
+        }
+        if accessibility::key_pressed(ui, Key::Delete) && !self.selected.is_empty() {
+            self.delete_selection();
+            self.wire_start = None;
+            changed = true;
+        }
+        changed
+    }
+
+    /// Zustand der Zeichenfläche für Screenreader: gewähltes Bauteil und begonnene Leitung
+    fn describe_canvas(&self, response: &egui::Response) {
+        let mut text = format!("Schaltplan mit {} Bauteilen", self.circuit.nodes.len());
+        if let Some(&n) = self.selected.last() {
+            text += &format!(", gewählt: {}", kind_name(&self.circuit.nodes[n].kind));
+        }
+        if let Some(from) = self.wire_start {
+            text += &format!(", Leitung ab {}", kind_name(&self.circuit.nodes[from.node].kind));
+        }
+        accessibility::describe(response, WidgetType::Other, text);
+    }
+
+    /// Ein- und Ausgänge mit Namen und Pegel für Screenreader
+    fn describe_io(&self, ui: &Ui, canvas: Rect) {
+        let levels = self.circuit.current_levels();
+        for (i, node) in self.circuit.nodes.iter().enumerate() {
+            let text = match &node.kind {
+                NodeKind::Input { name, value, format, .. } => format!("Eingang {}", bus_text(name, value, *format)),
+                NodeKind::Output { name, format, .. } => format!("Ausgang {}", level_text(name, &levels[i], *format)),
+                _ => continue,
+            };
+            accessibility::describe_area(ui, node_rect(node).translate(canvas.min.to_vec2()), ui.id().with(("io", i)), text);
+        }
+    }
+
+    /// Nur die Schaltung, mittig und ohne Werkzeugleiste, z.B. für den Präsentationsmodus. Eingänge lassen sich
+    /// weiterhin schalten, der Aufbau bleibt unverändert.
+    pub fn show_presentation(&mut self, ui: &mut Ui) {
+        let size = schematic_size(&self.circuit);
+        egui::ScrollArea::both().show(ui, |ui| {
+            let (response, painter) = ui.allocate_painter(ui.available_size().max(size), Sense::click_and_drag());
+            let canvas = Rect::from_center_size(response.rect.center(), size);
+            let to_local = |p: Pos2| p - canvas.min.to_vec2();
+            if let Some(p) = response.interact_pointer_pos().filter(|_| response.clicked()).map(to_local) {
+                if let Some(n) = self.hit_node(p) {
+                    self.operate(n, p);
+                }
+            }
+            self.hold_buttons(ui, &response, to_local);
+            self.keyboard_inputs(ui);
+            draw(ui, &painter, canvas, &self.circuit, &[], &[], false);
+            self.describe_io(ui, canvas);
+        });
+    }
+
+    /// Zeichnet Werkzeugleiste und Zeichenfläche. Gibt zurück, ob sich der Aufbau der Schaltung geändert hat.
+    pub fn show(&mut self, ui: &mut Ui) -> bool {
+        let mut changed = false;
+        if let Some(circuit) = self.history.shortcuts(ui) {
+            self.restore(circuit);
+            changed = true;
+        }
+        ui.horizontal_wrapped(|ui| {
+            if let Some(circuit) = self.history.show_controls(ui) {
+                self.restore(circuit);
+                changed = true;
+            }
+            ui.separator();
+            if !self.fixed_io {
+                if ui.button("+ Eingang").clicked() {
+                    let name = self.next_name("E", "ABCDEFGH");
+                    self.spawn(NodeKind::input(name));
+                    changed = true;
+                }
+                if ui.button("+ Ausgang").clicked() {
+                    let name = self.next_name("Y", "YXZ");
+                    self.spawn(NodeKind::output(name));
+                    changed = true;
+                }
+                ui.menu_button("+ Bus", |ui| {
+                    if ui.button("Zahleneingabe (4 Bit)").clicked() {
+                        let name = self.next_name("E", "ABCDEFGH");
+                        self.spawn(NodeKind::bus_input(name, 4));
+                        changed = true;
+                        ui.close_menu();
+                    }
+                    if ui.button("Zahlenanzeige (4 Bit)").clicked() {
+                        let name = self.next_name("Y", "YXZ");
+                        self.spawn(NodeKind::bus_output(name, 4));
+                        changed = true;
+                        ui.close_menu();
+                    }
+                });
+                ui.menu_button("+ Peripherie", |ui| {
+                    let inputs = [
+                        ("Taster (tastend)", InputDevice::Button { momentary: true }),
+                        ("Taster (rastend)", InputDevice::Button { momentary: false }),
+                        ("DIP-Schalter (4 Bit)", InputDevice::Dip),
+                        ("Hex-Tastatur", InputDevice::HexKeypad),
+                    ];
+                    for (label, device) in inputs {
+                        if ui.button(label).clicked() {
+                            let name = self.next_name("E", "ABCDEFGH");
+                            self.spawn(NodeKind::device_input(name, device));
+                            changed = true;
+                            ui.close_menu();
+                        }
+                    }
+                    ui.separator();
+                    let outputs =
+                        [("LED", OutputDevice::Led(LedColor::Red)), ("7-Segment-Anzeige", OutputDevice::SevenSegment(LedColor::Red))];
+                    for (label, device) in outputs {
+                        if ui.button(label).clicked() {
+                            let name = self.next_name("Y", "YXZ");
+                            self.spawn(NodeKind::device_output(name, device));
+                            changed = true;
+                            ui.close_menu();
+                        }
+                    }
+                });
+                ui.separator();
+            }
+            for gate in ALL_GATES {
+                if ui.button(gate.to_string()).clicked() {
+                    self.spawn(NodeKind::Gate(gate));
+                    changed = true;
+                }
+            }
+            if !self.fixed_io {
+                ui.menu_button("Bausteine", |ui| {
+                    for (family, components) in Component::library() {
+                        ui.menu_button(family, |ui| {
+                            for component in components {
+                                if ui.button(component.name()).clicked() {
+                                    self.spawn(NodeKind::Component(component));
+                                    changed = true;
+                                    ui.close_menu();
+                                }
+                            }
+                        });
+                    }
+                    ui.menu_button("Tri-State", |ui| {
+                        let parts = [Tristate::Buffer, Tristate::Inverter, Tristate::Bus(2), Tristate::Bus(3), Tristate::Bus(4)];
+                        for tristate in parts {
+                            if ui.button(tristate.name()).clicked() {
+                                self.spawn(NodeKind::Tristate(tristate));
+                                changed = true;
+                                ui.close_menu();
+                            }
+                        }
+                    });
+                    if !self.subcircuits.is_empty() {
+                        ui.separator();
+                        ui.menu_button("Eigene Teilschaltungen", |ui| {
+                            for subcircuit in self.subcircuits.clone() {
+                                if ui.button(&subcircuit.name).clicked() {
+                                    self.spawn(NodeKind::Component(Component::Custom(subcircuit)));
+                                    changed = true;
+                                    ui.close_menu();
+                                }
+                            }
+                        });
+                    }
+                });
+            }
+            ui.separator();
+            ui.toggle_value(&mut self.timing.open, "Zeitdiagramm").on_hover_text("Simuliert die Schaltung mit den Laufzeiten der Gatter");
+            if ui.button("Aufräumen").on_hover_text("Ordnet die Bauteile nach der Logiktiefe an und verlegt die Leitungen neu").clicked() {
+                layout::arrange(&mut self.circuit);
+            }
+        });
+        ui.label("Ziehe vom Ausgang eines Bauteils zu einem Eingang, um zu verbinden. Klick auf einen Eingang schaltet ihn um (ein Bus zählt weiter), Taster, DIP-Schalter und Tastaturen werden direkt bedient. Rechtsklick öffnet das Kontextmenü.");
+        ui.label("Leitungsfarben: grün = 1, rot = 0, grau = hochohmig (Z), violett = unbestimmt oder Konflikt (X).");
+        if !self.fixed_io {
+            ui.label("Ziehe auf der freien Fläche ein Rechteck auf oder klicke mit gedrückter Umschalttaste, um Bauteile auszuwählen.");
+        }
+        ui.label("Tastatur: Tab wählt die Zeichenfläche, Bild auf/ab ein Bauteil, Pfeiltasten verschieben es. Enter beginnt eine Leitung und verbindet sie mit dem danach gewählten Bauteil, eine Ziffer wählt den Anschluss. Entf löscht, Esc bricht ab.");
+        let node_count = self.circuit.nodes.len();
+
+        let (response, painter) = ui.allocate_painter(ui.available_size(), Sense::click_and_drag());
+        let canvas = response.rect;
+        self.canvas_size = canvas.size();
+        let origin = canvas.min.to_vec2();
+        let to_local = |p: Pos2| p - origin;
+
+        // Eingaben
+        if response.drag_started() {
+            if let Some(p) = ui.input().pointer.press_origin().map(to_local) {
+                if let Some(n) = self.hit_output_pin(p) {
+                    self.wire_start = Some(n);
+                } else if self.hit_input_pin(p).is_none() {
+                    self.dragged = self.hit_node(p);
+                    match self.dragged {
+                        Some(n) if !self.selected.contains(&n) => self.selected.clear(),
+                        Some(_) => {}
+                        None if !self.fixed_io => self.selecting = Some(p),
+                        None => {}
+                    }
+                }
+            }
+        }
+        if response.dragged() {
+            if let Some(n) = self.dragged {
+                // Ein ausgewählter Knoten nimmt die übrige Auswahl mit
+                let moved = if self.selected.contains(&n) { self.selected.clone() } else { vec![n] };
+                for n in moved {
+                    let node = &mut self.circuit.nodes[n];
+                    node.pos = (node.pos + response.drag_delta()).clamp(Pos2::ZERO, Pos2::ZERO + canvas.size());
+                }
+            }
+        }
+        if response.drag_released() {
+            if let (Some(start), Some(p)) = (self.selecting.take(), response.hover_pos().map(to_local)) {
+                let area = Rect::from_two_pos(start, p);
+                self.selected = (0..self.circuit.nodes.len()).filter(|&i| area.contains(self.circuit.nodes[i].pos)).collect();
+            }
+            if let (Some(from), Some(p)) = (self.wire_start, response.hover_pos().map(to_local)) {
+                if let Some((to, pin)) = self.hit_input_pin(p) {
+                    changed |= self.circuit.connect_port(from, to, pin);
+                }
+            }
+            self.dragged = None;
+            self.wire_start = None;
+        }
+        if response.clicked() {
+            // Danach wirken die Tasten zum Bearbeiten auf die Zeichenfläche
+            response.request_focus();
+            if let Some(p) = response.interact_pointer_pos().map(to_local) {
+                if let Some(n) = self.hit_output_pin(p) {
+                    self.wire_start = Some(n);
+                } else if let Some((to, pin)) = self.hit_input_pin(p) {
+                    if let Some(from) = self.wire_start.take() {
+                        changed |= self.circuit.connect_port(from, to, pin);
+                    } else {
+                        changed |= self.circuit.disconnect_port(to, pin);
+                    }
+                } else {
+                    self.wire_start = None;
+                    match self.hit_node(p) {
+                        Some(n) if ui.input().modifiers.shift && !self.fixed_io => {
+                            match self.selected.iter().position(|&s| s == n) {
+                                Some(i) => {
+                                    self.selected.remove(i);
+                                }
+                                None => self.selected.push(n),
+                            }
+                        }
+                        Some(n) => self.operate(n, p),
+                        None => self.selected.clear(),
+                    }
+                }
+            }
+        }
+        self.hold_buttons(ui, &response, to_local);
+        if response.secondary_clicked() {
+            self.context_node = response.interact_pointer_pos().map(to_local).and_then(|p| self.hit_node(p));
+        }
+        let mut paste = None;
+        let response = response.context_menu(|ui| {
+            match self.context_node {
+                Some(n) => {
+                    let is_io = self.circuit.nodes[n].kind.is_io();
+                    if is_io && !self.fixed_io {
+                        if let NodeKind::Input { name, .. } | NodeKind::Output { name, .. } = &mut self.circuit.nodes[n].kind {
+                            ui.horizontal(|ui| {
+                                ui.label("Name:");
+                                ui.text_edit_singleline(name);
+                            });
+                        }
+                        if self.circuit.nodes[n].kind.resizable() {
+                            let mut width = self.circuit.nodes[n].kind.width();
+                            ui.horizontal(|ui| {
+                                ui.label("Breite:");
+                                ui.add(egui::DragValue::new(&mut width).clamp_range(1..=bus::MAX_WIDTH).suffix(" Bit"));
+                            });
+                            if width != self.circuit.nodes[n].kind.width() {
+                                self.circuit.set_width(n, width);
+                                changed = true;
+                            }
+                        }
+                    }
+                    if is_io {
+                        show_bus_settings(ui, &mut self.circuit.nodes[n].kind);
+                        show_device_settings(ui, &mut self.circuit.nodes[n].kind);
+                    }
+                    if matches!(self.circuit.nodes[n].kind, NodeKind::Gate(_) | NodeKind::Component(_) | NodeKind::Tristate(_)) {
+                        ui.horizontal(|ui| {
+                            ui.label("Laufzeit:");
+                            ui.add(egui::DragValue::new(&mut self.circuit.nodes[n].delay).clamp_range(0..=MAX_DELAY).suffix(" t"));
+                        });
+                    }
+                    if let NodeKind::Component(component) = &self.circuit.nodes[n].kind {
+                        ui.label(component.name());
+                        if ui.button("Innenschaltung anzeigen").clicked() {
+                            self.inspected = vec![n];
+                            ui.close_menu();
+                        }
+                        if ui.button("In Gatter auflösen").on_hover_text("Ersetzt den Baustein durch seine Innenschaltung").clicked() {
+                            self.circuit.expand(n);
+                            self.context_node = None;
+                            self.inspected.clear();
+                            changed = true;
+                            ui.close_menu();
+                        }
+                    }
+                    if self.selected.contains(&n) {
+                        self.clipboard_buttons(ui, &mut changed);
+                        self.extraction_button(ui);
+                    }
+                    if ui.add_enabled(!is_io || !self.fixed_io, egui::Button::new("Löschen")).clicked() {
+                        self.circuit.remove_node(n);
+                        self.context_node = None;
+                        self.inspected.clear();
+                        changed = true;
+                        ui.close_menu();
+                    }
+                }
+                None => {
+                    if !self.selected.is_empty() {
+                        self.clipboard_buttons(ui, &mut changed);
+                        self.extraction_button(ui);
+                    }
+                    let copied = ui.ctx().data().get_temp::<String>(egui::Id::new(CLIPBOARD_FORMAT));
+                    if ui.add_enabled(copied.is_some(), egui::Button::new("Einfügen")).on_hover_text("Strg+V").clicked() {
+                        paste = copied;
+                        ui.close_menu();
+                    }
+                    if ui.button("Alle Gatter und Bausteine entfernen").clicked() {
+                        while let Some(n) = self
+                            .circuit
+                            .nodes
+                            .iter()
+                            .position(|n| matches!(n.kind, NodeKind::Gate(_) | NodeKind::Component(_) | NodeKind::Tristate(_)))
+                        {
+                            self.circuit.remove_node(n);
+                        }
+                        self.inspected.clear();
+                        changed = true;
+                        ui.close_menu();
+                    }
+                }
+            }
+        });
+        changed |= self.show_extraction(ui.ctx());
+        if changed {
+            self.highlighted.clear();
+        }
+        // Nach dem Einfügen oder Entfernen von Knoten stimmen die Nummern der Auswahl nicht mehr
+        if self.circuit.nodes.len() != node_count {
+            self.selected.clear();
+        }
+        changed |= self.keyboard_editing(ui, &response);
+        // Zwischenablage; solange ein Textfeld bearbeitet wird, gehören die Tastenkürzel ihm
+        if !accessibility::typing(ui.ctx()) {
+            let events = ui.input().events.clone();
+            for event in events {
+                match event {
+                    Event::Copy => self.copy_selection(ui.ctx()),
+                    Event::Cut if !self.selected.is_empty() => {
+                        self.copy_selection(ui.ctx());
+                        self.delete_selection();
+                        changed = true;
+                    }
+                    Event::Paste(text) => paste = Some(text),
+                    _ => {}
+                }
+            }
+        }
+        if let Some(text) = paste {
+            changed |= self.paste(&text);
+        }
+        self.keyboard_inputs(ui);
+
+        draw(ui, &painter, canvas, &self.circuit, &self.highlighted, &self.selected, self.timing.open);
+        self.describe_io(ui, canvas);
+        self.describe_canvas(&response);
+        if let (Some(from), Some(pointer)) = (self.wire_start, response.hover_pos()) {
+            let from = output_pin(&self.circuit.nodes[from.node], from.pin).unwrap() + origin;
+            painter.line_segment([from, pointer], Stroke::new(1.5, Color32::LIGHT_BLUE));
+        }
+        if let (Some(start), Some(pointer)) = (self.selecting, response.hover_pos()) {
+            let area = Rect::from_two_pos(start + origin, pointer);
+            painter.rect(area, 0.0, Color32::LIGHT_BLUE.linear_multiply(0.1), Stroke::new(1.0, Color32::LIGHT_BLUE));
+        }
+        self.show_inspected(ui.ctx());
+        self.timing.show(ui.ctx(), &self.circuit);
+        self.history.track(ui, &self.circuit, describe_change);
+        changed
+    }
+
+    /// Menüeinträge zum Kopieren und Ausschneiden der Auswahl
+    fn clipboard_buttons(&mut self, ui: &mut Ui, changed: &mut bool) {
+        if ui.button("Kopieren").on_hover_text("Strg+C").clicked() {
+            self.copy_selection(ui.ctx());
+            ui.close_menu();
+        }
+        if ui.button("Ausschneiden").on_hover_text("Strg+X").clicked() {
+            self.copy_selection(ui.ctx());
+            self.delete_selection();
+            *changed = true;
+            ui.close_menu();
+        }
+    }
+
+    /// Menüeintrag zum Zusammenfassen der Auswahl; ist das nicht möglich, erklärt der Tooltip den Grund
+    fn extraction_button(&mut self, ui: &mut Ui) {
+        if self.fixed_io {
+            return;
+        }
+        match Extraction::new(&self.circuit, &self.selected, self.subcircuits.len() + 1) {
+            Ok(extraction) => {
+                if ui.button("Auswahl als Teilschaltung zusammenfassen…").clicked() {
+                    self.extraction = Some(extraction);
+                    ui.close_menu();
+                }
+            }
+            Err(e) => {
+                ui.add_enabled(false, egui::Button::new("Auswahl als Teilschaltung zusammenfassen…")).on_disabled_hover_text(e);
+            }
+        }
+    }
+
+    /// Dialog für Name, Kurzzeichen und Anschlussnamen der neuen Teilschaltung. Gibt zurück, ob sie erstellt wurde.
+    fn show_extraction(&mut self, ctx: &egui::Context) -> bool {
+        let Some(extraction) = &mut self.extraction else { return false };
+        let (mut create, mut cancel) = (false, false);
+        egui::Window::new("Teilschaltung erstellen").collapsible(false).resizable(false).show(ctx, |ui| {
+            egui::Grid::new("subcircuit_names").num_columns(2).show(ui, |ui| {
+                ui.label("Name:");
+                ui.text_edit_singleline(&mut extraction.name);
+                ui.end_row();
+                ui.label("Kurzzeichen:");
+                ui.text_edit_singleline(&mut extraction.symbol);
+                ui.end_row();
+            });
+            ui.separator();
+            ui.columns(2, |columns| {
+                for (ui, (title, ports)) in columns.iter_mut().zip([("Eingänge", &mut extraction.inputs), ("Ausgänge", &mut extraction.outputs)]) {
+                    ui.strong(title);
+                    for (_, name) in ports.iter_mut() {
+                        ui.add(egui::TextEdit::singleline(name).desired_width(80.0));
+                    }
+                }
+            });
+            ui.separator();
+            let valid = extraction.validate();
+            if let Err(e) = &valid {
+                ui.label(RichText::new(e).color(Color32::RED));
+            }
+            ui.horizontal(|ui| {
+                create = ui.add_enabled(valid.is_ok(), egui::Button::new("Erstellen")).clicked();
+                cancel = ui.button("Abbrechen").clicked();
+            });
+        });
+        if create {
+            let subcircuit = self.extraction.take().unwrap().apply(&mut self.circuit);
+            self.subcircuits.push(subcircuit);
+            self.inspected.clear();
+        } else if cancel {
+            self.extraction = None;
+        }
+        create
+    }
+
+    /// Fenster mit der Innenschaltung des ausgewählten Bausteins; die Eingänge zeigen die Pegel an den Anschlüssen.
+    /// Ein Klick auf einen enthaltenen Baustein öffnet dessen Innenschaltung.
+    fn show_inspected(&mut self, ctx: &egui::Context) {
+        // Von außen nach innen: Name des Bausteins und seine Innenschaltung mit den anliegenden Pegeln
+        let mut levels: Vec<(String, Circuit)> = Vec::new();
+        for &n in &self.inspected {
+            let outer = levels.last().map_or(&self.circuit, |(_, inner)| inner);
+            let Some(NodeKind::Component(component)) = outer.nodes.get(n).map(|node| &node.kind) else { break };
+            let values = outer.node_values(&outer.input_values());
+            let pins: Vec<bool> = outer.nodes[n].inputs.iter().map(|src| src.is_some_and(|s| values[s.node][s.pin])).collect();
+            let mut inner = component.circuit();
+            inner.set_input_values(&pins);
+            levels.push((component.name(), inner));
+        }
+        self.inspected.truncate(levels.len());
+        let Some((name, inner)) = levels.last() else { return };
+        let mut open = true;
+        let mut path = self.inspected.clone();
+        egui::Window::new(format!("Innenschaltung: {}", name))
+            .id(egui::Id::new("inspected_component"))
+            .open(&mut open)
+            .default_size(Vec2::new(600.0, 400.0))
+            .show(ctx, |ui| {
+                if levels.len() > 1 {
+                    ui.horizontal_wrapped(|ui| {
+                        for (depth, (name, _)) in levels.iter().enumerate() {
+                            if depth > 0 {
+                                ui.label(">");
+                            }
+                            if ui.add_enabled(depth + 1 < levels.len(), egui::Button::new(name)).clicked() {
+                                path.truncate(depth + 1);
+                            }
+                        }
+                    });
+                }
+                ui.label(gate_summary(inner));
+                if inner.nodes.iter().any(|node| matches!(node.kind, NodeKind::Component(_))) {
+                    ui.label("Klicke auf einen Baustein, um hineinzusehen.");
+                }
+                egui::ScrollArea::both().show(ui, |ui| {
+                    if let Some(n) = show_schematic(ui, inner) {
+                        if matches!(inner.nodes[n].kind, NodeKind::Component(_)) {
+                            path.push(n);
+                        }
+                    }
+                });
+            });
+        self.inspected = if open { path } else { Vec::new() };
+    }
+}
@@ -0,0 +1,328 @@
+// Vergleich einer Schaltung mit einer Referenzfunktion, z.B. zur Bewertung von Übungen
+
+use crate::bdd::{Bdd, BddRef, FALSE, TRUE};
+use crate::circuit::{Circuit, NodeKind, Source};
+use crate::expr::Expr;
+use crate::level::Tristate;
+
+/// Bis zu dieser Anzahl an Eingängen wird jede Zeile der Wahrheitstabelle ausgewertet,
+/// darüber werden die Funktionen symbolisch als BDD verglichen
+pub const EXHAUSTIVE_MAX_INPUTS: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Method {
+    Exhaustive,
+    Bdd,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Counterexample {
+    pub inputs: Vec<bool>,
+    pub expected: Vec<bool>,
+    pub actual: Vec<bool>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Verdict {
+    pub method: Method,
+    pub counterexample: Option<Counterexample>,
+    /// Belegung, bei der Treiber auf einer Sammelleitung gegeneinander arbeiten; der Editor zeigt dort X, der
+    /// Vergleich rechnet zweiwertig wie `Tristate::compute_bit`
+    pub conflict: Option<Vec<bool>>,
+}
+
+impl Verdict {
+    pub fn correct(&self) -> bool {
+        self.counterexample.is_none() && self.conflict.is_none()
+    }
+}
+
+/// Eingangsbelegung einer Tabellenzeile; der erste Eingang ist das höchstwertige Bit
+pub fn row_inputs(row: usize, input_count: usize) -> Vec<bool> {
+    (0..input_count).map(|i| row >> (input_count - 1 - i) & 1 == 1).collect()
+}
+
+pub fn row_index(inputs: &[bool]) -> usize {
+    inputs.iter().fold(0, |acc, &b| acc << 1 | b as usize)
+}
+
+fn expr_bdd(bdd: &mut Bdd, expr: &Expr) -> BddRef {
+    match expr {
+        Expr::Const(c) => if *c { TRUE } else { FALSE },
+        Expr::Var(i) => bdd.var(*i),
+        Expr::Not(e) => {
+            let e = expr_bdd(bdd, e);
+            bdd.not(e)
+        }
+        Expr::And(es) => es.iter().fold(TRUE, |acc, e| {
+            let e = expr_bdd(bdd, e);
+            bdd.and(acc, e)
+        }),
+        Expr::Or(es) => es.iter().fold(FALSE, |acc, e| {
+            let e = expr_bdd(bdd, e);
+            bdd.or(acc, e)
+        }),
+        Expr::Xor(a, b) => {
+            let (a, b) = (expr_bdd(bdd, a), expr_bdd(bdd, b));
+            bdd.xor(a, b)
+        }
+    }
+}
+
+/// BDDs der Ausgangsbits und der Belegungen, bei denen Treiber auf einer Sammelleitung gegeneinander arbeiten
+fn circuit_bdds(bdd: &mut Bdd, circuit: &Circuit) -> (Vec<BddRef>, BddRef) {
+    let mut values = vec![Vec::new(); circuit.nodes.len()];
+    // Wann ein Tri-State-Treiber bzw. eine Sammelleitung überhaupt treibt; alle anderen Quellen treiben immer
+    let mut enabled = vec![TRUE; circuit.nodes.len()];
+    let mut conflict = FALSE;
+    let inputs = circuit.input_bits();
+    for n in circuit.topological_order() {
+        let node = &circuit.nodes[n];
+        let pin = |i: usize| node.inputs.get(i).copied().flatten().map_or(FALSE, |src| values[src.node][src.pin]);
+        let (a, b) = (pin(0), pin(1));
+        if let NodeKind::Tristate(tristate) = &node.kind {
+            let drivers: Vec<(BddRef, BddRef)> = (0..node.inputs.len())
+                .map(|i| (pin(i), node.inputs[i].map_or(FALSE, |src| enabled[src.node])))
+                .collect();
+            enabled[n] = match tristate {
+                Tristate::Buffer | Tristate::Inverter => b,
+                Tristate::Bus(_) => drivers.iter().fold(FALSE, |any, &(_, en)| bdd.or(any, en)),
+            };
+            if matches!(tristate, Tristate::Bus(_)) {
+                for (i, &(value_i, en_i)) in drivers.iter().enumerate() {
+                    for &(value_j, en_j) in &drivers[i + 1..] {
+                        let both = bdd.and(en_i, en_j);
+                        let differ = bdd.xor(value_i, value_j);
+                        let clash = bdd.and(both, differ);
+                        conflict = bdd.or(conflict, clash);
+                    }
+                }
+            }
+        }
+        values[n] = match &node.kind {
+            NodeKind::Input { value, .. } => {
+                (0..value.len()).map(|pin| bdd.var(inputs.iter().position(|&i| i == Source { node: n, pin }).unwrap())).collect()
+            }
+            NodeKind::Output { .. } => (0..node.inputs.len()).map(pin).collect(),
+            NodeKind::Component(_) => unreachable!("Bausteine werden vorher aufgelöst"),
+            // Zweiwertig wie `Tristate::compute_bit`
+            NodeKind::Tristate(tristate) => vec![match tristate {
+                Tristate::Buffer => bdd.and(a, b),
+                Tristate::Inverter => {
+                    let d = bdd.not(a);
+                    bdd.and(d, b)
+                }
+                Tristate::Bus(_) => (0..node.inputs.len()).map(pin).fold(FALSE, |bus, driver| bdd.or(bus, driver)),
+            }],
+            NodeKind::Gate(gate) => {
+                use crate::LogicGates::*;
+                vec![match gate {
+                    AND => bdd.and(a, b),
+                    OR => bdd.or(a, b),
+                    XOR => bdd.xor(a, b),
+                    NOT => bdd.not(a),
+                    NAND => {
+                        let r = bdd.and(a, b);
+                        bdd.not(r)
+                    }
+                    NOR => {
+                        let r = bdd.or(a, b);
+                        bdd.not(r)
+                    }
+                    XNOR => {
+                        let r = bdd.xor(a, b);
+                        bdd.not(r)
+                    }
+                }]
+            }
+        };
+    }
+    (circuit.output_bits().into_iter().map(|(n, pin)| values[n][pin]).collect(), conflict)
+}
+
+/// Funktion, mit der eine Schaltung verglichen wird
+#[derive(Clone, Copy)]
+enum Reference<'a> {
+    Exprs(&'a [Expr]),
+    Circuit(&'a Circuit),
+}
+
+impl Reference<'_> {
+    fn evaluate(&self, inputs: &[bool]) -> Vec<bool> {
+        match self {
+            Reference::Exprs(exprs) => exprs.iter().map(|e| e.eval(inputs)).collect(),
+            Reference::Circuit(circuit) => circuit.evaluate(inputs),
+        }
+    }
+
+    fn bdds(&self, bdd: &mut Bdd) -> Vec<BddRef> {
+        match self {
+            Reference::Exprs(exprs) => exprs.iter().map(|e| expr_bdd(bdd, e)).collect(),
+            Reference::Circuit(circuit) => circuit_bdds(bdd, &circuit.flattened()).0,
+        }
+    }
+}
+
+/// Prüft, ob die Ausgänge der Schaltung den Referenzausdrücken entsprechen.
+/// Eingänge und Ausgänge werden in Knotenreihenfolge den Variablen bzw. Ausdrücken zugeordnet.
+pub fn check(circuit: &Circuit, reference: &[Expr]) -> Verdict {
+    compare(circuit, Reference::Exprs(reference))
+}
+
+/// Wie `check`, aber mit einer zweiten Schaltung als Referenz, z.B. vor einer Umformung
+pub fn check_circuit(circuit: &Circuit, reference: &Circuit) -> Verdict {
+    compare(circuit, Reference::Circuit(reference))
+}
+
+fn compare(circuit: &Circuit, reference: Reference) -> Verdict {
+    let input_count = circuit.input_names().len();
+    if input_count <= EXHAUSTIVE_MAX_INPUTS {
+        exhaustive(circuit, reference)
+    } else {
+        symbolic(circuit, reference)
+    }
+}
+
+fn counterexample_for(circuit: &Circuit, reference: Reference, inputs: Vec<bool>) -> Option<Counterexample> {
+    let expected = reference.evaluate(&inputs);
+    let actual = circuit.evaluate(&inputs);
+    (expected != actual).then_some(Counterexample { inputs, expected, actual })
+}
+
+/// Belegung, bei der Treiber auf einer Sammelleitung gegeneinander arbeiten; symbolisch gesucht und daher auch
+/// für Schaltungen mit vielen Eingängen
+pub fn conflict(circuit: &Circuit) -> Option<Vec<bool>> {
+    let mut bdd = Bdd::default();
+    let (_, conflict) = circuit_bdds(&mut bdd, &circuit.flattened());
+    bdd.satisfying_assignment(conflict, circuit.input_names().len())
+}
+
+/// Wertet jede Zeile der Wahrheitstabelle aus
+fn exhaustive(circuit: &Circuit, reference: Reference) -> Verdict {
+    let input_count = circuit.input_names().len();
+    let counterexample = (0..1 << input_count).find_map(|row| counterexample_for(circuit, reference, row_inputs(row, input_count)));
+    let conflict = circuit.conflict_rows().first().map(|&row| row_inputs(row, input_count));
+    Verdict { method: Method::Exhaustive, counterexample, conflict }
+}
+
+/// Vergleicht Schaltung und Referenz als BDD
+fn symbolic(circuit: &Circuit, reference: Reference) -> Verdict {
+    let input_count = circuit.input_names().len();
+    let mut bdd = Bdd::default();
+    let (actual, conflict) = circuit_bdds(&mut bdd, &circuit.flattened());
+    let conflict = bdd.satisfying_assignment(conflict, input_count);
+    let expected = reference.bdds(&mut bdd);
+    if actual.len() != expected.len() {
+        // Bei falscher Anzahl an Ausgängen ist jede Belegung ein Gegenbeispiel
+        let counterexample = counterexample_for(circuit, reference, vec![false; input_count]);
+        return Verdict { method: Method::Bdd, counterexample, conflict };
+    }
+    let mut counterexample = None;
+    for (expected, actual) in expected.into_iter().zip(actual) {
+        let difference = bdd.xor(expected, actual);
+        if let Some(inputs) = bdd.satisfying_assignment(difference, input_count) {
+            counterexample = counterexample_for(circuit, reference, inputs);
+            break;
+        }
+    }
+    Verdict { method: Method::Bdd, counterexample, conflict }
+}
+
+#[cfg(test)]
+mod tests {
+    use egui::Pos2;
+
+    use super::*;
+    use crate::test_support::{self, Rng};
+
+    /// Ohne Konstanten, da `Circuit::from_exprs` keine kennt
+    fn random_expr(rng: &mut Rng, vars: usize, depth: usize) -> Expr {
+        test_support::random_expr(rng, vars, depth, false)
+    }
+
+    fn table(expr: &Expr, vars: usize) -> Vec<bool> {
+        (0..1 << vars).map(|row| expr.eval(&row_inputs(row, vars))).collect()
+    }
+
+    fn names(count: usize) -> Vec<String> {
+        (0..count).map(|i| ((b'A' + i as u8) as char).to_string()).collect()
+    }
+
+    #[test]
+    fn bdd_is_canonical() {
+        let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+        let mut bdd = Bdd::default();
+        let exprs: Vec<Expr> = (0..200).map(|_| random_expr(&mut rng, 4, 4)).collect();
+        let refs: Vec<BddRef> = exprs.iter().map(|e| expr_bdd(&mut bdd, e)).collect();
+        for (a, ra) in exprs.iter().zip(&refs) {
+            if let Some(inputs) = bdd.satisfying_assignment(*ra, 4) {
+                assert!(a.eval(&inputs), "Belegung {:?} erfüllt {:?} nicht", inputs, a);
+            } else {
+                assert!(table(a, 4).iter().all(|&v| !v));
+            }
+            for (b, rb) in exprs.iter().zip(&refs) {
+                assert_eq!(ra == rb, table(a, 4) == table(b, 4), "{:?} und {:?}", a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn symbolic_check_agrees_with_exhaustive() {
+        let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+        let vars = names(5);
+        for _ in 0..300 {
+            let built = random_expr(&mut rng, vars.len(), 4);
+            // Jede zweite Referenz weicht ab, sofern der Zufall nicht eine gleichwertige Funktion liefert
+            let mut exprs = vec![if rng.below(2) == 0 { built.clone() } else { random_expr(&mut rng, vars.len(), 4) }];
+            // Ab und zu fehlt ein Ausgang oder es ist einer zu viel
+            match rng.below(10) {
+                0 => exprs.clear(),
+                1 => exprs.push(random_expr(&mut rng, vars.len(), 4)),
+                _ => {}
+            }
+            let Ok(circuit) = Circuit::from_exprs(&vars, &[("Y".to_string(), built)]) else { continue };
+            let reference = Reference::Exprs(&exprs);
+            let (a, b) = (exhaustive(&circuit, reference), symbolic(&circuit, reference));
+            assert_eq!(a.counterexample.is_none(), b.counterexample.is_none(), "Referenz {:?}", exprs);
+            if let Some(counterexample) = b.counterexample {
+                assert_ne!(counterexample.expected, counterexample.actual);
+            }
+            // Dieselbe Referenz als Schaltung, wie beim Vergleich vor und nach einer Umformung
+            let outputs: Vec<(String, Expr)> = exprs.iter().enumerate().map(|(i, e)| (format!("Y{}", i), e.clone())).collect();
+            if let Ok(other) = Circuit::from_exprs(&vars, &outputs) {
+                let reference = Reference::Circuit(&other);
+                let (c, d) = (exhaustive(&circuit, reference), symbolic(&circuit, reference));
+                assert_eq!(c.counterexample.is_none(), a.counterexample.is_none(), "Referenz {:?}", exprs);
+                assert_eq!(d.counterexample.is_none(), a.counterexample.is_none(), "Referenz {:?}", exprs);
+            }
+        }
+    }
+
+    #[test]
+    fn symbolic_check_finds_bus_conflicts() {
+        let mut circuit = Circuit::default();
+        let inputs: Vec<usize> = names(4).into_iter().map(|name| circuit.add_node(NodeKind::input(name), Pos2::ZERO)).collect();
+        let drivers: Vec<usize> = [Tristate::Buffer, Tristate::Inverter]
+            .into_iter()
+            .enumerate()
+            .map(|(i, tristate)| {
+                let t = circuit.add_node(NodeKind::Tristate(tristate), Pos2::ZERO);
+                circuit.nodes[t].inputs = vec![Some(inputs[2 * i].into()), Some(inputs[2 * i + 1].into())];
+                t
+            })
+            .collect();
+        let bus = circuit.add_node(NodeKind::Tristate(Tristate::Bus(2)), Pos2::ZERO);
+        circuit.nodes[bus].inputs = drivers.iter().map(|&t| Some(t.into())).collect();
+        let y = circuit.add_node(NodeKind::output("Y"), Pos2::ZERO);
+        circuit.nodes[y].inputs[0] = Some(bus.into());
+
+        let conflicts = circuit.conflict_rows();
+        // Beide Treiber aktiv (B = D = 1) und A = C, da der zweite Treiber invertiert
+        assert_eq!(conflicts, vec![0b0101, 0b1111]);
+        let reference = Reference::Exprs(&[Expr::Const(false)]);
+        let conflict = symbolic(&circuit, reference).conflict.expect("Konflikt nicht gefunden");
+        assert!(conflicts.contains(&row_index(&conflict)));
+        assert!(exhaustive(&circuit, reference).conflict.is_some());
+        assert!(conflicts.contains(&row_index(&super::conflict(&circuit).expect("Konflikt nicht gefunden"))));
+    }
+}
@@ -0,0 +1,531 @@
+// Übungsaufgaben: Schüler bauen eine Schaltung, die automatisch mit der Musterlösung verglichen wird
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use egui::{Color32, Pos2, RichText, Ui};
+use egui_extras::{Column, TableBuilder};
+
+use crate::circuit::{Circuit, NodeKind};
+use crate::editor::CircuitEditor;
+use crate::equivalence::{self, Method, Verdict};
+use crate::export;
+use crate::expr::{self, Expr};
+use crate::{gen_bit_label, LogicGates};
+use crate::progress::{self, ProgressStore};
+use crate::worksheet;
+
+pub struct Exercise {
+    pub title: &'static str,
+    pub task: &'static str,
+    pub inputs: &'static [&'static str],
+    /// Ausgänge mit ihrer Referenzfunktion als Ausdruck über den Eingängen
+    pub outputs: &'static [(&'static str, &'static str)],
+    /// Tipps, die nacheinander aufgedeckt werden können
+    pub hints: &'static [&'static str],
+    /// Erlaubte Gatter; leer heißt, alle Gatter und Bauteile sind erlaubt
+    pub gates: &'static [LogicGates],
+}
+
+impl Exercise {
+    pub fn reference(&self) -> Vec<Expr> {
+        let mut vars: Vec<String> = self.inputs.iter().map(|s| s.to_string()).collect();
+        self.outputs
+            .iter()
+            .map(|(name, src)| {
+                let expr = expr::parse(src, &mut vars)
+                    .unwrap_or_else(|e| panic!("Referenz für {} in \"{}\" ungültig: {}", name, self.title, e));
+                assert_eq!(vars.len(), self.inputs.len(), "Referenz für {} in \"{}\" nutzt unbekannte Eingänge", name, self.title);
+                expr
+            })
+            .collect()
+    }
+
+    /// Knoten der Schaltung, die die Aufgabe nicht erlaubt
+    pub fn forbidden_nodes(&self, circuit: &Circuit) -> Vec<usize> {
+        if self.gates.is_empty() {
+            return Vec::new();
+        }
+        (0..circuit.nodes.len())
+            .filter(|&i| match &circuit.nodes[i].kind {
+                NodeKind::Gate(gate) => !self.gates.contains(gate),
+                kind => !kind.is_io(),
+            })
+            .collect()
+    }
+
+    /// Leere Schaltung, in der nur die vorgegebenen Ein- und Ausgänge platziert sind
+    pub fn new_circuit(&self) -> Circuit {
+        let mut circuit = Circuit::default();
+        for (i, name) in self.inputs.iter().enumerate() {
+            circuit.add_node(NodeKind::input(*name), Pos2::new(40.0, 30.0 + 40.0 * i as f32));
+        }
+        for (i, (name, _)) in self.outputs.iter().enumerate() {
+            circuit.add_node(NodeKind::output(*name), Pos2::new(460.0, 30.0 + 60.0 * i as f32));
+        }
+        circuit
+    }
+}
+
+pub struct ExercisePack {
+    pub name: &'static str,
+    pub exercises: &'static [Exercise],
+}
+
+pub static PACKS: &[ExercisePack] = &[
+    ExercisePack {
+        name: "Grundlagen",
+        exercises: &[
+            Exercise {
+                title: "UND aus NAND",
+                task: "Baue ein UND-Gatter ausschließlich aus NAND-Gattern.",
+                inputs: &["A", "B"],
+                outputs: &[("Y", "A & B")],
+                hints: &["Ein NAND-Gatter, dessen Eingänge verbunden sind, wirkt wie ein NICHT-Gatter.", "Negiere den Ausgang eines NAND-Gatters."],
+                gates: &[LogicGates::NAND],
+            },
+            Exercise {
+                title: "Antivalenz",
+                task: "Baue ein XOR-Gatter aus UND-, ODER- und NICHT-Gattern.",
+                inputs: &["A", "B"],
+                outputs: &[("Y", "A ^ B")],
+                hints: &["A XOR B ist High, wenn genau einer der Eingänge High ist.", "Y = (A UND NICHT B) ODER (NICHT A UND B)"],
+                gates: &[LogicGates::AND, LogicGates::OR, LogicGates::NOT],
+            },
+            Exercise {
+                title: "Mehrheitsentscheid",
+                task: "Der Ausgang soll High sein, wenn mindestens zwei der drei Eingänge High sind.",
+                inputs: &["A", "B", "C"],
+                outputs: &[("Y", "A & B | A & C | B & C")],
+                hints: &["Betrachte jedes Paar von Eingängen einzeln.", "Y = (A UND B) ODER (A UND C) ODER (B UND C)"],
+                gates: &[],
+            },
+            Exercise {
+                title: "Halbaddierer",
+                task: "Addiere die Bits A und B. S ist die Summe, C der Übertrag.",
+                inputs: &["A", "B"],
+                outputs: &[("S", "A ^ B"), ("C", "A & B")],
+                hints: &["Die Summe ist High, wenn genau eines der Bits High ist.", "Ein Übertrag entsteht nur, wenn beide Bits High sind."],
+                gates: &[],
+            },
+            Exercise {
+                title: "Volladdierer",
+                task: "Addiere die Bits A, B und den eingehenden Übertrag Cin zur Summe S und zum Übertrag Cout.",
+                inputs: &["A", "B", "Cin"],
+                outputs: &[("S", "A ^ B ^ Cin"), ("Cout", "A & B | Cin & (A ^ B)")],
+                hints: &["Ein Volladdierer lässt sich aus zwei Halbaddierern aufbauen.", "Cout ist High, wenn einer der beiden Halbaddierer einen Übertrag liefert."],
+                gates: &[],
+            },
+        ],
+    },
+    ExercisePack {
+        name: "Fortgeschritten",
+        exercises: &[
+            Exercise {
+                title: "2:1-Multiplexer",
+                task: "Ist S Low, soll Y den Pegel von A haben, sonst den von B.",
+                inputs: &["S", "A", "B"],
+                outputs: &[("Y", "!S & A | S & B")],
+                hints: &["Gib A mit NICHT S und B mit S frei.", "Y = (NICHT S UND A) ODER (S UND B)"],
+                gates: &[],
+            },
+            Exercise {
+                title: "2-Bit-Vergleicher",
+                task: "Y soll High sein, wenn die Zahlen A1A0 und B1B0 gleich sind.",
+                inputs: &["A1", "A0", "B1", "B0"],
+                outputs: &[("Y", "!(A1 ^ B1) & !(A0 ^ B0)")],
+                hints: &["Zwei Bits sind gleich, wenn ihr XNOR High ist.", "Verknüpfe die beiden Bitvergleiche mit UND."],
+                gates: &[],
+            },
+            Exercise {
+                title: "Paritätsbit über 10 Bit",
+                task: "P soll High sein, wenn eine ungerade Anzahl der Eingänge D0 bis D9 High ist.",
+                inputs: &["D0", "D1", "D2", "D3", "D4", "D5", "D6", "D7", "D8", "D9"],
+                outputs: &[("P", "D0 ^ D1 ^ D2 ^ D3 ^ D4 ^ D5 ^ D6 ^ D7 ^ D8 ^ D9")],
+                hints: &["XOR liefert die Parität von zwei Bits.", "Verkette neun XOR-Gatter hintereinander."],
+                gates: &[],
+            },
+        ],
+    },
+];
+
+#[derive(Default)]
+pub struct ExerciseView {
+    selected: (usize, usize),
+    editors: HashMap<(usize, usize), CircuitEditor>,
+    /// Ergebnis der letzten Prüfung; ein Fehler heißt, die Schaltung nutzt nicht erlaubte Bauteile
+    verdicts: HashMap<(usize, usize), Result<Verdict, String>>,
+    scroll_to_counterexample: bool,
+    /// Name des angemeldeten Schülers, leer solange sich niemand angemeldet hat
+    student: String,
+    name_input: String,
+    progress: ProgressStore,
+    last_frame_time: Option<f64>,
+    show_overview: bool,
+    status: Option<String>,
+}
+
+impl ExerciseView {
+    pub fn new() -> Self {
+        Self { progress: ProgressStore::load(&progress::progress_file()), ..Default::default() }
+    }
+
+    pub fn save(&mut self) {
+        if let Err(e) = self.progress.save(&progress::progress_file()) {
+            self.status = Some(format!("Fortschritt konnte nicht gespeichert werden: {}", e));
+        }
+    }
+
+    fn current_progress(&mut self) -> &mut progress::ExerciseProgress {
+        let (p, e) = self.selected;
+        self.progress.exercise_mut(&self.student, PACKS[p].name, PACKS[p].exercises[e].title)
+    }
+
+    /// Schaltungen und Prüfergebnisse gehören dem angemeldeten Schüler; der nächste soll sie weder sehen noch als
+    /// eigenen Versuch prüfen können
+    fn clear_work(&mut self) {
+        self.editors.clear();
+        self.verdicts.clear();
+        self.last_frame_time = None;
+    }
+
+    /// Arbeitsblatt und Lösungsblatt als getrennte Dateien
+    fn export_worksheet(&mut self, pack: &ExercisePack) {
+        let files = [worksheet::file_name("arbeitsblatt", pack), worksheet::file_name("loesungen", pack)];
+        let result = files.iter().zip([false, true]).try_for_each(|(file, answers)| std::fs::write(file, worksheet::html(pack, answers)));
+        self.status = Some(match result {
+            Ok(_) => format!("Exportiert nach {} und {}", files[0], files[1]),
+            Err(e) => format!("Export des Arbeitsblatts fehlgeschlagen: {}", e),
+        });
+    }
+
+    fn check(&mut self) {
+        let (p, e) = self.selected;
+        let exercise = &PACKS[p].exercises[e];
+        let editor = self.editors.get_mut(&self.selected).unwrap();
+        editor.highlighted.clear();
+        let forbidden = exercise.forbidden_nodes(&editor.circuit);
+        let verdict = if forbidden.is_empty() {
+            Ok(equivalence::check(&editor.circuit, &exercise.reference()))
+        } else {
+            editor.highlighted = forbidden;
+            let gates: Vec<String> = exercise.gates.iter().map(|gate| gate.to_string()).collect();
+            Err(format!("Erlaubt sind nur {}-Gatter. Die übrigen Bauteile sind markiert.", gates.join("-, ")))
+        };
+        let correct = verdict.as_ref().is_ok_and(Verdict::correct);
+        if let Some(conflict) = verdict.as_ref().ok().and_then(|verdict| verdict.conflict.as_ref()) {
+            // Belegung mit dem Konflikt einstellen, damit der Editor die Sammelleitung mit X zeigt
+            editor.circuit.set_input_values(conflict);
+        }
+        if let Some(counterexample) = verdict.as_ref().ok().and_then(|verdict| verdict.counterexample.as_ref()) {
+            // Gegenbeispiel direkt im Schaltplan einstellen und falsche Ausgänge markieren
+            editor.circuit.set_input_values(&counterexample.inputs);
+            let outputs = editor.circuit.output_bits();
+            for (i, (node, _)) in outputs.into_iter().enumerate() {
+                if counterexample.expected.get(i) != counterexample.actual.get(i) && !editor.highlighted.contains(&node) {
+                    editor.highlighted.push(node);
+                }
+            }
+            self.scroll_to_counterexample = true;
+        }
+        self.verdicts.insert(self.selected, verdict);
+        let progress = self.current_progress();
+        progress.attempts += 1;
+        progress.solved |= correct;
+        self.save();
+    }
+
+    fn show_result(&self, ui: &mut Ui, exercise: &Exercise) {
+        let verdict = match self.verdicts.get(&self.selected) {
+            None => {
+                ui.label("Noch nicht geprüft.");
+                return;
+            }
+            Some(Err(e)) => {
+                ui.label(RichText::new(format!("Leider falsch. {}", e)).color(Color32::RED));
+                return;
+            }
+            Some(Ok(verdict)) => verdict,
+        };
+        let assignment = |inputs: &[bool]| {
+            let values: Vec<String> =
+                exercise.inputs.iter().zip(inputs).map(|(name, value)| format!("{} = {}", name, *value as u8)).collect();
+            values.join(", ")
+        };
+        match (&verdict.counterexample, &verdict.conflict) {
+            (None, None) => {
+                ui.label(RichText::new("Richtig! Die Schaltung erfüllt die Aufgabe.").color(Color32::GREEN));
+            }
+            (None, Some(conflict)) => {
+                ui.label(
+                    RichText::new(format!(
+                        "Leider falsch. Bei {} arbeiten Treiber auf einer Sammelleitung gegeneinander, der Pegel ist unbestimmt (X).",
+                        assignment(conflict)
+                    ))
+                    .color(Color32::RED),
+                );
+            }
+            (Some(counterexample), _) if counterexample.actual.len() != counterexample.expected.len() => {
+                ui.label(
+                    RichText::new(format!(
+                        "Leider falsch. Die Schaltung hat {} Ausgangsbits, die Aufgabe verlangt {}.",
+                        counterexample.actual.len(),
+                        counterexample.expected.len()
+                    ))
+                    .color(Color32::RED),
+                );
+            }
+            (Some(counterexample), _) => {
+                ui.label(RichText::new("Leider falsch. Gegenbeispiel:").color(Color32::RED));
+                ui.label(assignment(&counterexample.inputs));
+                for (i, (name, _)) in exercise.outputs.iter().enumerate() {
+                    if counterexample.expected[i] != counterexample.actual[i] {
+                        ui.label(format!(
+                            "{} müsste {} sein, ist aber {}.",
+                            name, counterexample.expected[i] as u8, counterexample.actual[i] as u8
+                        ));
+                    }
+                }
+            }
+        }
+        ui.small(match verdict.method {
+            Method::Exhaustive => format!(
+                "Geprüft durch Auswerten aller {} Zeilen der Wahrheitstabelle.",
+                1usize << exercise.inputs.len()
+            ),
+            Method::Bdd => format!(
+                "Geprüft durch symbolischen Vergleich (BDD), da die Aufgabe mehr als {} Eingänge hat.",
+                equivalence::EXHAUSTIVE_MAX_INPUTS
+            ),
+        });
+    }
+
+    fn show_truth_table(&mut self, ui: &mut Ui, exercise: &Exercise) {
+        let reference = exercise.reference();
+        let circuit = &self.editors[&self.selected].circuit;
+        let highlight_row = self.verdicts.get(&self.selected)
+            .and_then(|v| v.as_ref().ok())
+            .and_then(|v| v.counterexample.as_ref())
+            .map(|c| equivalence::row_index(&c.inputs));
+        let input_count = exercise.inputs.len();
+        let mut table = TableBuilder::new(ui)
+            .striped(true)
+            .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
+            .columns(Column::auto().at_least(24.0), input_count + 2 * exercise.outputs.len());
+        if let (Some(row), true) = (highlight_row, self.scroll_to_counterexample) {
+            table = table.scroll_to_row(row, Some(egui::Align::Center));
+            self.scroll_to_counterexample = false;
+        }
+        table
+            .header(20.0, |mut header| {
+                for name in exercise.inputs {
+                    header.col(|ui| {
+                        ui.strong(*name);
+                    });
+                }
+                for (name, _) in exercise.outputs {
+                    header.col(|ui| {
+                        ui.strong(format!("{} soll", name));
+                    });
+                    header.col(|ui| {
+                        ui.strong(format!("{} ist", name));
+                    });
+                }
+            })
+            .body(|body| {
+                body.rows(18.0, 1 << input_count, |row_idx, mut row| {
+                    let inputs = equivalence::row_inputs(row_idx, input_count);
+                    let actual = circuit.evaluate(&inputs);
+                    let mut cell = |value: bool| {
+                        row.col(|ui| {
+                            if highlight_row == Some(row_idx) {
+                                ui.painter().rect_filled(ui.max_rect().expand(2.0), 0.0, Color32::from_rgb(90, 80, 0));
+                            }
+                            ui.add(gen_bit_label(value));
+                        });
+                    };
+                    for value in &inputs {
+                        cell(*value);
+                    }
+                    for (expr, actual) in reference.iter().zip(actual) {
+                        cell(expr.eval(&inputs));
+                        cell(actual);
+                    }
+                });
+            });
+    }
+
+    fn show_hints(&mut self, ui: &mut Ui, exercise: &Exercise) {
+        let used = self.current_progress().hints_used as usize;
+        for hint in &exercise.hints[..used.min(exercise.hints.len())] {
+            ui.label(format!("💡 {}", hint));
+        }
+        let button = egui::Button::new(format!("Tipp anzeigen ({}/{})", used, exercise.hints.len()));
+        if ui.add_enabled(used < exercise.hints.len(), button).clicked() {
+            self.current_progress().hints_used += 1;
+            self.save();
+        }
+    }
+
+    fn show_overview(&mut self, ui: &mut Ui) {
+        if self.progress.students.is_empty() {
+            ui.label("Es wurden noch keine Ergebnisse gespeichert.");
+        } else {
+            egui::ScrollArea::both().max_height(400.0).show(ui, |ui| {
+                egui::Grid::new("overview").striped(true).show(ui, |ui| {
+                    ui.strong("Name");
+                    for pack in PACKS {
+                        for exercise in pack.exercises {
+                            ui.strong(exercise.title);
+                        }
+                    }
+                    ui.end_row();
+                    for student in &self.progress.students {
+                        ui.label(&student.name);
+                        for pack in PACKS {
+                            for exercise in pack.exercises {
+                                match self.progress.exercise(&student.name, pack.name, exercise.title) {
+                                    Some(e) => {
+                                        let text = format!(
+                                            "{} {}× {} 💡{}",
+                                            if e.solved { "✔" } else { "✖" },
+                                            e.attempts,
+                                            progress::format_duration(e.seconds),
+                                            e.hints_used
+                                        );
+                                        ui.label(RichText::new(text).color(if e.solved { Color32::GREEN } else { Color32::RED }));
+                                    }
+                                    None => {
+                                        ui.label("–");
+                                    }
+                                }
+                            }
+                        }
+                        ui.end_row();
+                    }
+                });
+            });
+        }
+        ui.small("✔/✖ gelöst, Anzahl der Versuche, Bearbeitungszeit, genutzte Tipps");
+        ui.horizontal(|ui| {
+            if ui.button("Als CSV exportieren").clicked() {
+                self.status = Some(export::write_file("ergebnisse.csv", Ok(self.progress.to_csv())));
+            }
+            if ui.button("Als JSON exportieren").clicked() {
+                self.status = Some(export::write_file("ergebnisse.json", Ok(self.progress.to_json())));
+            }
+        });
+        ui.separator();
+        ui.horizontal(|ui| {
+            ui.label("Arbeitsblatt mit Lösungen:");
+            for pack in PACKS {
+                if ui.button(pack.name).clicked() {
+                    self.export_worksheet(pack);
+                }
+            }
+        });
+        ui.small("Die HTML-Dateien lassen sich im Browser drucken oder als PDF speichern.");
+    }
+
+    fn show_login(&mut self, ui: &mut Ui) {
+        ui.heading("Übungen");
+        ui.label("Gib deinen Namen ein, damit dein Fortschritt gespeichert werden kann.");
+        ui.horizontal(|ui| {
+            let response = ui.text_edit_singleline(&mut self.name_input);
+            let entered = response.lost_focus() && ui.input().key_pressed(egui::Key::Enter);
+            if (ui.button("Anmelden").clicked() || entered) && !self.name_input.trim().is_empty() {
+                self.clear_work();
+                self.student = self.name_input.trim().to_string();
+            }
+        });
+    }
+
+    pub fn show(&mut self, ui: &mut Ui) {
+        let mut show_overview = self.show_overview;
+        egui::Window::new("Ergebnisübersicht")
+            .open(&mut show_overview)
+            .show(ui.ctx(), |ui| self.show_overview(ui));
+        self.show_overview = show_overview;
+
+        if self.student.is_empty() {
+            self.show_login(ui);
+            return;
+        }
+
+        // Bearbeitungszeit der offenen Aufgabe mitzählen, solange sie nicht gelöst ist.
+        // Lange Pausen (z.B. wenn die Ansicht nicht sichtbar war) werden nicht angerechnet.
+        let now = ui.input().time;
+        if let Some(last) = self.last_frame_time.replace(now) {
+            let progress = self.current_progress();
+            if !progress.solved {
+                progress.seconds += (now - last).min(5.0);
+            }
+        }
+        ui.ctx().request_repaint_after(Duration::from_secs(1));
+
+        let previous = self.selected;
+        egui::SidePanel::left("exercise_list").resizable(false).show_inside(ui, |ui| {
+            ui.horizontal(|ui| {
+                ui.label(format!("Angemeldet: {}", self.student));
+                if ui.small_button("Abmelden").clicked() {
+                    self.save();
+                    self.student.clear();
+                    self.clear_work();
+                }
+            });
+            ui.separator();
+            for (p, pack) in PACKS.iter().enumerate() {
+                ui.strong(pack.name);
+                for (e, exercise) in pack.exercises.iter().enumerate() {
+                    let solved = self.progress.exercise(&self.student, pack.name, exercise.title).is_some_and(|e| e.solved);
+                    let text = format!("{} {}", if solved { "✔" } else { "  " }, exercise.title);
+                    ui.selectable_value(&mut self.selected, (p, e), text);
+                }
+                ui.add_space(6.0);
+            }
+            ui.separator();
+            if ui.button("Ergebnisübersicht").clicked() {
+                self.show_overview = true;
+            }
+            if let Some(status) = &self.status {
+                ui.small(status);
+            }
+        });
+        if self.selected != previous {
+            self.save();
+        }
+        if self.student.is_empty() {
+            return;
+        }
+
+        let (p, e) = self.selected;
+        let exercise = &PACKS[p].exercises[e];
+        self.editors.entry(self.selected).or_insert_with(|| CircuitEditor::new(exercise.new_circuit(), true));
+
+        egui::SidePanel::right("exercise_result").min_width(220.0).show_inside(ui, |ui| {
+            if ui.button("Prüfen").clicked() {
+                self.check();
+            }
+            self.show_result(ui, exercise);
+            let progress = self.current_progress();
+            ui.small(format!(
+                "Versuche: {} · Zeit: {}",
+                progress.attempts,
+                progress::format_duration(progress.seconds)
+            ));
+            ui.separator();
+            self.show_hints(ui, exercise);
+            ui.separator();
+            ui.label(RichText::new("Wahrheitstabelle").color(ui.visuals().strong_text_color()));
+            self.show_truth_table(ui, exercise);
+        });
+
+        egui::CentralPanel::default().show_inside(ui, |ui| {
+            ui.heading(exercise.title);
+            ui.label(exercise.task);
+            if self.editors.get_mut(&self.selected).unwrap().show(ui) {
+                self.verdicts.remove(&self.selected);
+            }
+        });
+    }
+}
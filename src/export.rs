@@ -0,0 +1,312 @@
+// Export des Schaltplans ohne Bildschirmfoto: als SVG und PNG mit denselben Schaltzeichen wie im Editor
+// (DIN EN 60617) und als CircuitTikZ-Quelltext mit den europäischen Gattersymbolen des Pakets.
+// SVG und PNG entstehen aus den Formen, die der Editor zeichnet; dazu läuft ein eigener egui-Kontext ohne Fenster.
+
+use egui::epaint::{ClippedShape, ImageData, Primitive, Shape};
+use egui::{Color32, FontFamily, Pos2, Rect, Vec2};
+use image::ImageEncoder;
+
+use crate::circuit::{Circuit, NodeKind, Source};
+use crate::editor::{input_pin, node_rect, output_pin, paint_schematic, schematic_size, wire_points};
+use crate::latex;
+use crate::text_table::html_escape;
+use crate::level::Tristate;
+use crate::LogicGates;
+
+/// Bildpunkte des Editors je Zentimeter in CircuitTikZ
+const TIKZ_SCALE: f32 = 40.0;
+
+/// Formen des Schaltplans auf weißem Hintergrund, wie der Editor sie zeichnet. `scale` ist die Zahl der Pixel je
+/// Bildpunkt des Editors; davon hängt ab, wie fein die Schrift gerastert wird.
+fn shapes(circuit: &Circuit, scale: f32) -> (Vec2, egui::Context, egui::FullOutput) {
+    let size = schematic_size(circuit);
+    let ctx = egui::Context::default();
+    ctx.set_visuals(egui::Visuals::light());
+    let raw = egui::RawInput {
+        screen_rect: Some(Rect::from_min_size(Pos2::ZERO, size)),
+        pixels_per_point: Some(scale),
+        ..Default::default()
+    };
+    let output = ctx.run(raw, |ctx| {
+        egui::CentralPanel::default().frame(egui::Frame::none()).show(ctx, |ui| {
+            paint_schematic(ui, ui.painter(), Rect::from_min_size(Pos2::ZERO, size), circuit);
+        });
+    });
+    (size, ctx, output)
+}
+
+/// Farbe und Deckkraft als SVG-Attribute; egui speichert Farben mit vormultipliziertem Alpha
+fn svg_paint(attribute: &str, color: Color32) -> String {
+    let [r, g, b, a] = color.to_srgba_unmultiplied();
+    if a == 0 {
+        return format!("{}=\"none\"", attribute);
+    }
+    format!("{}=\"rgb({},{},{})\" {}-opacity=\"{:.2}\"", attribute, r, g, b, attribute, a as f32 / 255.0)
+}
+
+fn svg_stroke(stroke: egui::Stroke) -> String {
+    if stroke.is_empty() {
+        return "stroke=\"none\"".to_string();
+    }
+    format!("{} stroke-width=\"{}\"", svg_paint("stroke", stroke.color), stroke.width)
+}
+
+fn svg_shape(shape: &Shape, out: &mut String) {
+    match shape {
+        Shape::Vec(shapes) => shapes.iter().for_each(|shape| svg_shape(shape, out)),
+        Shape::Circle(c) => {
+            *out += &format!(
+                "<circle cx=\"{}\" cy=\"{}\" r=\"{}\" {} {}/>\n",
+                c.center.x, c.center.y, c.radius, svg_paint("fill", c.fill), svg_stroke(c.stroke)
+            );
+        }
+        Shape::LineSegment { points: [a, b], stroke } => {
+            *out += &format!("<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" {}/>\n", a.x, a.y, b.x, b.y, svg_stroke(*stroke));
+        }
+        Shape::Path(path) => {
+            let points: Vec<String> = path.points.iter().map(|p| format!("{},{}", p.x, p.y)).collect();
+            let element = if path.closed { "polygon" } else { "polyline" };
+            let fill = if path.closed { svg_paint("fill", path.fill) } else { "fill=\"none\"".to_string() };
+            *out += &format!(
+                "<{} points=\"{}\" {} {} stroke-linejoin=\"round\"/>\n",
+                element, points.join(" "), fill, svg_stroke(path.stroke)
+            );
+        }
+        Shape::Rect(r) => {
+            *out += &format!(
+                "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" rx=\"{}\" {} {}/>\n",
+                r.rect.min.x, r.rect.min.y, r.rect.width(), r.rect.height(), r.rounding.nw, svg_paint("fill", r.fill), svg_stroke(r.stroke)
+            );
+        }
+        Shape::Text(text) => {
+            let Some(section) = text.galley.job.sections.first() else { return };
+            let family = if section.format.font_id.family == FontFamily::Monospace { "monospace" } else { "sans-serif" };
+            let color = text.override_text_color.unwrap_or(section.format.color);
+            for row in &text.galley.rows {
+                let line: String = row.glyphs.iter().map(|glyph| glyph.chr).collect();
+                let pos = text.pos + row.rect.left_center().to_vec2();
+                *out += &format!(
+                    "<text x=\"{}\" y=\"{}\" font-family=\"{}\" font-size=\"{}\" dominant-baseline=\"central\" {}>{}</text>\n",
+                    pos.x, pos.y, family, section.format.font_id.size, svg_paint("fill", color), html_escape(&line)
+                );
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Schaltplan als SVG; `scale` vergrößert die angegebene Bildgröße, die Zeichnung selbst bleibt eine Vektorgrafik
+pub fn svg(circuit: &Circuit, scale: f32) -> String {
+    let (size, _, output) = shapes(circuit, 1.0);
+    let mut text = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\">\n",
+        size.x * scale, size.y * scale, size.x, size.y
+    );
+    for ClippedShape(_, shape) in &output.shapes {
+        svg_shape(shape, &mut text);
+    }
+    text + "</svg>\n"
+}
+
+/// Pixel eines Bildes mit vormultipliziertem Alpha
+struct Canvas {
+    width: usize,
+    height: usize,
+    pixels: Vec<[f32; 4]>,
+}
+
+impl Canvas {
+    /// Füllt ein Dreieck; Farbe und Texturkoordinaten werden zwischen den Ecken interpoliert
+    fn triangle(&mut self, vertices: [&egui::epaint::Vertex; 3], scale: f32, clip: Rect, texture: &(usize, usize, Vec<Color32>)) {
+        let p: Vec<Pos2> = vertices.iter().map(|v| (v.pos.to_vec2() * scale).to_pos2()).collect();
+        let area = (p[1] - p[0]).x * (p[2] - p[0]).y - (p[1] - p[0]).y * (p[2] - p[0]).x;
+        if area.abs() < 1e-6 {
+            return;
+        }
+        let clip = Rect::from_min_max((clip.min.to_vec2() * scale).to_pos2(), (clip.max.to_vec2() * scale).to_pos2());
+        let bounds = Rect::from_points(&p).intersect(clip).intersect(Rect::from_min_size(Pos2::ZERO, Vec2::new(self.width as f32, self.height as f32)));
+        if !bounds.is_positive() {
+            return;
+        }
+        let (tex_width, tex_height, tex) = texture;
+        for y in bounds.min.y.floor() as usize..bounds.max.y.ceil() as usize {
+            for x in bounds.min.x.floor() as usize..bounds.max.x.ceil() as usize {
+                let c = Pos2::new(x as f32 + 0.5, y as f32 + 0.5);
+                let edge = |a: Pos2, b: Pos2| ((b - a).x * (c - a).y - (b - a).y * (c - a).x) / area;
+                let w = [edge(p[1], p[2]), edge(p[2], p[0]), edge(p[0], p[1])];
+                if w.iter().any(|&w| w < 0.0) || x >= self.width || y >= self.height {
+                    continue;
+                }
+                let uv = vertices.iter().zip(w).fold(Vec2::ZERO, |uv, (v, w)| uv + v.uv.to_vec2() * w);
+                let u = ((uv.x * *tex_width as f32) as usize).min(tex_width - 1);
+                let v = ((uv.y * *tex_height as f32) as usize).min(tex_height - 1);
+                let texel = tex[v * tex_width + u];
+                let dst = &mut self.pixels[y * self.width + x];
+                let alpha = (0..3).fold(0.0, |sum, k| sum + vertices[k].color.a() as f32 * w[k]) / 255.0 * texel.a() as f32 / 255.0;
+                for (channel, value) in dst.iter_mut().enumerate() {
+                    let source = match channel {
+                        3 => alpha,
+                        _ => {
+                            let color = (0..3).fold(0.0, |sum, k| sum + vertices[k].color[channel] as f32 * w[k]) / 255.0;
+                            color * texel[channel] as f32 / 255.0
+                        }
+                    };
+                    *value = source + *value * (1.0 - alpha);
+                }
+            }
+        }
+    }
+}
+
+/// Schaltplan als PNG mit `scale` Pixeln je Bildpunkt des Editors
+pub fn png(circuit: &Circuit, scale: f32) -> Result<Vec<u8>, String> {
+    let (size, ctx, output) = shapes(circuit, scale);
+    // Die Schrift liegt in einer Textur, die der erste Frame vollständig mitliefert
+    let texture = output
+        .textures_delta
+        .set
+        .iter()
+        .find_map(|(_, delta)| match &delta.image {
+            ImageData::Font(font) if delta.pos.is_none() => Some((font.size[0], font.size[1], font.srgba_pixels(None).collect())),
+            _ => None,
+        })
+        .ok_or("Die Schrift konnte nicht geladen werden.")?;
+    let mut canvas = Canvas {
+        width: (size.x * scale).round() as usize,
+        height: (size.y * scale).round() as usize,
+        pixels: Vec::new(),
+    };
+    canvas.pixels = vec![[1.0; 4]; canvas.width * canvas.height];
+    for primitive in ctx.tessellate(output.shapes) {
+        if let Primitive::Mesh(mesh) = &primitive.primitive {
+            for triangle in mesh.indices.chunks_exact(3) {
+                let vertices = [0, 1, 2].map(|k| &mesh.vertices[triangle[k] as usize]);
+                canvas.triangle(vertices, scale, primitive.clip_rect, &texture);
+            }
+        }
+    }
+    let bytes: Vec<u8> = canvas.pixels.iter().flat_map(|p| p[..3].iter().map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8)).collect();
+    let mut png = Vec::new();
+    image::codecs::png::PngEncoder::new(&mut png)
+        .write_image(&bytes, canvas.width as u32, canvas.height as u32, image::ColorType::Rgb8)
+        .map_err(|e| e.to_string())?;
+    Ok(png)
+}
+
+fn tikz_point(pos: Pos2) -> String {
+    format!("({:.2},{:.2})", pos.x / TIKZ_SCALE, -pos.y / TIKZ_SCALE)
+}
+
+/// Gattersymbol in CircuitTikZ; `european` entspricht den Schaltzeichen des Editors
+fn tikz_port(gate: LogicGates) -> &'static str {
+    match gate {
+        LogicGates::AND => "european and port",
+        LogicGates::OR => "european or port",
+        LogicGates::XOR => "european xor port",
+        LogicGates::NOT => "european not port",
+        LogicGates::NAND => "european nand port",
+        LogicGates::NOR => "european nor port",
+        LogicGates::XNOR => "european xnor port",
+    }
+}
+
+/// Schaltplan als CircuitTikZ-Quelltext für die Umgebung `circuitikz`. Gatter werden als Symbole des Pakets gesetzt,
+/// alle anderen Bauteile als Kästen; Leitungen folgen mit allen Knickpunkten dem Verlauf im Editor.
+pub fn circuitikz(circuit: &Circuit) -> String {
+    let mut text = String::new();
+    // Anschlüsse als TikZ-Koordinaten: bei Gattern die Anker des Symbols, sonst die Stelle aus dem Editor
+    let input_anchor = |node: usize, pin: usize| match circuit.nodes[node].kind {
+        NodeKind::Gate(LogicGates::NOT) => format!("(n{}.in)", node),
+        NodeKind::Gate(_) => format!("(n{}.in {})", node, pin + 1),
+        _ => tikz_point(input_pin(&circuit.nodes[node], pin)),
+    };
+    let output_anchor = |src: Source| match circuit.nodes[src.node].kind {
+        NodeKind::Gate(_) => format!("(n{}.out)", src.node),
+        _ => tikz_point(output_pin(&circuit.nodes[src.node], src.pin).unwrap()),
+    };
+    for (i, node) in circuit.nodes.iter().enumerate() {
+        let rect = node_rect(node);
+        match &node.kind {
+            NodeKind::Gate(gate) => text += &format!("  \\draw {} node[{}] (n{}) {{}};\n", tikz_point(node.pos), tikz_port(*gate), i),
+            // Ein- und Ausgänge als Anschlusspunkte mit Namen
+            NodeKind::Input { name, .. } => {
+                text += &format!("  \\draw {} node[ocirc] {{}} node[left] {{{}}};\n", tikz_point(output_pin(node, 0).unwrap()), latex::escape(name));
+            }
+            NodeKind::Output { name, .. } => {
+                text += &format!("  \\draw {} node[ocirc] {{}} node[right] {{{}}};\n", tikz_point(input_pin(node, 0)), latex::escape(name));
+            }
+            NodeKind::Tristate(Tristate::Bus(_)) => {
+                text += &format!("  \\fill {} rectangle {};\n", tikz_point(rect.left_top()), tikz_point(rect.right_bottom()));
+            }
+            kind => {
+                let symbol = match kind {
+                    NodeKind::Component(component) => component.symbol().to_string(),
+                    _ => "1".to_string(),
+                };
+                text += &format!("  \\draw {} rectangle {};\n", tikz_point(rect.left_top()), tikz_point(rect.right_bottom()));
+                text += &format!("  \\node[below] at {} {{{}}};\n", tikz_point(rect.center_top()), latex::escape(&symbol));
+                let mut pin = 0;
+                for port in kind.input_ports() {
+                    text += &format!("  \\node[right, font=\\tiny] at {} {{{}}};\n", tikz_point(input_pin(node, pin)), latex::escape(&port.name));
+                    pin += port.width;
+                }
+                pin = 0;
+                for port in kind.output_ports() {
+                    let pos = output_pin(node, pin).unwrap();
+                    text += &format!("  \\node[left, font=\\tiny] at {} {{{}}};\n", tikz_point(pos), latex::escape(&port.name));
+                    pin += port.width;
+                }
+            }
+        }
+    }
+    for (i, node) in circuit.nodes.iter().enumerate() {
+        for (pin, src) in node.inputs.iter().enumerate() {
+            let Some(src) = *src else { continue };
+            let (from, to) = (output_pin(&circuit.nodes[src.node], src.pin).unwrap(), input_pin(node, pin));
+            let route = node.routes.get(pin).map_or(&[][..], |r| &r[..]);
+            let (start, end) = (output_anchor(src), input_anchor(i, pin));
+            // Knickpunkte auf der Höhe eines Anschlusses übernehmen dessen Höhe, da die Anker der Gattersymbole
+            // etwas anders liegen als die Anschlüsse im Editor
+            let aligned = |anchor: &str, x: f32| format!("({} -| {:.2},0)", &anchor[1..anchor.len() - 1], x / TIKZ_SCALE);
+            let mut path = vec![start.clone()];
+            let points = wire_points(from, to, route);
+            for &p in &points[1..points.len() - 1] {
+                path.push(if (p.y - from.y).abs() < 0.5 {
+                    aligned(&start, p.x)
+                } else if (p.y - to.y).abs() < 0.5 {
+                    aligned(&end, p.x)
+                } else {
+                    tikz_point(p)
+                });
+            }
+            path.push(end);
+            text += &format!("  \\draw {};\n", path.join(" -- "));
+        }
+    }
+    format!("\\begin{{circuitikz}}\n{}\\end{{circuitikz}}\n", text)
+}
+
+/// Vollständiges Dokument mit dem Schaltplan
+pub fn circuitikz_document(circuit: &Circuit) -> String {
+    latex::document(&["circuitikz"], &circuitikz(circuit))
+}
+
+/// Dateiname ohne Leer- und Sonderzeichen aus einem frei gewählten Namen, z.B. `dokument-1.svg` für den Tab
+/// „Dokument 1“; ohne Buchstaben oder Ziffern im Namen `schaltung.svg`
+pub fn file_name(name: &str, extension: &str) -> String {
+    let name: String = name.trim().to_lowercase().chars().map(|c| if c.is_alphanumeric() { c } else { '-' }).collect();
+    let name = if name.chars().any(char::is_alphanumeric) { name.as_str() } else { "schaltung" };
+    format!("{}.{}", name, extension)
+}
+
+/// Schreibt eine exportierte Datei ins Arbeitsverzeichnis und liefert die Meldung für die Statuszeile. Ein Fehler
+/// in `content`, z.B. beim Erzeugen eines Bildes, wird wie ein Schreibfehler gemeldet. Ersetzt der Export eine
+/// vorhandene Datei, sagt die Meldung das, denn der vorige Export ist damit verloren.
+pub fn write_file(file: &str, content: Result<impl AsRef<[u8]>, String>) -> String {
+    let replaced = std::path::Path::new(file).exists();
+    match content.and_then(|content| std::fs::write(file, content).map_err(|e| e.to_string())) {
+        Ok(()) if replaced => format!("Exportiert nach {}, die vorhandene Datei wurde ersetzt", file),
+        Ok(()) => format!("Exportiert nach {}", file),
+        Err(e) => format!("Export nach {} fehlgeschlagen: {}", file, e),
+    }
+}
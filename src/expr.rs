@@ -0,0 +1,303 @@
+// Boolesche Ausdrücke: Syntaxbaum, Parser und Auswertung
+
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Expr {
+    Const(bool),
+    /// Index in die Variablenliste, mit der der Ausdruck geparst wurde
+    Var(usize),
+    Not(Box<Expr>),
+    And(Vec<Expr>),
+    Or(Vec<Expr>),
+    Xor(Box<Expr>, Box<Expr>),
+}
+
+impl Expr {
+    pub fn eval(&self, vals: &[bool]) -> bool {
+        match self {
+            Expr::Const(c) => *c,
+            Expr::Var(i) => vals[*i],
+            Expr::Not(e) => !e.eval(vals),
+            Expr::And(es) => es.iter().all(|e| e.eval(vals)),
+            Expr::Or(es) => es.iter().any(|e| e.eval(vals)),
+            Expr::Xor(a, b) => a.eval(vals) ^ b.eval(vals),
+        }
+    }
+
+    /// Ersetzt jeden Variablenindex `i` durch `map[i]`
+    pub fn map_vars(&self, map: &[usize]) -> Expr {
+        match self {
+            Expr::Const(c) => Expr::Const(*c),
+            Expr::Var(i) => Expr::Var(map[*i]),
+            Expr::Not(e) => Expr::Not(Box::new(e.map_vars(map))),
+            Expr::And(es) => Expr::And(es.iter().map(|e| e.map_vars(map)).collect()),
+            Expr::Or(es) => Expr::Or(es.iter().map(|e| e.map_vars(map)).collect()),
+            Expr::Xor(a, b) => Expr::Xor(Box::new(a.map_vars(map)), Box::new(b.map_vars(map))),
+        }
+    }
+
+    /// Bindungsstärke für die Klammersetzung bei der Ausgabe
+    fn precedence(&self) -> u8 {
+        match self {
+            Expr::Or(_) => 1,
+            Expr::Xor(_, _) => 2,
+            Expr::And(_) => 3,
+            Expr::Not(_) | Expr::Var(_) | Expr::Const(_) => 4,
+        }
+    }
+
+    /// Gibt den Ausdruck mit den gegebenen Variablennamen aus (z.B. `¬A ∧ (B ∨ C)`)
+    pub fn display<'a>(&'a self, vars: &'a [String]) -> ExprDisplay<'a> {
+        ExprDisplay { expr: self, vars }
+    }
+}
+
+/// Konjunktion der Glieder; leere Liste = 1, ein Glied bleibt ohne Klammer
+pub fn and_of(mut es: Vec<Expr>) -> Expr {
+    match es.len() {
+        0 => Expr::Const(true),
+        1 => es.pop().unwrap(),
+        _ => Expr::And(es),
+    }
+}
+
+/// Disjunktion der Glieder; leere Liste = 0
+pub fn or_of(mut es: Vec<Expr>) -> Expr {
+    match es.len() {
+        0 => Expr::Const(false),
+        1 => es.pop().unwrap(),
+        _ => Expr::Or(es),
+    }
+}
+
+pub struct ExprDisplay<'a> {
+    expr: &'a Expr,
+    vars: &'a [String],
+}
+
+impl<'a> ExprDisplay<'a> {
+    fn child(&self, expr: &'a Expr, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let inner = ExprDisplay { expr, vars: self.vars };
+        let needs_parens = match self.expr {
+            Expr::Not(_) => expr.precedence() < 4,
+            _ => expr.precedence() <= self.expr.precedence(),
+        };
+        if needs_parens {
+            write!(f, "({})", inner)
+        } else {
+            write!(f, "{}", inner)
+        }
+    }
+
+    fn list(&self, es: &'a [Expr], op: &str, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, e) in es.iter().enumerate() {
+            if i > 0 {
+                write!(f, " {} ", op)?;
+            }
+            self.child(e, f)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for ExprDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.expr {
+            Expr::Const(c) => write!(f, "{}", if *c { "1" } else { "0" }),
+            Expr::Var(i) => write!(f, "{}", self.vars[*i]),
+            Expr::Not(e) => {
+                write!(f, "¬")?;
+                self.child(e, f)
+            }
+            Expr::And(es) => self.list(es, "∧", f),
+            Expr::Or(es) => self.list(es, "∨", f),
+            Expr::Xor(a, b) => {
+                self.child(a, f)?;
+                write!(f, " ⊕ ")?;
+                self.child(b, f)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// Zeichenposition, an der der Fehler erkannt wurde
+    pub position: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Zeichen {}: {}", self.position + 1, self.message)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Const(bool),
+    Not,
+    And,
+    Or,
+    Xor,
+    LParen,
+    RParen,
+}
+
+fn tokenize(src: &str) -> Result<Vec<(usize, Token)>, ParseError> {
+    let chars: Vec<char> = src.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let start = i;
+        i += 1;
+        let token = match c {
+            c if c.is_whitespace() => continue,
+            '(' => Token::LParen,
+            ')' => Token::RParen,
+            '!' | '¬' | '~' => Token::Not,
+            '&' | '∧' | '*' | '·' => Token::And,
+            '|' | '∨' | '+' => Token::Or,
+            '^' | '⊕' => Token::Xor,
+            '0' => Token::Const(false),
+            '1' => Token::Const(true),
+            c if c.is_alphabetic() => {
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                    i += 1;
+                }
+                let word: String = chars[start..i].iter().collect();
+                // Operatoren dürfen auch ausgeschrieben werden (englisch oder deutsch)
+                match word.to_uppercase().as_str() {
+                    "NOT" | "NICHT" => Token::Not,
+                    "AND" | "UND" => Token::And,
+                    "OR" | "ODER" => Token::Or,
+                    "XOR" => Token::Xor,
+                    _ => Token::Ident(word),
+                }
+            }
+            _ => {
+                return Err(ParseError {
+                    position: start,
+                    message: format!("Unerwartetes Zeichen '{}'", c),
+                })
+            }
+        };
+        tokens.push((start, token));
+    }
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    tokens: Vec<(usize, Token)>,
+    pos: usize,
+    end: usize,
+    vars: &'a mut Vec<String>,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(_, t)| t)
+    }
+
+    fn position(&self) -> usize {
+        self.tokens.get(self.pos).map_or(self.end, |(p, _)| *p)
+    }
+
+    fn error(&self, message: &str) -> ParseError {
+        ParseError { position: self.position(), message: message.to_string() }
+    }
+
+    fn or(&mut self) -> Result<Expr, ParseError> {
+        let mut terms = vec![self.xor()?];
+        while self.peek() == Some(&Token::Or) {
+            self.pos += 1;
+            terms.push(self.xor()?);
+        }
+        Ok(if terms.len() == 1 { terms.pop().unwrap() } else { Expr::Or(terms) })
+    }
+
+    fn xor(&mut self) -> Result<Expr, ParseError> {
+        let mut lhs = self.and()?;
+        while self.peek() == Some(&Token::Xor) {
+            self.pos += 1;
+            lhs = Expr::Xor(Box::new(lhs), Box::new(self.and()?));
+        }
+        Ok(lhs)
+    }
+
+    fn and(&mut self) -> Result<Expr, ParseError> {
+        let mut factors = vec![self.unary()?];
+        while self.peek() == Some(&Token::And) {
+            self.pos += 1;
+            factors.push(self.unary()?);
+        }
+        Ok(if factors.len() == 1 { factors.pop().unwrap() } else { Expr::And(factors) })
+    }
+
+    fn unary(&mut self) -> Result<Expr, ParseError> {
+        if self.peek() == Some(&Token::Not) {
+            self.pos += 1;
+            return Ok(Expr::Not(Box::new(self.unary()?)));
+        }
+        self.atom()
+    }
+
+    fn atom(&mut self) -> Result<Expr, ParseError> {
+        match self.peek().cloned() {
+            Some(Token::Ident(name)) => {
+                self.pos += 1;
+                let idx = match self.vars.iter().position(|v| *v == name) {
+                    Some(idx) => idx,
+                    None => {
+                        self.vars.push(name);
+                        self.vars.len() - 1
+                    }
+                };
+                Ok(Expr::Var(idx))
+            }
+            Some(Token::Const(c)) => {
+                self.pos += 1;
+                Ok(Expr::Const(c))
+            }
+            Some(Token::LParen) => {
+                self.pos += 1;
+                let inner = self.or()?;
+                if self.peek() != Some(&Token::RParen) {
+                    return Err(self.error("Schließende Klammer erwartet"));
+                }
+                self.pos += 1;
+                Ok(inner)
+            }
+            Some(_) => Err(self.error("Variable, Konstante oder '(' erwartet")),
+            None => Err(self.error("Unerwartetes Ende des Ausdrucks")),
+        }
+    }
+}
+
+/// Parst einen Ausdruck. Bereits bekannte Variablen behalten ihren Index, neue werden an `vars` angehängt.
+pub fn parse(src: &str, vars: &mut Vec<String>) -> Result<Expr, ParseError> {
+    let tokens = tokenize(src)?;
+    let mut parser = Parser { tokens, pos: 0, end: src.chars().count(), vars };
+    let expr = parser.or()?;
+    if parser.pos < parser.tokens.len() {
+        return Err(parser.error("Operator erwartet"));
+    }
+    Ok(expr)
+}
+
+/// Ausdruck zusammen mit den Namen seiner Variablen
+pub type ParsedExpression = Result<(Expr, Vec<String>), ParseError>;
+
+/// Parst einen frei eingegebenen Ausdruck. Die Variablen werden alphabetisch sortiert,
+/// damit z.B. `B ∧ A` dieselbe Spaltenreihenfolge in der Wahrheitstabelle hat wie `A ∧ B`.
+pub fn parse_expression(src: &str) -> ParsedExpression {
+    let mut vars = Vec::new();
+    let expr = parse(src, &mut vars)?;
+    let mut sorted = vars.clone();
+    sorted.sort();
+    let map: Vec<usize> = vars.iter().map(|v| sorted.iter().position(|s| s == v).unwrap()).collect();
+    Ok((expr.map_vars(&map), sorted))
+}
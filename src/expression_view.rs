@@ -0,0 +1,139 @@
+// Ansicht für frei eingegebene boolesche Ausdrücke
+
+use egui::{Color32, RichText, TextStyle, Ui};
+
+use crate::algebra::{self, Derivation};
+use crate::compare_view::Comparison;
+use crate::expr::{self, Expr, ParsedExpression};
+use crate::history::History;
+use crate::minimize;
+use crate::synthesis;
+use crate::truth_table::TruthTable;
+
+pub struct ExpressionView {
+    input: String,
+    parsed: ParsedExpression,
+    derivation: Derivation,
+    history: History<String>,
+}
+
+impl Default for ExpressionView {
+    fn default() -> Self {
+        let input = "(A ∧ B) ∨ (A ∧ ¬B) ∨ ¬(¬A ∨ C)".to_string();
+        let (parsed, derivation) = analyze(&input);
+        Self { input, parsed, derivation, history: History::default() }
+    }
+}
+
+fn analyze(input: &str) -> (ParsedExpression, Derivation) {
+    let parsed = expr::parse_expression(input);
+    let derivation = match &parsed {
+        Ok((expr, _)) => algebra::derive(expr),
+        Err(_) => Derivation { steps: Vec::new(), complete: true },
+    };
+    (parsed, derivation)
+}
+
+impl ExpressionView {
+    /// Der zuletzt erfolgreich eingelesene Ausdruck mit seinen Variablen
+    pub fn parsed(&self) -> Option<(&Expr, &[String])> {
+        self.parsed.as_ref().ok().map(|(expr, vars)| (expr, vars.as_slice()))
+    }
+
+    fn show_derivation(&self, ui: &mut Ui, expr: &Expr, vars: &[String]) {
+        ui.label(RichText::new("Schrittweise Vereinfachung").color(ui.visuals().strong_text_color()));
+        if self.derivation.steps.is_empty() {
+            ui.label("Der Ausdruck lässt sich mit den Gesetzen der booleschen Algebra nicht weiter vereinfachen.");
+            return;
+        }
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            egui::Grid::new("derivation").striped(true).show(ui, |ui| {
+                ui.label("");
+                ui.label(RichText::new(expr.display(vars).to_string()).monospace());
+                ui.label("Ausgangsausdruck");
+                ui.end_row();
+                for step in &self.derivation.steps {
+                    ui.label(RichText::new("=").monospace());
+                    ui.label(RichText::new(step.expr.display(vars).to_string()).monospace());
+                    ui.label(step.law.name()).on_hover_text(RichText::new(step.law.formula()).monospace());
+                    ui.end_row();
+                }
+            });
+            if !self.derivation.complete {
+                ui.label(
+                    RichText::new(format!(
+                        "Nach {} Schritten abgebrochen – der Ausdruck lässt sich womöglich noch weiter vereinfachen.",
+                        self.derivation.steps.len()
+                    ))
+                    .color(ui.visuals().warn_fg_color),
+                );
+            }
+        });
+    }
+
+    /// Minimiert den Ausdruck und baut daraus eine Schaltung in der gewünschten Form
+    fn synthesize(&self, form: synthesis::Form) -> Option<Comparison> {
+        let (expr, vars) = self.parsed()?;
+        let table = TruthTable::from_expr(expr, vars);
+        let dnf = minimize::shared_dnf(&table.outputs, vars.len());
+        let result = synthesis::synthesize(&table, &dnf, form);
+        Some(Comparison::synthesized(table, form, result))
+    }
+
+    pub fn input(&self) -> &str {
+        &self.input
+    }
+
+    /// Ersetzt den eingegebenen Ausdruck, z.B. beim Wiederherstellen der letzten Sitzung
+    pub fn set_input(&mut self, input: String) {
+        (self.parsed, self.derivation) = analyze(&input);
+        self.input = input;
+    }
+
+    /// Gibt einen Vergleich zurück, wenn aus dem Ausdruck eine Schaltung erzeugt wurde
+    pub fn show(&mut self, ui: &mut Ui) -> Option<Comparison> {
+        let mut synthesized = None;
+        ui.heading("Boolescher Ausdruck");
+        ui.label("Operatoren: NICHT (¬ ! ~), UND (∧ & *), ODER (∨ | +), XOR (⊕ ^), Klammern sowie die Konstanten 0 und 1.");
+        let mut changed = false;
+        if let Some(input) = self.history.shortcuts(ui) {
+            self.input = input;
+            changed = true;
+        }
+        ui.horizontal(|ui| {
+            let edit = egui::TextEdit::singleline(&mut self.input).font(TextStyle::Monospace).desired_width(400.0);
+            changed |= ui.add(edit).changed();
+            for symbol in ["¬", "∧", "∨", "⊕"] {
+                if ui.button(RichText::new(symbol).monospace()).clicked() {
+                    self.input.push_str(symbol);
+                    changed = true;
+                }
+            }
+            ui.separator();
+            if let Some(input) = self.history.show_controls(ui) {
+                self.input = input;
+                changed = true;
+            }
+        });
+        self.history.track(ui, &self.input, |old, new| (old != new).then(|| format!("Ausdruck: {}", new)));
+        if changed {
+            (self.parsed, self.derivation) = analyze(&self.input);
+        }
+        let small = self.parsed().is_some_and(|(_, vars)| vars.len() <= synthesis::MAX_INPUTS);
+        ui.add_enabled_ui(small, |ui| {
+            if let Some(form) = synthesis::menu(ui) {
+                synthesized = self.synthesize(form);
+            }
+        })
+        .response
+        .on_disabled_hover_text(format!("Möglich für gültige Ausdrücke mit höchstens {} Variablen", synthesis::MAX_INPUTS));
+        ui.separator();
+        match &self.parsed {
+            Err(e) => {
+                ui.label(RichText::new(format!("Ungültiger Ausdruck – {}", e)).color(Color32::RED));
+            }
+            Ok((expr, vars)) => self.show_derivation(ui, expr, vars),
+        }
+        synthesized
+    }
+}
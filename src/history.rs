@@ -0,0 +1,115 @@
+// Verlauf zum Rückgängigmachen und Wiederherstellen. Jeder Schritt speichert den ganzen Zustand eines Dokuments
+// (Schaltung, Ausdruck oder Wahrheitstabelle); beschrieben wird er durch den Vergleich mit dem vorigen Zustand.
+// So landet jede Änderung im Verlauf, ohne dass jede Bearbeitung einzeln als Befehl erfasst werden muss.
+
+use egui::{Key, Modifiers, Ui};
+
+use crate::accessibility;
+
+/// Tippt man schneller, werden die Änderungen zu einem Schritt zusammengefasst
+const MERGE_SECONDS: f64 = 1.0;
+
+struct Entry<T> {
+    label: String,
+    state: T,
+    /// Zeitpunkt der letzten Änderung und ob dabei getippt wurde
+    time: f64,
+    typing: bool,
+}
+
+pub struct History<T> {
+    entries: Vec<Entry<T>>,
+    /// Schritt, der dem aktuellen Zustand entspricht
+    position: usize,
+}
+
+impl<T> Default for History<T> {
+    fn default() -> Self {
+        Self { entries: Vec::new(), position: 0 }
+    }
+}
+
+impl<T: Clone> History<T> {
+    /// Nimmt den aktuellen Zustand auf, sobald keine Maustaste mehr gedrückt ist; Verschieben per Maus wird so ein
+    /// einziger Schritt. `describe` vergleicht mit dem zuletzt aufgenommenen Zustand und beschreibt die Änderung,
+    /// `None` heißt unverändert.
+    pub fn track(&mut self, ui: &Ui, current: &T, describe: impl Fn(&T, &T) -> Option<String>) {
+        let time = ui.input().time;
+        let typing = accessibility::typing(ui.ctx());
+        let Some(last) = self.entries.get(self.position) else {
+            self.entries.push(Entry { label: "Anfang".to_string(), state: current.clone(), time, typing: false });
+            return;
+        };
+        if ui.input().pointer.any_down() {
+            return;
+        }
+        let Some(label) = describe(&last.state, current) else { return };
+        let merge = typing && last.typing && self.position > 0 && self.position + 1 == self.entries.len() && time - last.time < MERGE_SECONDS;
+        if !merge {
+            self.entries.truncate(self.position + 1);
+            self.position += 1;
+        }
+        let entry = Entry { label, state: current.clone(), time, typing };
+        match self.entries.get_mut(self.position) {
+            Some(last) => *last = entry,
+            None => self.entries.push(entry),
+        }
+    }
+
+    fn jump(&mut self, position: usize) -> Option<T> {
+        (position < self.entries.len() && position != self.position).then(|| {
+            self.position = position;
+            self.entries[position].state.clone()
+        })
+    }
+
+    fn undo(&mut self) -> Option<T> {
+        self.position.checked_sub(1).and_then(|position| self.jump(position))
+    }
+
+    fn redo(&mut self) -> Option<T> {
+        self.jump(self.position + 1)
+    }
+
+    /// Strg+Z macht rückgängig, Strg+Y oder Strg+Umschalt+Z stellt wieder her. Muss vor den Textfeldern
+    /// aufgerufen werden, damit sie die Tasten nicht selbst verarbeiten. Gibt den Zustand zurück, zu dem gesprungen wurde.
+    pub fn shortcuts(&mut self, ui: &Ui) -> Option<T> {
+        let mut input = ui.ctx().input_mut();
+        if input.consume_key(Modifiers::COMMAND, Key::Z) {
+            drop(input);
+            return self.undo();
+        }
+        if input.consume_key(Modifiers::COMMAND, Key::Y) || input.consume_key(Modifiers::COMMAND | Modifiers::SHIFT, Key::Z) {
+            drop(input);
+            return self.redo();
+        }
+        None
+    }
+
+    /// Knöpfe zum Rückgängigmachen und Wiederherstellen sowie die Liste aller Schritte; ein Klick auf einen
+    /// Schritt springt zu diesem Zustand
+    pub fn show_controls(&mut self, ui: &mut Ui) -> Option<T> {
+        let mut result = None;
+        if ui.add_enabled(self.position > 0, egui::Button::new("Rückgängig")).on_hover_text("Strg+Z").clicked() {
+            result = self.undo();
+        }
+        if ui.add_enabled(self.position + 1 < self.entries.len(), egui::Button::new("Wiederholen")).on_hover_text("Strg+Y").clicked() {
+            result = self.redo();
+        }
+        let mut target = None;
+        ui.menu_button(format!("Verlauf ({})", self.entries.len().saturating_sub(1)), |ui| {
+            egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                for (i, entry) in self.entries.iter().enumerate() {
+                    // Rückgängig gemachte Schritte grau, sie lassen sich wiederherstellen
+                    let text = egui::RichText::new(format!("{}. {}", i, entry.label));
+                    let text = if i > self.position { text.weak() } else { text };
+                    if ui.selectable_label(i == self.position, text).clicked() {
+                        target = Some(i);
+                        ui.close_menu();
+                    }
+                }
+            });
+        });
+        target.and_then(|i| self.jump(i)).or(result)
+    }
+}
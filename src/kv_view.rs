@@ -0,0 +1,660 @@
+// Funktion über Wahrheitstabelle oder KV-Diagramm festlegen und minimieren
+
+use egui::{Align2, Color32, FontId, Pos2, Rect, RichText, Sense, Stroke, Ui, Vec2};
+
+use crate::accessibility;
+use crate::equivalence::row_inputs;
+use crate::export;
+use crate::expr::{and_of, or_of, Expr};
+use crate::history::History;
+use crate::compare_view::Comparison;
+use crate::minimize::{self, Implicant, SharedCover};
+use crate::synthesis;
+use crate::text_table::{TableFormat, TextTable};
+use crate::latex;
+use crate::truth_table::{TruthTable, Value};
+use crate::gen_bit_label;
+use crate::theme::level_color;
+
+/// KV-Diagramme mit mehr als vier Eingängen lassen sich auf dem Bildschirm kaum noch lesen
+pub const MAX_INPUTS: usize = 4;
+const INPUT_NAMES: [&str; MAX_INPUTS] = ["A", "B", "C", "D"];
+const MAX_OUTPUTS: usize = 8;
+const CELL_SIZE: f32 = 40.0;
+const HEADER_SIZE: f32 = 44.0;
+/// Benachbarte Felder ohne gemeinsamen Block
+const HAZARD_COLOR: Color32 = Color32::from_rgb(255, 60, 40);
+/// Farben der Blöcke im Diagramm und der zugehörigen Terme
+const GROUP_COLORS: [Color32; 8] = [
+    Color32::from_rgb(80, 160, 255),
+    Color32::from_rgb(255, 170, 40),
+    Color32::from_rgb(200, 90, 255),
+    Color32::from_rgb(40, 210, 200),
+    Color32::from_rgb(255, 90, 160),
+    Color32::from_rgb(230, 230, 60),
+    Color32::from_rgb(150, 110, 70),
+    Color32::from_rgb(240, 240, 240),
+];
+
+fn input_names(count: usize) -> Vec<String> {
+    INPUT_NAMES[..count].iter().map(|s| s.to_string()).collect()
+}
+
+fn output_name(index: usize) -> String {
+    match index {
+        0 => "Y".to_string(),
+        1 => "Z".to_string(),
+        _ => format!("Y{}", index + 1),
+    }
+}
+
+fn group_color(index: usize) -> Color32 {
+    GROUP_COLORS[index % GROUP_COLORS.len()]
+}
+
+/// Segmente a bis g der Ziffern 0 bis 9 auf einer Siebensegmentanzeige
+const SEVEN_SEGMENT_DIGITS: [&str; 10] = ["abcdef", "bc", "abdeg", "abcdg", "bcfg", "acdfg", "acdefg", "abc", "abcdefg", "abcdfg"];
+
+/// Gray-Code, damit sich benachbarte Spalten / Zeilen in genau einem Eingang unterscheiden
+fn gray(i: usize) -> usize {
+    i ^ (i >> 1)
+}
+
+/// Aufteilung der Eingänge: die vorderen bestimmen die Spalte, die hinteren die Zeile des Diagramms
+fn kv_split(input_count: usize) -> (usize, usize) {
+    (input_count.div_ceil(2), input_count / 2)
+}
+
+/// Zeile der Wahrheitstabelle, die in Zelle (`col`, `row`) des KV-Diagramms steht
+fn kv_cell_row(input_count: usize, col: usize, row: usize) -> usize {
+    let (_, row_bits) = kv_split(input_count);
+    gray(col) << row_bits | gray(row)
+}
+
+fn inverse_gray(mut g: usize) -> usize {
+    let mut b = 0;
+    while g != 0 {
+        b ^= g;
+        g >>= 1;
+    }
+    b
+}
+
+/// Zelle (Spalte, Zeile) des KV-Diagramms, in der die Zeile `table_row` der Wahrheitstabelle steht
+fn kv_cell(input_count: usize, table_row: usize) -> (usize, usize) {
+    let (_, row_bits) = kv_split(input_count);
+    (inverse_gray(table_row >> row_bits), inverse_gray(table_row & ((1 << row_bits) - 1)))
+}
+
+/// Beschreibt die Änderung der Wahrheitstabelle für den Verlauf
+fn describe_change(old: &TruthTable, new: &TruthTable) -> Option<String> {
+    if old == new {
+        return None;
+    }
+    if old.inputs.len() != new.inputs.len() {
+        return Some(format!("{} Eingänge", new.inputs.len()));
+    }
+    if old.outputs.len() != new.outputs.len() {
+        return Some(if new.outputs.len() > old.outputs.len() { "Ausgang hinzugefügt" } else { "Ausgang entfernt" }.to_string());
+    }
+    let cells: Vec<(usize, usize)> = (0..new.outputs.len())
+        .flat_map(|o| (0..new.row_count()).map(move |row| (o, row)))
+        .filter(|&(o, row)| old.outputs[o].values[row] != new.outputs[o].values[row])
+        .collect();
+    Some(match cells[..] {
+        [] => "Ausgang umbenannt".to_string(),
+        [(o, row)] => format!("Zeile {}: {} = {}", row, new.outputs[o].name, new.outputs[o].values[row].symbol()),
+        _ => "Tabelle neu belegt".to_string(),
+    })
+}
+
+fn bit_string(value: usize, bits: usize) -> String {
+    (0..bits).rev().map(|i| if value >> i & 1 == 1 { '1' } else { '0' }).collect()
+}
+
+/// Belegung der Eingänge einer Zeile zum Vorlesen, z.B. `A = 0, B = 1`
+fn assignment(inputs: &[String], row: usize) -> String {
+    let bits = row_inputs(row, inputs.len());
+    inputs.iter().zip(bits).map(|(name, bit)| format!("{} = {}", name, bit as u8)).collect::<Vec<_>>().join(", ")
+}
+
+fn spoken(value: Value) -> &'static str {
+    match value {
+        Value::DontCare => "beliebig",
+        value => value.symbol(),
+    }
+}
+
+/// KV-Diagramm eines Ausgangs als Tabelle; die Ecke nennt die Eingänge der Zeilen und Spalten
+pub fn kv_text_table(table: &TruthTable, output: usize) -> TextTable {
+    let n = table.inputs.len();
+    let (col_bits, row_bits) = kv_split(n);
+    let corner = format!("{}\\{}", table.inputs[col_bits..].concat(), table.inputs[..col_bits].concat());
+    let header = std::iter::once(corner).chain((0..1 << col_bits).map(|col| bit_string(gray(col), col_bits))).collect();
+    let rows = (0..1 << row_bits)
+        .map(|row| {
+            let values = (0..1 << col_bits).map(|col| table.outputs[output].values[kv_cell_row(n, col, row)].symbol().to_string());
+            std::iter::once(bit_string(gray(row), row_bits)).chain(values).collect()
+        })
+        .collect();
+    TextTable { header, rows, split: 1 }
+}
+
+/// KV-Diagramm eines Ausgangs für das LaTeX-Paket `karnaugh-map` mit den Blöcken `groups`. Das Paket nummeriert
+/// die Felder mit den Eingängen der Zeilen als höherwertigen Bits; Blöcke über den Rand werden geteilt eingezeichnet.
+fn kv_tikz(table: &TruthTable, output: usize, groups: &[Implicant]) -> String {
+    let n = table.inputs.len();
+    let (col_bits, row_bits) = kv_split(n);
+    let (cols, rows) = (1 << col_bits, 1 << row_bits);
+    let index = |col: usize, row: usize| gray(row) << col_bits | gray(col);
+    let values = &table.outputs[output].values;
+    let cells = |value: Value| {
+        let mut cells: Vec<usize> =
+            (0..cols).flat_map(|col| (0..rows).map(move |row| (col, row))).filter(|&(col, row)| values[kv_cell_row(n, col, row)] == value).map(|(col, row)| index(col, row)).collect();
+        cells.sort_unstable();
+        cells.iter().map(|c| c.to_string()).collect::<Vec<_>>().join(",")
+    };
+    let names = |range: std::ops::Range<usize>| latex::escape(&table.inputs[range].concat());
+    let mut text = format!("% Ausgang {}\n", table.outputs[output].name);
+    text += &format!("\\begin{{karnaugh-map}}[{}][{}][1][${}$][${}$]\n", cols, rows, names(0..col_bits), names(col_bits..n));
+    for (command, value) in [("minterms", Value::One), ("maxterms", Value::Zero), ("indeterminants", Value::DontCare)] {
+        let cells = cells(value);
+        if !cells.is_empty() {
+            text += &format!("  \\{}{{{}}}\n", command, cells);
+        }
+    }
+    for group in groups {
+        // Überdeckte Spalten bzw. Zeilen; ein Block über den Rand enthält die erste und letzte, aber nicht alle
+        let span = |size: usize, covered: &dyn Fn(usize) -> bool| {
+            let positions: Vec<usize> = (0..size).filter(|&p| covered(p)).collect();
+            let wraps = positions.len() < size && covered(0) && covered(size - 1);
+            (positions[0], positions[positions.len() - 1], wraps)
+        };
+        let (c0, c1, col_wraps) = span(cols, &|col| (0..rows).any(|row| group.covers(kv_cell_row(n, col, row))));
+        let (r0, r1, row_wraps) = span(rows, &|row| (0..cols).any(|col| group.covers(kv_cell_row(n, col, row))));
+        text += &match (col_wraps, row_wraps) {
+            (false, false) => format!("  \\implicant{{{}}}{{{}}}\n", index(c0, r0), index(c1, r1)),
+            (true, false) => format!("  \\implicantedge{{{}}}{{{}}}{{{}}}{{{}}}\n", index(0, r0), index(0, r1), index(cols - 1, r0), index(cols - 1, r1)),
+            (false, true) => format!("  \\implicantedge{{{}}}{{{}}}{{{}}}{{{}}}\n", index(c0, 0), index(c1, 0), index(c0, rows - 1), index(c1, rows - 1)),
+            (true, true) => "  \\implicantcorner\n".to_string(),
+        };
+    }
+    text + "\\end{karnaugh-map}\n"
+}
+
+/// Minimierte Formen zu einem Stand der Tabelle
+struct Minimized {
+    table: TruthTable,
+    shared: SharedCover,
+    cnf: Vec<(Vec<Implicant>, Expr)>,
+    /// Je Ausgang um Konsensterme ergänzte Überdeckungen, in denen benachbarte Einsen bzw. Nullen einen gemeinsamen Block haben
+    hazard_free_dnf: Vec<Vec<Implicant>>,
+    hazard_free_cnf: Vec<Vec<Implicant>>,
+}
+
+impl Minimized {
+    fn new(table: &TruthTable) -> Self {
+        let n = table.inputs.len();
+        Self {
+            table: table.clone(),
+            shared: minimize::shared_dnf(&table.outputs, n),
+            cnf: table.outputs.iter().map(|o| minimize::minimal_cnf(o, n)).collect(),
+            hazard_free_dnf: table.outputs.iter().map(|o| minimize::hazard_free_cover(&o.minterms(), &o.dont_cares(), n)).collect(),
+            hazard_free_cnf: table.outputs.iter().map(|o| minimize::hazard_free_cover(&o.maxterms(), &o.dont_cares(), n)).collect(),
+        }
+    }
+}
+
+pub struct KvView {
+    table: TruthTable,
+    /// Blöcke aus Nullen (KNF) statt aus Einsen (DNF) einzeichnen
+    show_zeros: bool,
+    /// Hazardfreie statt minimaler Überdeckung anzeigen und daraus Schaltungen erzeugen
+    hazard_free: bool,
+    /// Die gemeinsame Minimierung vieler Ausgänge dauert zu lange, um sie in jedem Frame zu wiederholen
+    minimized: Minimized,
+    history: History<TruthTable>,
+    /// Ergebnis des letzten Exports
+    status: Option<String>,
+}
+
+impl Default for KvView {
+    fn default() -> Self {
+        let table = TruthTable::new(input_names(3), vec![output_name(0)]);
+        Self { minimized: Minimized::new(&table), table, show_zeros: false, hazard_free: false, history: History::default(), status: None }
+    }
+}
+
+impl KvView {
+    pub fn table(&self) -> &TruthTable {
+        &self.table
+    }
+
+    /// Ersetzt die Tabelle, z.B. beim Wiederherstellen der letzten Sitzung; passt die Zahl der Zeilen nicht zu den
+    /// Eingängen oder sind es zu viele Eingänge, bleibt die bisherige Tabelle
+    pub fn load_table(&mut self, table: TruthTable) {
+        let rows = 1 << table.inputs.len().min(MAX_INPUTS);
+        if table.inputs.len() <= MAX_INPUTS && !table.outputs.is_empty() && table.outputs.iter().all(|o| o.values.len() == rows) {
+            self.table = table;
+        }
+    }
+
+    /// Gemeinsam minimierte UND-ODER-Form aller Ausgänge, wie sie zuletzt angezeigt wurde
+    pub fn shared_dnf(&self) -> &SharedCover {
+        &self.minimized.shared
+    }
+
+    /// Minimiert neu, falls sich die Tabelle seit dem letzten Mal geändert hat
+    fn refresh(&mut self) {
+        if self.minimized.table != self.table {
+            self.minimized = Minimized::new(&self.table);
+        }
+    }
+
+    /// Blöcke je Ausgang, wie sie gerade angezeigt werden; gemeinsame Terme haben in allen Diagrammen dieselbe Farbe
+    fn covers(&self) -> Vec<Vec<(Implicant, Color32)>> {
+        let Minimized { shared, cnf, hazard_free_dnf, hazard_free_cnf, .. } = &self.minimized;
+        (0..self.table.outputs.len())
+            .map(|o| match (self.show_zeros, self.hazard_free) {
+                (false, false) => shared.outputs[o].iter().map(|&t| (shared.terms[t], group_color(t))).collect(),
+                (true, false) => cnf[o].0.iter().enumerate().map(|(k, g)| (*g, group_color(k))).collect(),
+                (false, true) => hazard_free_dnf[o].iter().enumerate().map(|(k, g)| (*g, group_color(k))).collect(),
+                (true, true) => hazard_free_cnf[o].iter().enumerate().map(|(k, g)| (*g, group_color(k))).collect(),
+            })
+            .collect()
+    }
+
+    /// Alle KV-Diagramme mit den angezeigten Blöcken
+    fn tikz(&self) -> String {
+        let covers = self.covers();
+        (0..self.table.outputs.len())
+            .map(|o| kv_tikz(&self.table, o, &covers[o].iter().map(|(g, _)| *g).collect::<Vec<_>>()))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn show_export_menu(&mut self, ui: &mut Ui) {
+        if ui.button("Wahrheitstabelle als LaTeX (wahrheitstabelle.tex)").clicked() {
+            let body = self.table.text_table().format(TableFormat::Latex);
+            self.status = Some(export::write_file("wahrheitstabelle.tex", Ok(latex::document(&[], &body))));
+            ui.close_menu();
+        }
+        if ui.button("KV-Diagramme als TikZ (kv-diagramme.tex)").on_hover_text("Benötigt das LaTeX-Paket karnaugh-map").clicked() {
+            self.refresh();
+            let body = self.tikz();
+            self.status = Some(export::write_file("kv-diagramme.tex", Ok(latex::document(&["karnaugh-map"], &body))));
+            ui.close_menu();
+        }
+    }
+
+    fn output_names(&self) -> Vec<String> {
+        self.table.outputs.iter().map(|o| o.name.clone()).collect()
+    }
+
+    pub(crate) fn load_example(&mut self, input_count: usize, outputs: &[&str], value: impl Fn(usize, usize) -> Value) {
+        self.table = TruthTable::new(input_names(input_count), outputs.iter().map(|s| s.to_string()).collect());
+        for (o, output) in self.table.outputs.iter_mut().enumerate() {
+            for (row, v) in output.values.iter_mut().enumerate() {
+                *v = value(o, row);
+            }
+        }
+    }
+
+    fn show_examples(&mut self, ui: &mut Ui) {
+        if ui.button("Primzahl-Ziffer (BCD)").on_hover_text("Y = 1 für die Ziffern 2, 3, 5 und 7; die Codes 10 bis 15 kommen nie vor").clicked() {
+            self.load_example(4, &["Y"], |_, row| match row {
+                2 | 3 | 5 | 7 => Value::One,
+                10.. => Value::DontCare,
+                _ => Value::Zero,
+            });
+            ui.close_menu();
+        }
+        if ui.button("Volladdierer").on_hover_text("Summe S und Übertrag C aus A, B und dem Übertrag C des vorigen Bits").clicked() {
+            self.load_example(3, &["S", "C"], |o, row| {
+                let ones = row.count_ones();
+                Value::from(if o == 0 { ones % 2 == 1 } else { ones >= 2 })
+            });
+            ui.close_menu();
+        }
+        if ui.button("Statischer Hazard").on_hover_text("Y = A·B + ¬A·C: wechselt A, während B = C = 1 ist, fällt Y kurz auf 0").clicked() {
+            self.load_example(3, &["Y"], |_, row| Value::from(matches!(row, 1 | 3 | 6 | 7)));
+            ui.close_menu();
+        }
+        if ui.button("BCD-zu-Siebensegment").on_hover_text("Segmente a bis g für die Ziffern 0 bis 9; die Codes 10 bis 15 kommen nie vor").clicked() {
+            self.load_example(4, &["a", "b", "c", "d", "e", "f", "g"], |o, row| match SEVEN_SEGMENT_DIGITS.get(row) {
+                Some(segments) => Value::from(segments.contains(char::from(b'a' + o as u8))),
+                None => Value::DontCare,
+            });
+            ui.close_menu();
+        }
+    }
+
+    /// Wahrheitstabelle oder KV-Diagramm als Text in die Zwischenablage, z.B. für Arbeitsblätter
+    fn show_copy_menu(&mut self, ui: &mut Ui) {
+        self.refresh();
+        let covers = self.covers();
+        let mut tables = vec![("Wahrheitstabelle".to_string(), self.table.text_table())];
+        for (i, output) in self.table.outputs.iter().enumerate() {
+            tables.push((format!("KV-Diagramm {}", output.name), kv_text_table(&self.table, i)));
+        }
+        for (k, (name, table)) in tables.into_iter().enumerate() {
+            ui.menu_button(name, |ui| {
+                for format in TableFormat::ALL {
+                    if ui.button(format.label()).clicked() {
+                        ui.output().copied_text = table.format(format);
+                        ui.close_menu();
+                    }
+                }
+                // Nach der Wahrheitstabelle folgen die Ausgänge
+                if k > 0 && ui.button("TikZ (karnaugh-map)").clicked() {
+                    ui.output().copied_text = kv_tikz(&self.table, k - 1, &covers[k - 1].iter().map(|(g, _)| *g).collect::<Vec<_>>());
+                    ui.close_menu();
+                }
+            });
+        }
+    }
+
+    fn show_truth_table(&mut self, ui: &mut Ui) {
+        egui::Grid::new("kv_truth_table").striped(true).show(ui, |ui| {
+            ui.strong("Nr.");
+            for input in &self.table.inputs {
+                ui.strong(input);
+            }
+            for output in &mut self.table.outputs {
+                ui.add(egui::TextEdit::singleline(&mut output.name).desired_width(24.0));
+            }
+            ui.end_row();
+            let mut ids = Vec::new();
+            for row in 0..self.table.row_count() {
+                ui.label(row.to_string());
+                for value in row_inputs(row, self.table.inputs.len()) {
+                    ui.add(gen_bit_label(value));
+                }
+                let assignment = assignment(&self.table.inputs, row);
+                let mut row_ids = Vec::new();
+                for output in &mut self.table.outputs {
+                    let value = &mut output.values[row];
+                    let response = ui.add(egui::Button::new(value.symbol()).small());
+                    if response.clicked() {
+                        *value = value.next();
+                    }
+                    let text = format!("Zeile {}, {}: {} = {}", row, assignment, output.name, spoken(*value));
+                    accessibility::describe(&response, egui::WidgetType::Button, text);
+                    row_ids.push(response.id);
+                }
+                ids.push(row_ids);
+                ui.end_row();
+            }
+            accessibility::grid_focus(ui, &ids);
+        });
+    }
+
+    /// KV-Diagramm eines Ausgangs mit den Blöcken `groups`. Paare benachbarter Felder in `hazards` werden rot verbunden;
+    /// liegen sie an gegenüberliegenden Rändern, zeigt je ein Strich zum Rand.
+    fn show_kv_map(&mut self, ui: &mut Ui, output: usize, groups: &[(Implicant, Color32)], hazards: &[(usize, usize)]) {
+        let n = self.table.inputs.len();
+        let (col_bits, row_bits) = kv_split(n);
+        let (cols, rows) = (1 << col_bits, 1 << row_bits);
+        let size = Vec2::new(HEADER_SIZE + CELL_SIZE * cols as f32, HEADER_SIZE + CELL_SIZE * rows as f32);
+        let (response, painter) = ui.allocate_painter(size, Sense::hover());
+        let grid = Rect::from_min_size(response.rect.min + Vec2::splat(HEADER_SIZE), size - Vec2::splat(HEADER_SIZE));
+        let cell = |col: usize, row: usize| {
+            Rect::from_min_size(grid.min + Vec2::new(col as f32, row as f32) * CELL_SIZE, Vec2::splat(CELL_SIZE))
+        };
+
+        // Jedes Feld ist ein eigenes Widget, damit es sich mit Tab und Pfeiltasten erreichen und vorlesen lässt
+        let mut ids = Vec::new();
+        for row in 0..rows {
+            let mut row_ids = Vec::new();
+            for col in 0..cols {
+                let table_row = kv_cell_row(n, col, row);
+                let field = ui.interact(cell(col, row), response.id.with(table_row), Sense::click());
+                let output = &mut self.table.outputs[output];
+                let value = &mut output.values[table_row];
+                if field.clicked() {
+                    *value = value.next();
+                }
+                if field.has_focus() {
+                    painter.rect_stroke(cell(col, row).shrink(1.0), 0.0, ui.visuals().selection.stroke);
+                }
+                let text = format!("Feld {}, {}: {} = {}", table_row, assignment(&self.table.inputs, table_row), output.name, spoken(*value));
+                accessibility::describe(&field, egui::WidgetType::Button, text);
+                row_ids.push(field.id);
+            }
+            ids.push(row_ids);
+        }
+        accessibility::grid_focus(ui, &ids);
+
+        let text_color = ui.visuals().text_color();
+        let font = FontId::proportional(14.0);
+        let names = |range: std::ops::Range<usize>| self.table.inputs[range].concat();
+        painter.text(Pos2::new(grid.center().x, response.rect.top() + 2.0), Align2::CENTER_TOP, names(0..col_bits), font.clone(), ui.visuals().strong_text_color());
+        if row_bits > 0 {
+            painter.text(Pos2::new(response.rect.left() + 2.0, grid.top() - 2.0), Align2::LEFT_BOTTOM, names(col_bits..n), font.clone(), ui.visuals().strong_text_color());
+        }
+        for col in 0..cols {
+            painter.text(cell(col, 0).center_top() - Vec2::new(0.0, 4.0), Align2::CENTER_BOTTOM, bit_string(gray(col), col_bits), font.clone(), text_color);
+        }
+        for row in 0..rows {
+            if row_bits > 0 {
+                painter.text(cell(0, row).left_center() - Vec2::new(6.0, 0.0), Align2::RIGHT_CENTER, bit_string(gray(row), row_bits), font.clone(), text_color);
+            }
+            for col in 0..cols {
+                let rect = cell(col, row);
+                let table_row = kv_cell_row(n, col, row);
+                let value = self.table.outputs[output].values[table_row];
+                painter.rect_stroke(rect, 0.0, Stroke::new(1.0, Color32::GRAY));
+                let color = match value {
+                    Value::DontCare => Color32::GRAY,
+                    value => level_color(value == Value::One),
+                };
+                painter.text(rect.center(), Align2::CENTER_CENTER, value.symbol(), FontId::proportional(18.0), color);
+                // Zeilennummer der Wahrheitstabelle klein in der Ecke
+                painter.text(rect.right_bottom() - Vec2::new(3.0, 1.0), Align2::RIGHT_BOTTOM, table_row.to_string(), FontId::proportional(9.0), Color32::GRAY);
+                for (k, (group, color)) in groups.iter().enumerate() {
+                    if group.covers(table_row) {
+                        painter.rect_stroke(rect.shrink(2.0 + 3.0 * (k % 5) as f32), 4.0, Stroke::new(2.0, *color));
+                    }
+                }
+            }
+        }
+        let stroke = Stroke::new(3.0, HAZARD_COLOR);
+        for &(a, b) in hazards {
+            let ((col_a, row_a), (col_b, row_b)) = (kv_cell(n, a), kv_cell(n, b));
+            let (center_a, center_b) = (cell(col_a, row_a).center(), cell(col_b, row_b).center());
+            if col_a.abs_diff(col_b) + row_a.abs_diff(row_b) == 1 {
+                painter.line_segment([center_a, center_b], stroke);
+            } else {
+                // Über den Rand benachbart: jeweils vom Feld nach außen
+                for (from, to) in [(center_a, center_b), (center_b, center_a)] {
+                    let outward = (from - to).normalized() * CELL_SIZE / 2.0;
+                    painter.line_segment([from, from + outward], stroke);
+                }
+            }
+            for p in [center_a, center_b] {
+                painter.circle_filled(p, 4.0, HAZARD_COLOR);
+            }
+        }
+    }
+
+    /// Hinweis auf die Hazards eines Ausgangs mit dem Eingang, dessen Wechsel sie auslöst
+    fn show_hazards(&self, ui: &mut Ui, output: usize, hazards: &[(usize, usize)]) {
+        let n = self.table.inputs.len();
+        let kind = if self.show_zeros { "0-Hazard" } else { "1-Hazard" };
+        for &(a, b) in hazards {
+            let input = &self.table.inputs[n - 1 - (a ^ b).trailing_zeros() as usize];
+            ui.label(
+                RichText::new(format!(
+                    "⚠ {}: statischer {} möglich, wenn {} wechselt (Zeilen {} und {})",
+                    self.table.outputs[output].name, kind, input, a, b
+                ))
+                .color(HAZARD_COLOR),
+            );
+        }
+    }
+
+    /// Ausdruck und farbige Terme passend zu den Blöcken im KV-Diagramm
+    fn show_terms(&self, ui: &mut Ui, output: usize, expr: &Expr, terms: &[(Expr, Color32)]) {
+        ui.label(RichText::new(format!("{} = {}", self.table.outputs[output].name, expr.display(&self.table.inputs))).monospace());
+        ui.horizontal_wrapped(|ui| {
+            for (term, color) in terms {
+                ui.label(RichText::new(format!("■ {}", term.display(&self.table.inputs))).monospace().color(*color));
+            }
+        });
+    }
+
+    fn show_shared_terms(&self, ui: &mut Ui, cover: &SharedCover) {
+        let n = self.table.inputs.len();
+        let shared: Vec<usize> = (0..cover.terms.len()).filter(|&t| cover.users(t).len() > 1).collect();
+        ui.label(format!("{} verschiedene UND-Terme für {} Ausgänge", cover.terms.len(), self.table.outputs.len()));
+        if shared.is_empty() {
+            return;
+        }
+        ui.label("Gemeinsam genutzte Terme:");
+        for t in shared {
+            let users: Vec<&str> = cover.users(t).into_iter().map(|o| self.table.outputs[o].name.as_str()).collect();
+            ui.label(
+                RichText::new(format!("■ {}  ({})", cover.terms[t].product(n).display(&self.table.inputs), users.join(", ")))
+                    .monospace()
+                    .color(group_color(t)),
+            );
+        }
+    }
+
+    /// Gibt einen Vergleich zurück, wenn aus der Tabelle eine Schaltung erzeugt wurde
+    pub fn show(&mut self, ui: &mut Ui) -> Option<Comparison> {
+        let mut synthesized = None;
+        if let Some(table) = self.history.shortcuts(ui) {
+            self.table = table;
+        }
+        ui.heading("KV-Diagramm");
+        ui.label("Lege die Funktion in der Wahrheitstabelle oder direkt im KV-Diagramm fest. Jeder Klick schaltet den Wert in der Reihenfolge 0, 1, X (beliebig) weiter.");
+        ui.horizontal(|ui| {
+            ui.label("Eingänge:");
+            for count in 1..=MAX_INPUTS {
+                if ui.selectable_label(self.table.inputs.len() == count, count.to_string()).clicked() && self.table.inputs.len() != count {
+                    self.table = TruthTable::new(input_names(count), self.output_names());
+                }
+            }
+            ui.separator();
+            ui.label(format!("Ausgänge: {}", self.table.outputs.len()));
+            if ui.add_enabled(self.table.outputs.len() > 1, egui::Button::new("-")).clicked() {
+                self.table.outputs.pop();
+            }
+            if ui.add_enabled(self.table.outputs.len() < MAX_OUTPUTS, egui::Button::new("+")).clicked() {
+                let mut names = self.output_names();
+                names.push((0..).map(output_name).find(|name| !names.contains(name)).unwrap());
+                let mut table = TruthTable::new(self.table.inputs.clone(), names);
+                table.outputs[..self.table.outputs.len()].clone_from_slice(&self.table.outputs);
+                self.table = table;
+            }
+            ui.separator();
+            if ui.button("Alles auf 0").clicked() {
+                self.table = TruthTable::new(self.table.inputs.clone(), self.output_names());
+            }
+            ui.menu_button("Beispiele", |ui| self.show_examples(ui));
+            ui.menu_button("Kopieren", |ui| self.show_copy_menu(ui));
+            ui.menu_button("Exportieren", |ui| self.show_export_menu(ui));
+            ui.separator();
+            if let Some(table) = self.history.show_controls(ui) {
+                self.table = table;
+            }
+            ui.separator();
+            if let Some(form) = synthesis::menu(ui) {
+                self.refresh();
+                let result = if self.hazard_free {
+                    synthesis::synthesize_hazard_free(&self.table, form)
+                } else {
+                    synthesis::synthesize(&self.table, &self.minimized.shared, form)
+                };
+                synthesized = Some(Comparison::synthesized(self.table.clone(), form, result));
+            }
+        });
+        if let Some(status) = &self.status {
+            ui.label(status);
+        }
+        ui.separator();
+
+        self.refresh();
+        let n = self.table.inputs.len();
+        let (shared, cnf) = (self.minimized.shared.clone(), self.minimized.cnf.clone());
+        ui.horizontal_top(|ui| {
+            egui::ScrollArea::vertical().id_source("kv_table_scroll").show(ui, |ui| self.show_truth_table(ui));
+            ui.separator();
+            egui::ScrollArea::vertical().id_source("kv_map_scroll").show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Blöcke:");
+                    ui.radio_value(&mut self.show_zeros, false, "Einsen (DNF)");
+                    ui.radio_value(&mut self.show_zeros, true, "Nullen (KNF)");
+                    ui.separator();
+                    ui.checkbox(&mut self.hazard_free, "Hazardfrei").on_hover_text(
+                        "Ergänzt Terme, sodass benachbarte Einsen (bzw. Nullen) immer in einem gemeinsamen Block liegen. \
+                         Rote Linien verbinden benachbarte Felder ohne gemeinsamen Block.",
+                    );
+                });
+                ui.label("X-Felder dürfen in Blöcke einbezogen werden, wenn diese dadurch größer werden.");
+                ui.add_space(8.0);
+                // Ein KV-Diagramm je Ausgang
+                let outputs = self.table.outputs.len();
+                let covers = self.covers();
+                let hazards: Vec<Vec<(usize, usize)>> = (0..outputs)
+                    .map(|o| {
+                        let output = &self.table.outputs[o];
+                        let rows = if self.show_zeros { output.maxterms() } else { output.minterms() };
+                        minimize::hazards(&covers[o].iter().map(|(g, _)| *g).collect::<Vec<_>>(), &rows)
+                    })
+                    .collect();
+                ui.horizontal_wrapped(|ui| {
+                    for o in 0..outputs {
+                        ui.vertical(|ui| {
+                            ui.label(RichText::new(format!("Ausgang {}", self.table.outputs[o].name)).color(ui.visuals().strong_text_color()));
+                            self.show_kv_map(ui, o, &covers[o], &hazards[o]);
+                        });
+                        ui.add_space(12.0);
+                    }
+                });
+                ui.separator();
+                let title = match (self.show_zeros, self.hazard_free) {
+                    (false, false) => "Minimale DNF",
+                    (true, false) => "Minimale KNF",
+                    (false, true) => "Hazardfreie DNF",
+                    (true, true) => "Hazardfreie KNF",
+                };
+                ui.label(RichText::new(title).color(ui.visuals().strong_text_color()));
+                for o in 0..outputs {
+                    let terms: Vec<(Expr, Color32)> =
+                        covers[o].iter().map(|(g, color)| (if self.show_zeros { g.sum(n) } else { g.product(n) }, *color)).collect();
+                    let exprs = terms.iter().map(|(term, _)| term.clone()).collect();
+                    let expr = match (self.show_zeros, self.hazard_free) {
+                        (false, false) => shared.expr(o, n),
+                        (true, false) => cnf[o].1.clone(),
+                        (false, true) => or_of(exprs),
+                        (true, true) => and_of(exprs),
+                    };
+                    self.show_terms(ui, o, &expr, &terms);
+                    if self.hazard_free {
+                        // Terme, die die minimale Form nicht braucht
+                        let minimal: Vec<Implicant> =
+                            if self.show_zeros { cnf[o].0.clone() } else { shared.outputs[o].iter().map(|&t| shared.terms[t]).collect() };
+                        let extra: Vec<String> = terms
+                            .iter()
+                            .zip(&covers[o])
+                            .filter(|(_, (g, _))| !minimal.contains(g))
+                            .map(|((term, _), _)| term.display(&self.table.inputs).to_string())
+                            .collect();
+                        if !extra.is_empty() {
+                            ui.label(format!("Zusätzlich gegen Hazards: {}", extra.join(", ")));
+                        }
+                    }
+                    self.show_hazards(ui, o, &hazards[o]);
+                }
+                if !self.show_zeros && !self.hazard_free && outputs > 1 {
+                    ui.add_space(4.0);
+                    self.show_shared_terms(ui, &shared);
+                }
+            });
+        });
+        self.history.track(ui, &self.table, describe_change);
+        synthesized
+    }
+}
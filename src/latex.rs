@@ -0,0 +1,23 @@
+// Hilfen für den Export nach LaTeX: Sonderzeichen maskieren und Ausschnitte in ein übersetzbares Dokument einbetten
+
+/// Zeichen mit Sonderbedeutung in LaTeX, etwa `_` in Namen wie `Y_1`
+pub fn escape(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '\\' => "\\textbackslash{}".to_string(),
+            '&' | '%' | '$' | '#' | '_' | '{' | '}' => format!("\\{}", c),
+            '~' => "\\textasciitilde{}".to_string(),
+            '^' => "\\textasciicircum{}".to_string(),
+            c => c.to_string(),
+        })
+        .collect()
+}
+
+/// Vollständiges Dokument, das sich direkt mit pdflatex übersetzen lässt
+pub fn document(packages: &[&str], body: &str) -> String {
+    let mut text = "\\documentclass{article}\n\\usepackage[utf8]{inputenc}\n\\usepackage[T1]{fontenc}\n".to_string();
+    for package in packages {
+        text += &format!("\\usepackage{{{}}}\n", package);
+    }
+    text + "\\begin{document}\n\n" + body + "\n\\end{document}\n"
+}
@@ -0,0 +1,392 @@
+// Automatische Anordnung eines Schaltplans in Schichten nach der Logiktiefe: Eingänge links, Ausgänge rechts,
+// dazwischen die Gatter in einer Reihenfolge mit möglichst wenigen Kreuzungen. Die Leitungen verlaufen rechtwinklig,
+// jedes Signal bekommt zwischen zwei Schichten eine eigene senkrechte Spur. Die Bits eines Busses laufen gemeinsam.
+
+use std::collections::HashMap;
+
+use egui::Pos2;
+
+use crate::bus::locate;
+use crate::circuit::{Circuit, NodeKind, Source};
+use crate::editor::{input_pin, node_rect, output_pin};
+
+const LEFT: f32 = 40.0;
+const TOP: f32 = 20.0;
+/// Senkrechter Abstand zwischen zwei Bauteilen einer Schicht
+const ROW_GAP: f32 = 20.0;
+/// Mindestbreite des Zwischenraums zwischen zwei Schichten
+const MIN_GAP: f32 = 50.0;
+/// Abstand der äußeren Spuren zu den Bauteilen
+const GAP_MARGIN: f32 = 16.0;
+const TRACK_SPACING: f32 = 8.0;
+/// Durchläufe der Schwerpunkt-Sortierung, abwechselnd von links und von rechts
+const SWEEPS: usize = 8;
+
+/// Knoten der Schaltung oder Hilfspunkt für eine Leitung, die eine Schicht überspringt
+struct Vertex {
+    node: Option<usize>,
+    layer: usize,
+    height: f32,
+    y: f32,
+}
+
+/// Leitungsstück zwischen zwei benachbarten Schichten. `out` ist der Ausgang von `from` (bei Hilfspunkten 0),
+/// `pin` der Eingang, wenn `to` ein Knoten ist.
+#[derive(Clone, Copy, PartialEq)]
+struct Link {
+    from: usize,
+    out: usize,
+    to: usize,
+    pin: Option<usize>,
+}
+
+/// Senkrechte Spur eines Signals im Zwischenraum hinter seiner Schicht
+struct Net {
+    from: (usize, usize),
+    source_y: f32,
+    target_ys: Vec<f32>,
+}
+
+impl Net {
+    fn span(&self) -> (f32, f32) {
+        self.target_ys.iter().fold((self.source_y, self.source_y), |(lo, hi), &y| (lo.min(y), hi.max(y)))
+    }
+
+    /// Kreuzungen, wenn `self` links von `other` liegt: die Abzweige von `self` nach rechts und
+    /// die Zuleitung von `other` von links schneiden jeweils die Spur des anderen
+    fn crossings_left_of(&self, other: &Net) -> usize {
+        let inside = |y: f32, (lo, hi): (f32, f32)| y > lo + 0.5 && y < hi - 0.5;
+        self.target_ys.iter().filter(|&&y| inside(y, other.span())).count() + inside(other.source_y, self.span()) as usize
+    }
+}
+
+/// Schicht jedes Knotens: Eingänge in Schicht 0, Gatter nach der längsten Kette von Vorgängern, Ausgänge ganz rechts
+fn layers(circuit: &Circuit) -> Vec<usize> {
+    let mut depth = vec![0; circuit.nodes.len()];
+    for n in circuit.topological_order() {
+        depth[n] = match circuit.nodes[n].kind {
+            NodeKind::Input { .. } => 0,
+            _ => 1 + circuit.nodes[n].inputs.iter().flatten().map(|src| depth[src.node]).max().unwrap_or(0),
+        };
+    }
+    let last = (0..circuit.nodes.len())
+        .filter(|&n| !matches!(circuit.nodes[n].kind, NodeKind::Output { .. }))
+        .map(|n| depth[n] + 1)
+        .max()
+        .unwrap_or(1);
+    for (n, node) in circuit.nodes.iter().enumerate() {
+        if matches!(node.kind, NodeKind::Output { .. }) {
+            depth[n] = last;
+        }
+    }
+    depth
+}
+
+/// Kreuzungen aller Leitungsstücke; die Eingänge eines Knotens zählen von oben nach unten
+fn crossings(links: &[Link], vertices: &[Vertex], pos: &[usize]) -> usize {
+    let key = |l: &Link| (vertices[l.from].layer, (pos[l.from], l.out), (pos[l.to], l.pin.unwrap_or(0)));
+    let mut count = 0;
+    for (i, a) in links.iter().enumerate() {
+        for b in &links[i + 1..] {
+            let (a, b) = (key(a), key(b));
+            if a.0 == b.0 && (a.1 < b.1 && a.2 > b.2 || a.1 > b.1 && a.2 < b.2) {
+                count += 1;
+            }
+        }
+    }
+    count
+}
+
+/// Sortiert eine Schicht nach der mittleren Position der Nachbarn; Knoten ohne Nachbarn behalten ihren Platz
+fn reorder(layer: &mut Vec<usize>, neighbors: impl Fn(usize) -> Vec<usize>, pos: &mut [usize]) {
+    let mut keyed: Vec<(f32, usize)> = layer
+        .iter()
+        .enumerate()
+        .map(|(i, &v)| match neighbors(v) {
+            n if n.is_empty() => (i as f32, v),
+            n => (n.iter().map(|&u| pos[u] as f32).sum::<f32>() / n.len() as f32, v),
+        })
+        .collect();
+    keyed.sort_by(|a, b| a.0.total_cmp(&b.0));
+    *layer = keyed.into_iter().map(|(_, v)| v).collect();
+    for (i, &v) in layer.iter().enumerate() {
+        pos[v] = i;
+    }
+}
+
+/// Kreuzungen zwischen den Leitungen von `u` und `v`, wenn `u` direkt über `v` liegt
+fn pair_crossings(u: usize, v: usize, preds: &[Vec<(usize, usize)>], succs: &[Vec<(usize, usize)>], pos: &[usize]) -> usize {
+    let left = preds[u]
+        .iter()
+        .map(|&(a, out_a)| preds[v].iter().filter(|&&(b, out_b)| (pos[a], out_a) > (pos[b], out_b)).count())
+        .sum::<usize>();
+    let right = succs[u]
+        .iter()
+        .map(|&(a, pin_a)| succs[v].iter().filter(|&&(b, pin_b)| (pos[a], pin_a) > (pos[b], pin_b)).count())
+        .sum::<usize>();
+    left + right
+}
+
+/// Vertauscht benachbarte Knoten einer Schicht, solange das Kreuzungen spart
+fn transpose(layer: &mut [usize], preds: &[Vec<(usize, usize)>], succs: &[Vec<(usize, usize)>], pos: &mut [usize]) {
+    let mut improved = true;
+    while improved {
+        improved = false;
+        for i in 1..layer.len() {
+            let (u, v) = (layer[i - 1], layer[i]);
+            if pair_crossings(v, u, preds, succs, pos) < pair_crossings(u, v, preds, succs, pos) {
+                layer.swap(i - 1, i);
+                (pos[u], pos[v]) = (pos[v], pos[u]);
+                improved = true;
+            }
+        }
+    }
+}
+
+/// Rückt die Knoten einer Schicht möglichst nah an ihre Wunschhöhe, ohne die Reihenfolge
+/// oder die Mindestabstände zu verletzen
+fn place(layer: &[usize], vertices: &mut [Vertex], desired: &[f32]) {
+    let n = layer.len();
+    let sep = |i: usize| {
+        let (a, b) = (&vertices[layer[i]], &vertices[layer[i + 1]]);
+        let gap = if a.node.is_some() && b.node.is_some() { ROW_GAP } else { ROW_GAP / 2.0 };
+        (a.height + b.height) / 2.0 + gap
+    };
+    // Einmal nach unten und einmal nach oben schieben; der Mittelwert hält die Abstände ebenfalls ein
+    let mut down = desired.to_vec();
+    for i in 1..n {
+        down[i] = down[i].max(down[i - 1] + sep(i - 1));
+    }
+    let mut up = desired.to_vec();
+    for i in (0..n.saturating_sub(1)).rev() {
+        up[i] = up[i].min(up[i + 1] - sep(i));
+    }
+    for i in 0..n {
+        vertices[layer[i]].y = (down[i] + up[i]) / 2.0;
+    }
+}
+
+/// Ordnet die Knoten neu an und legt für jede Leitung rechtwinklige Knickpunkte fest.
+/// Ein- und Ausgänge behalten ihre Reihenfolge.
+pub fn arrange(circuit: &mut Circuit) {
+    if circuit.nodes.is_empty() {
+        return;
+    }
+    let layer_of = layers(circuit);
+    let last = *layer_of.iter().max().unwrap();
+    let mut vertices: Vec<Vertex> = circuit
+        .nodes
+        .iter()
+        .enumerate()
+        .map(|(n, node)| Vertex { node: Some(n), layer: layer_of[n], height: node_rect(node).height(), y: 0.0 })
+        .collect();
+
+    // Überspringt eine Leitung Schichten, bekommt sie dort Hilfspunkte. Alle Leitungen einer Quelle
+    // teilen sich die Hilfspunkte und laufen dadurch gebündelt. Bits eines Busses zählen dabei
+    // als eine Leitung vom ersten Bit des Anschlusses zum ersten Bit des Zielanschlusses.
+    let mut dummies: HashMap<(Source, usize), usize> = HashMap::new();
+    let mut links: Vec<Link> = Vec::new();
+    let mut chains = Vec::new();
+    let outputs: Vec<_> = circuit.nodes.iter().map(|node| node.kind.output_ports()).collect();
+    for (t, node) in circuit.nodes.iter().enumerate() {
+        let inputs = node.kind.input_ports();
+        for (bit, src) in node.inputs.iter().enumerate() {
+            let Some(src) = *src else { continue };
+            let src = Source { pin: locate(&outputs[src.node], src.pin).1, ..src };
+            let pin = locate(&inputs, bit).1;
+            let mut chain = vec![src.node];
+            for layer in layer_of[src.node] + 1..layer_of[t] {
+                chain.push(*dummies.entry((src, layer)).or_insert_with(|| {
+                    vertices.push(Vertex { node: None, layer, height: 0.0, y: 0.0 });
+                    vertices.len() - 1
+                }));
+            }
+            chain.push(t);
+            let chain: Vec<Link> = chain
+                .windows(2)
+                .map(|w| Link {
+                    from: w[0],
+                    out: if w[0] == src.node { src.pin } else { 0 },
+                    to: w[1],
+                    pin: (w[1] == t).then_some(pin),
+                })
+                .collect();
+            for link in &chain {
+                if !links.contains(link) {
+                    links.push(*link);
+                }
+            }
+            chains.push((t, bit, chain));
+        }
+    }
+    let mut preds = vec![Vec::new(); vertices.len()];
+    let mut succs = vec![Vec::new(); vertices.len()];
+    for l in &links {
+        preds[l.to].push((l.from, l.out));
+        succs[l.from].push((l.to, l.pin.unwrap_or(0)));
+    }
+
+    // Reihenfolge innerhalb der Schichten; die erste und letzte Schicht (Ein- und Ausgänge) bleiben fest
+    let mut order = vec![Vec::new(); last + 1];
+    for (v, vertex) in vertices.iter().enumerate() {
+        order[vertex.layer].push(v);
+    }
+    let mut pos = vec![0; vertices.len()];
+    for layer in &order {
+        for (i, &v) in layer.iter().enumerate() {
+            pos[v] = i;
+        }
+    }
+    let mut best = (usize::MAX, order.clone());
+    for sweep in 0..SWEEPS {
+        let inner = &mut order[1..last.max(1)];
+        if sweep % 2 == 0 {
+            for layer in inner.iter_mut() {
+                reorder(layer, |v| preds[v].iter().map(|&(u, _)| u).collect(), &mut pos);
+            }
+        } else {
+            for layer in inner.iter_mut().rev() {
+                reorder(layer, |v| succs[v].iter().map(|&(w, _)| w).collect(), &mut pos);
+            }
+        }
+        for layer in inner {
+            transpose(layer, &preds, &succs, &mut pos);
+        }
+        let count = crossings(&links, &vertices, &pos);
+        if count < best.0 {
+            best = (count, order.clone());
+        }
+    }
+    let order = best.1;
+
+    // Höhen: erst übereinander stapeln, dann abwechselnd an Vorgänger und Nachfolger angleichen,
+    // damit möglichst viele Leitungen gerade durchlaufen. Die Versätze der Anschlüsse werden berücksichtigt.
+    let offsets: Vec<f32> = links
+        .iter()
+        .map(|l| {
+            let out = match vertices[l.from].node {
+                Some(n) => output_pin(&circuit.nodes[n], l.out).unwrap().y - circuit.nodes[n].pos.y,
+                None => 0.0,
+            };
+            let pin = match l.pin {
+                Some(pin) => input_pin(&circuit.nodes[l.to], pin).y - circuit.nodes[l.to].pos.y,
+                None => 0.0,
+            };
+            pin - out
+        })
+        .collect();
+    for layer in &order {
+        let desired: Vec<f32> = vec![0.0; layer.len()];
+        place(layer, &mut vertices, &desired);
+    }
+    for round in 0..5 {
+        let layers: Vec<usize> = if round % 2 == 0 { (1..=last).collect() } else { (0..last).rev().collect() };
+        for l in layers {
+            let desired: Vec<f32> = order[l]
+                .iter()
+                .map(|&v| {
+                    let ys: Vec<f32> = links
+                        .iter()
+                        .zip(&offsets)
+                        .filter_map(|(link, offset)| match round % 2 {
+                            0 if link.to == v => Some(vertices[link.from].y - offset),
+                            1 if link.from == v => Some(vertices[link.to].y + offset),
+                            _ => None,
+                        })
+                        .collect();
+                    if ys.is_empty() { vertices[v].y } else { ys.iter().sum::<f32>() / ys.len() as f32 }
+                })
+                .collect();
+            place(&order[l], &mut vertices, &desired);
+        }
+    }
+    let top = vertices.iter().map(|v| v.y - v.height / 2.0).fold(f32::INFINITY, f32::min);
+    for vertex in &mut vertices {
+        vertex.y += TOP - top;
+    }
+    for (n, node) in circuit.nodes.iter_mut().enumerate() {
+        node.pos = Pos2::new(0.0, vertices[n].y);
+    }
+
+    // Spuren je Zwischenraum: jede Quelle mit Abnehmern auf anderer Höhe bekommt eine eigene
+    let in_y = |l: &Link| match l.pin {
+        Some(pin) => input_pin(&circuit.nodes[l.to], pin).y,
+        None => vertices[l.to].y,
+    };
+    let out_y = |l: &Link| match vertices[l.from].node {
+        Some(n) => output_pin(&circuit.nodes[n], l.out).unwrap().y,
+        None => vertices[l.from].y,
+    };
+    let mut tracks: Vec<Vec<Net>> = (0..=last).map(|_| Vec::new()).collect();
+    for l in &links {
+        let gap = &mut tracks[vertices[l.from].layer];
+        match gap.iter_mut().find(|net| net.from == (l.from, l.out)) {
+            Some(net) => net.target_ys.push(in_y(l)),
+            None => gap.push(Net { from: (l.from, l.out), source_y: out_y(l), target_ys: vec![in_y(l)] }),
+        }
+    }
+    for gap in &mut tracks {
+        gap.retain(|net| net.target_ys.iter().any(|&y| (y - net.source_y).abs() > 0.5));
+        gap.sort_by(|a, b| a.source_y.total_cmp(&b.source_y));
+        // Benachbarte Spuren tauschen, solange das Kreuzungen spart; jeder Tausch ändert nur dieses Paar
+        let mut improved = true;
+        while improved {
+            improved = false;
+            for i in 1..gap.len() {
+                if gap[i].crossings_left_of(&gap[i - 1]) < gap[i - 1].crossings_left_of(&gap[i]) {
+                    gap.swap(i - 1, i);
+                    improved = true;
+                }
+            }
+        }
+    }
+
+    // Breite der Schichten und Zwischenräume
+    let mut left = vec![0.0f32; last + 1];
+    let mut right = vec![0.0f32; last + 1];
+    for (n, node) in circuit.nodes.iter().enumerate() {
+        let l = layer_of[n];
+        let half = node_rect(node).width() / 2.0;
+        left[l] = left[l].max(half);
+        right[l] = right[l].max(output_pin(node, 0).map_or(half, |p| p.x - node.pos.x));
+    }
+    let gap_width = |l: usize| match tracks[l].len() {
+        0 => MIN_GAP,
+        k => MIN_GAP.max(2.0 * GAP_MARGIN + (k - 1) as f32 * TRACK_SPACING),
+    };
+    let mut x = vec![LEFT + left[0]; last + 1];
+    for l in 1..=last {
+        x[l] = x[l - 1] + right[l - 1] + gap_width(l - 1) + left[l];
+    }
+    let track_x: HashMap<(usize, usize), f32> = tracks
+        .iter()
+        .enumerate()
+        .flat_map(|(l, gap)| {
+            let start = x[l] + right[l] + (gap_width(l) - (gap.len().max(1) - 1) as f32 * TRACK_SPACING) / 2.0;
+            gap.iter().enumerate().map(move |(i, net)| (net.from, start + i as f32 * TRACK_SPACING))
+        })
+        .collect();
+
+    let routes: Vec<(usize, usize, Vec<Pos2>)> = chains
+        .into_iter()
+        .map(|(t, pin, chain)| {
+            let mut points = Vec::new();
+            for link in chain {
+                let (from_y, to_y) = (out_y(&link), in_y(&link));
+                if let (Some(&x), true) = (track_x.get(&(link.from, link.out)), (from_y - to_y).abs() > 0.5) {
+                    points.push(Pos2::new(x, from_y));
+                    points.push(Pos2::new(x, to_y));
+                }
+            }
+            (t, pin, points)
+        })
+        .collect();
+
+    for (n, node) in circuit.nodes.iter_mut().enumerate() {
+        node.pos.x = x[layer_of[n]];
+        node.routes = vec![Vec::new(); node.inputs.len()];
+    }
+    for (t, pin, points) in routes {
+        circuit.nodes[t].routes[pin] = points;
+    }
+}
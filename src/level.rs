@@ -0,0 +1,174 @@
+// Vierwertige Logik: neben 0 und 1 gibt es Z (hochohmig, kein Treiber) und X (unbestimmt, z.B. ein offener
+// Gattereingang oder zwei Treiber, die gegeneinander arbeiten). Damit lassen sich Tri-State-Treiber und
+// Sammelleitungen simulieren. Wahrheitstabellen und Vergleiche bleiben zweiwertig, siehe `Tristate::compute_bit`.
+
+use egui::Color32;
+use serde::{Deserialize, Serialize};
+
+use crate::bus::Port;
+use crate::theme::level_color;
+use crate::LogicGates;
+
+/// Farben für Leitungen und Anzeigen; X soll sich deutlich von Low (rot) und High (grün) abheben
+const Z_COLOR: Color32 = Color32::from_gray(150);
+const X_COLOR: Color32 = Color32::from_rgb(230, 60, 230);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Level {
+    Low,
+    High,
+    /// Hochohmig: die Leitung wird von niemandem getrieben
+    Z,
+    /// Unbestimmt oder Konflikt
+    X,
+}
+
+impl From<bool> for Level {
+    fn from(bit: bool) -> Self {
+        if bit { Level::High } else { Level::Low }
+    }
+}
+
+impl Level {
+    /// Eindeutiger Pegel als Bit
+    pub fn bit(self) -> Option<bool> {
+        match self {
+            Level::Low => Some(false),
+            Level::High => Some(true),
+            Level::Z | Level::X => None,
+        }
+    }
+
+    pub fn symbol(self) -> char {
+        match self {
+            Level::Low => '0',
+            Level::High => '1',
+            Level::Z => 'Z',
+            Level::X => 'X',
+        }
+    }
+
+    pub fn color(self) -> Color32 {
+        match self {
+            Level::Low | Level::High => level_color(self == Level::High),
+            Level::Z => Z_COLOR,
+            Level::X => X_COLOR,
+        }
+    }
+
+    /// Strich- und Lückenlänge, an der sich der Pegel einer Leitung auch ohne Farbe erkennen lässt: Low gestrichelt,
+    /// Z gepunktet, High und X durchgezogen
+    pub fn dashes(self) -> Option<(f32, f32)> {
+        match self {
+            Level::Low => Some((6.0, 4.0)),
+            Level::Z => Some((2.0, 4.0)),
+            Level::High | Level::X => None,
+        }
+    }
+
+    /// An einem Gattereingang wirkt ein hochohmiger Pegel wie ein unbestimmter
+    fn driven(self) -> Level {
+        if self == Level::Z { Level::X } else { self }
+    }
+
+    fn not(self) -> Level {
+        self.driven().bit().map_or(Level::X, |b| (!b).into())
+    }
+}
+
+fn and(a: Level, b: Level) -> Level {
+    match (a.driven(), b.driven()) {
+        (Level::Low, _) | (_, Level::Low) => Level::Low,
+        (Level::High, Level::High) => Level::High,
+        _ => Level::X,
+    }
+}
+
+fn or(a: Level, b: Level) -> Level {
+    match (a.driven(), b.driven()) {
+        (Level::High, _) | (_, Level::High) => Level::High,
+        (Level::Low, Level::Low) => Level::Low,
+        _ => Level::X,
+    }
+}
+
+fn xor(a: Level, b: Level) -> Level {
+    match (a.bit(), b.bit()) {
+        (Some(a), Some(b)) => (a ^ b).into(),
+        _ => Level::X,
+    }
+}
+
+/// Ausgang eines Gatters; ein eindeutiger Eingang kann den Ausgang trotz X am anderen festlegen (0 bei AND, 1 bei OR)
+pub fn gate(gate: LogicGates, a: Level, b: Level) -> Level {
+    match gate {
+        LogicGates::AND => and(a, b),
+        LogicGates::OR => or(a, b),
+        LogicGates::XOR => xor(a, b),
+        LogicGates::NOT => a.not(),
+        LogicGates::NAND => and(a, b).not(),
+        LogicGates::NOR => or(a, b).not(),
+        LogicGates::XNOR => xor(a, b).not(),
+    }
+}
+
+/// Pegel einer Sammelleitung: hochohmige Treiber zählen nicht, widersprechen sich die übrigen, entsteht ein Konflikt
+pub fn resolve(drivers: impl IntoIterator<Item = Level>) -> Level {
+    drivers.into_iter().fold(Level::Z, |bus, driver| match (bus, driver) {
+        (Level::Z, level) | (level, Level::Z) => level,
+        (a, b) if a == b => a,
+        _ => Level::X,
+    })
+}
+
+/// Bauteile, deren Ausgang hochohmig sein kann bzw. die mehrere Treiber zusammenführen
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Tristate {
+    /// Gibt D weiter, solange EN High ist, sonst ist der Ausgang hochohmig
+    Buffer,
+    /// Wie der Treiber, gibt D aber negiert weiter
+    Inverter,
+    /// Leitung mit n Treibern, z.B. den Ausgängen mehrerer Tri-State-Treiber
+    Bus(usize),
+}
+
+impl Tristate {
+    pub fn name(&self) -> String {
+        match self {
+            Tristate::Buffer => "Tri-State-Treiber".to_string(),
+            Tristate::Inverter => "Tri-State-Inverter".to_string(),
+            Tristate::Bus(n) => format!("Sammelleitung ({} Treiber)", n),
+        }
+    }
+
+    pub fn input_ports(&self) -> Vec<Port> {
+        match self {
+            Tristate::Buffer | Tristate::Inverter => vec![Port::new("D", 1), Port::new("EN", 1)],
+            Tristate::Bus(n) => vec![Port::new("", 1); *n],
+        }
+    }
+
+    /// Vierwertiger Ausgang; offene Eingänge sind hochohmig
+    pub fn compute(&self, inputs: &[Level]) -> Level {
+        match self {
+            Tristate::Buffer | Tristate::Inverter => match inputs[1].driven() {
+                Level::Low => Level::Z,
+                Level::High if *self == Tristate::Inverter => inputs[0].not(),
+                Level::High => inputs[0].driven(),
+                _ => Level::X,
+            },
+            Tristate::Bus(_) => resolve(inputs.iter().copied()),
+        }
+    }
+
+    /// Zweiwertiger Ausgang für Wahrheitstabellen: eine Leitung ohne Treiber liegt über einen Pull-down-Widerstand
+    /// auf 0, bei mehreren Treibern setzt sich die 1 durch. Wo der Editor deshalb X zeigt, melden Wahrheitstabelle
+    /// und Vergleich den Konflikt, siehe `Circuit::conflict_rows`.
+    pub fn compute_bit(&self, inputs: &[bool]) -> bool {
+        match self {
+            Tristate::Buffer => inputs[0] && inputs[1],
+            Tristate::Inverter => !inputs[0] && inputs[1],
+            Tristate::Bus(_) => inputs.iter().any(|&b| b),
+        }
+    }
+}
@@ -1,11 +1,50 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")] // hide console window on Windows in release
 
+mod accessibility;
+mod algebra;
+mod bdd;
+mod bus;
+mod canonical_view;
+mod circuit;
+mod compare_view;
+mod components;
+mod editor;
+mod equivalence;
+mod exercise;
+mod export;
+mod expr;
+mod expression_view;
+mod history;
+mod kv_view;
+mod latex;
+mod layout;
+mod level;
+mod minimize;
+mod peripherals;
+mod presentation;
+mod progress;
+mod settings;
+mod subcircuit;
+mod synthesis;
+#[cfg(test)]
+mod test_support;
+mod text_table;
+mod theme;
+mod timing;
+mod truth_table;
+mod universal;
+mod worksheet;
+
 use std::{collections::HashMap};
 
 use eframe::epaint::{TextShape, Fonts};
 use egui::{Color32, Pos2, text::LayoutJob, TextFormat, FontId, FontFamily, Rect, menu};
 use egui_extras::{RetainedImage, TableBuilder, Column};
 use strum_macros::Display;
+use serde::{Deserialize, Serialize};
+
+use peripherals::LedColor;
+use theme::level_color;
 
 static ALL_GATES: [LogicGates; 7] = [LogicGates::AND, LogicGates::OR, LogicGates::XOR, LogicGates::NOT, LogicGates::NAND, LogicGates::NOR, LogicGates::XNOR];
 
@@ -67,13 +106,15 @@ fn load_icon(path: &str) -> eframe::IconData {
     }
 }
 
-fn configure_styles(ctx: &egui::Context) {
-    let style = (*ctx.style()).clone();
-    // Possible to define styles in here
+fn configure_styles(ctx: &egui::Context, theme: theme::Theme, patterns: bool) {
+    let mut style = (*ctx.style()).clone();
+    style.visuals = theme.visuals();
     ctx.set_style(style);
+    theme::apply(theme, patterns);
 }
 
-#[derive(Debug, PartialEq, Clone, Copy, Eq, Hash, Display)]
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, PartialEq, Clone, Copy, Eq, Hash, Display, Serialize, Deserialize)]
 enum LogicGates {
     AND,
     OR,
@@ -110,37 +151,180 @@ impl LogicGates {
             },
         }
     }
+
+    fn input_count(&self) -> usize {
+        if *self == LogicGates::NOT {
+            1
+        } else {
+            2
+        }
+    }
 }
 
-struct LogikgatterApp {
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
+enum View {
+    Gate,
+    Circuit,
+    Expression,
+    Kv,
+    Exercises,
+}
+
+/// Ein Tab mit eigener Ansicht und eigenem Zustand: Gatter, Schaltung, Ausdruck und KV-Diagramm werden getrennt
+/// simuliert, sodass sich mehrere Beispiele nebeneinander vorbereiten lassen
+struct Document {
+    /// Unterscheidet den Zustand der Bedienelemente verschiedener Tabs
+    id: usize,
+    name: String,
+    view: View,
     selected_gate: LogicGates,
-    gate_images: HashMap<LogicGates, RetainedImage>,
     input_a: bool,
     input_b: bool,
     output: bool,
+    circuit_editor: editor::CircuitEditor,
+    expression: expression_view::ExpressionView,
+    kv: kv_view::KvView,
+    comparison: Option<compare_view::Comparison>,
+    /// Konfliktzeilen der Schaltung für die Normalformen; nur neu berechnet, wenn sich die Schaltung ändert
+    conflicts: Option<(circuit::Circuit, Vec<usize>)>,
+}
+
+impl Document {
+    fn new(id: usize, view: View, gate: LogicGates) -> Self {
+        Self {
+            id,
+            name: format!("Dokument {}", id),
+            view,
+            selected_gate: gate,
+            input_a: false,
+            input_b: false,
+            output: false,
+            circuit_editor: Default::default(),
+            expression: Default::default(),
+            kv: Default::default(),
+            comparison: None,
+            conflicts: None,
+        }
+    }
+}
+
+struct LogikgatterApp {
+    documents: Vec<Document>,
+    /// Index des angezeigten Tabs
+    current: usize,
+    /// Nummer für den nächsten neuen Tab
+    next_document: usize,
+    gate_images: HashMap<LogicGates, RetainedImage>,
     show_error_popup: bool,
+    /// Übungen und Fortschritt gehören zum angemeldeten Schüler und sind daher in allen Tabs dieselben
+    exercises: exercise::ExerciseView,
+    show_canonical_forms: bool,
+    /// Pixel je Bildpunkt beim Export als PNG bzw. Vergrößerung beim SVG
+    export_scale: f32,
+    export_status: Option<String>,
+    presentation: presentation::Presentation,
+    theme: theme::Theme,
+    /// Pegel zusätzlich mit 1/0 und Linienmustern kennzeichnen
+    patterns: bool,
+    /// Gatter, mit dem neue Tabs beginnen
+    default_gate: LogicGates,
 }
 
 impl LogikgatterApp {
     fn new(cc: &eframe::CreationContext<'_>) -> Self {
-        configure_styles(&cc.egui_ctx);
+        // Einstellungen und Tabs der letzten Sitzung
+        let settings = settings::Settings::load(cc.storage);
+        configure_styles(&cc.egui_ctx, settings.theme, settings.patterns);
+        let mut documents: Vec<Document> = settings
+            .tabs
+            .iter()
+            .enumerate()
+            .map(|(i, tab)| {
+                let mut document = Document::new(i + 1, tab.view, settings.default_gate);
+                document.name = tab.name.clone();
+                document.selected_gate = tab.gate;
+                document.input_a = tab.input_a;
+                document.input_b = tab.input_b;
+                // Eine von Hand veränderte Datei darf das Programm nicht zum Absturz bringen
+                if tab.circuit.is_valid() {
+                    document.circuit_editor.load(tab.circuit.clone());
+                }
+                if let Some(expression) = &tab.expression {
+                    document.expression.set_input(expression.clone());
+                }
+                if let Some(table) = &tab.kv {
+                    document.kv.load_table(table.clone());
+                }
+                document
+            })
+            .collect();
+        if documents.is_empty() {
+            documents.push(Document::new(1, View::Gate, settings.default_gate));
+        }
         let img_map: HashMap<LogicGates, RetainedImage> = load_images();
         Self {
-            selected_gate: LogicGates::AND,
+            current: settings.current.min(documents.len() - 1),
+            next_document: documents.len() + 1,
+            documents,
             gate_images: img_map,
-            input_a: false,
-            input_b: false,
-            output: false,
             show_error_popup: false,
+            exercises: exercise::ExerciseView::new(),
+            show_canonical_forms: false,
+            export_scale: settings.export_scale,
+            export_status: None,
+            presentation: Default::default(),
+            theme: settings.theme,
+            patterns: settings.patterns,
+            default_gate: settings.default_gate,
+        }
+    }
+
+    fn settings(&self) -> settings::Settings {
+        let tabs = self
+            .documents
+            .iter()
+            .map(|document| settings::Tab {
+                name: document.name.clone(),
+                view: document.view,
+                gate: document.selected_gate,
+                input_a: document.input_a,
+                input_b: document.input_b,
+                circuit: document.circuit_editor.circuit.clone(),
+                expression: Some(document.expression.input().to_string()),
+                kv: Some(document.kv.table().clone()),
+            })
+            .collect();
+        settings::Settings {
+            theme: self.theme,
+            patterns: self.patterns,
+            export_scale: self.export_scale,
+            default_gate: self.default_gate,
+            tabs,
+            current: self.current,
         }
     }
 }
 
 fn gen_bool_label(val: bool, label: &str) -> egui::Label {
-    if val {
-        egui::Label::new(egui::RichText::new(format!("{}{}", label, "High")).color(Color32::GREEN))
-    } else {
-        egui::Label::new(egui::RichText::new(format!("{}{}", label, "Low")).color(Color32::RED))
+    let text = match (val, theme::patterns()) {
+        (true, false) => "High",
+        (false, false) => "Low",
+        (true, true) => "High (1)",
+        (false, true) => "Low (0)",
+    };
+    egui::Label::new(egui::RichText::new(format!("{}{}", label, text)).color(level_color(val)))
+}
+
+// Kurzform für größere Tabellen, in denen "High" / "Low" zu breit wäre
+fn gen_bit_label(val: bool) -> egui::Label {
+    egui::Label::new(egui::RichText::new(if val { "1" } else { "0" }).color(level_color(val)))
+}
+
+// Ausgangswert einer Wahrheitstabelle, beliebige Werte ("X") grau
+fn gen_value_label(val: truth_table::Value) -> egui::Label {
+    match val {
+        truth_table::Value::DontCare => egui::Label::new(egui::RichText::new("X").color(Color32::GRAY)),
+        val => gen_bit_label(val == truth_table::Value::One),
     }
 }
 
@@ -152,39 +336,37 @@ fn open_file(app: &mut LogikgatterApp, name: &str) {
     }
 }
 
-impl eframe::App for LogikgatterApp {
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        // Falls Datei nicht gefunden wird, zeige Error Popup an
-        if self.show_error_popup {
-            egui::Window::new("Fehler - Datei nicht gefunden")
-                .open(&mut self.show_error_popup)
-                .resizable(false)
-                .collapsible(false)
-                .show(ctx, |ui| {
-                    ui.label("Die Datei konnte nicht geöffnet werden, da Sie sich nicht im Ordner des Programms befindet.");
-                });
+impl Document {
+    fn show_gate_view(&mut self, ctx: &egui::Context, ui: &mut egui::Ui, gate_images: &HashMap<LogicGates, RetainedImage>) {
+        // Tastatur: A / B schalten die Eingänge, Pfeil hoch / runter wechselt das Gatter
+        for letter in accessibility::letters_pressed(ui) {
+            match letter {
+                'A' => self.input_a = !self.input_a,
+                'B' if self.selected_gate != LogicGates::NOT => self.input_b = !self.input_b,
+                _ => {}
+            }
+        }
+        let index = ALL_GATES.iter().position(|&gate| gate == self.selected_gate).unwrap();
+        if accessibility::key_pressed(ui, egui::Key::ArrowDown) {
+            self.selected_gate = ALL_GATES[(index + 1) % ALL_GATES.len()];
+        } else if accessibility::key_pressed(ui, egui::Key::ArrowUp) {
+            self.selected_gate = ALL_GATES[(index + ALL_GATES.len() - 1) % ALL_GATES.len()];
         }
-        
         self.output = LogicGates::compute(&self.selected_gate, self.input_a, self.input_b);
-        egui::CentralPanel::default().show(ctx, |ui| {
-            // Menu Bar mit Hilfe Button
-            menu::bar(ui, |ui| {
-                ui.menu_button("Hilfe", |ui| {
-                    if ui.button("Theorie Logikgatter").clicked() {
-                        open_file(self, "Theorie.pdf");
-                    }
-                    if ui.button("Bedienungsanleitung").clicked() {
-                        open_file(self, "Bedienungsanleitung.pdf");
-                    }
-                })
-            });
+        // Die Ansicht ist auf die ursprüngliche Fensterbreite von 640 ausgelegt. Sie wird daher in einer
+        // Spalte dieser Breite zentriert und alle Markierungen um denselben Abstand verschoben.
+        let column = Rect::from_min_size(Pos2::new(ui.max_rect().center().x - 312.0, ui.cursor().min.y), egui::vec2(624.0, ui.available_height()));
+        let offset = egui::vec2(column.min.x - ui.max_rect().min.x, 0.0);
+        ui.allocate_ui_at_rect(column, |ui| {
             ui.with_layout(egui::Layout::from_main_dir_and_cross_align(egui::Direction::TopDown, egui::Align::Center), |ui| {
                 // Überschrift + Erklärtext
-                ui.label(egui::RichText::new("Logikgatter").heading().color(egui::Color32::from_rgb(255, 255, 255)));
-                ui.label("Wähle ein Logikgatter aus und setze die Pegel A / B auf High (Checked) oder Low (Unchecked).");
+                ui.label(egui::RichText::new("Logikgatter").heading().color(ui.visuals().strong_text_color()));
+                // Weitere Zeilen würden die fest platzierten Markierungen unten verschieben, daher als Tooltip
+                ui.label("Wähle ein Logikgatter aus und schalte die Eingänge A / B auf High (an) oder Low (aus).")
+                    .on_hover_text("Tastatur: A / B schalten die Eingänge, Pfeil hoch / runter wechselt das Gatter.");
                 ui.separator();
                 ui.horizontal(|ui| {
-                    // Controls (Select Menu + Checkboxes)
+                    // Controls (Select Menu + Schalter)
                     ui.label("Logikgatter auswählen:");
                     egui::ComboBox::from_id_source(0)
                         .selected_text(self.selected_gate.to_string())
@@ -193,15 +375,15 @@ impl eframe::App for LogikgatterApp {
                                 ui.selectable_value(&mut self.selected_gate, gate, gate.to_string());
                             }
                         });
-                    ui.checkbox(&mut self.input_a, "Eingang A");
+                    peripherals::toggle_switch(ui, &mut self.input_a, "Eingang A");
                     if self.selected_gate != LogicGates::NOT {
-                        ui.checkbox(&mut self.input_b, "Eingang B");
+                        peripherals::toggle_switch(ui, &mut self.input_b, "Eingang B");
                     }
                 });
                 // Wahrheitstabelle
                 ui.horizontal(|ui| {
                     ui.vertical(|ui| {
-                        ui.label(egui::RichText::new("Wahrheitstabelle").color(egui::Color32::from_rgb(255, 255, 255)));
+                        ui.label(egui::RichText::new("Wahrheitstabelle").color(ui.visuals().strong_text_color()));
                         if self.selected_gate == LogicGates::NOT {
                             let bool_table = TableBuilder::new(ui)
                                 .striped(true)
@@ -225,7 +407,10 @@ impl eframe::App for LogikgatterApp {
                                                 ui.add(gen_bool_label(table_a, ""));
                                             });
                                             row.col(|ui| {
-                                                ui.add(gen_bool_label(LogicGates::compute(&self.selected_gate, table_a, false), ""));
+                                                let output = LogicGates::compute(&self.selected_gate, table_a, false);
+                                                let response = ui.add(gen_bool_label(output, ""));
+                                                let text = format!("A {}: Ausgang {}", accessibility::level_name(table_a), accessibility::level_name(output));
+                                                accessibility::describe(&response, egui::WidgetType::Label, text);
                                             });
                                         })
                                     }
@@ -261,7 +446,15 @@ impl eframe::App for LogikgatterApp {
                                                     ui.add(gen_bool_label(table_b, ""));
                                                 });
                                                 row.col(|ui| {
-                                                    ui.add(gen_bool_label(LogicGates::compute(&self.selected_gate, table_a, table_b), ""));
+                                                    let output = LogicGates::compute(&self.selected_gate, table_a, table_b);
+                                                    let response = ui.add(gen_bool_label(output, ""));
+                                                    let text = format!(
+                                                        "A {}, B {}: Ausgang {}",
+                                                        accessibility::level_name(table_a),
+                                                        accessibility::level_name(table_b),
+                                                        accessibility::level_name(output)
+                                                    );
+                                                    accessibility::describe(&response, egui::WidgetType::Label, text);
                                                 });
                                             })
                                         }
@@ -270,9 +463,16 @@ impl eframe::App for LogikgatterApp {
                         }
                     });
                     // KV-Diagramm
-                    self.gate_images.get(&self.selected_gate).unwrap().show_size(ui, egui::vec2(640.0/3.15, 640.0/3.15));
+                    let response = gate_images.get(&self.selected_gate).unwrap().show_size(ui, egui::vec2(640.0/3.15, 640.0/3.15));
+                    // Die farbigen Kreise auf dem Symbol werden nur gezeichnet, daher hier als Text
+                    let mut text = format!("{}-Gatter: Eingang A {}", self.selected_gate, accessibility::level_name(self.input_a));
+                    if self.selected_gate != LogicGates::NOT {
+                        text += &format!(", Eingang B {}", accessibility::level_name(self.input_b));
+                    }
+                    text += &format!(", Ausgang {}", accessibility::level_name(self.output));
+                    accessibility::describe(&response, egui::WidgetType::Label, text);
                     ui.vertical(|ui| {
-                        ui.label(egui::RichText::new("Karnaugh-Veitch-Diagramm").color(egui::Color32::from_rgb(255, 255, 255)));
+                        ui.label(egui::RichText::new("Karnaugh-Veitch-Diagramm").color(ui.visuals().strong_text_color()));
                         ui.push_id(100, |ui| {
                             if self.selected_gate == LogicGates::NOT {
                                 let kv_diagram = TableBuilder::new(ui)
@@ -356,8 +556,10 @@ impl eframe::App for LogikgatterApp {
                     });
                 });
                 ui.add(gen_bool_label(self.output, "Ausgangspegel: "));
+                peripherals::led(ui, self.output, LedColor::Red);
             });
             let painter = ui.painter();
+            let label_color = ui.visuals().strong_text_color();
             // Labels am KV-Diagramm
             let mut job = LayoutJob::default();
             job.append(
@@ -365,11 +567,11 @@ impl eframe::App for LogikgatterApp {
                 0.0,
                 TextFormat {
                     font_id: FontId::new(14.0, FontFamily::Proportional),
-                    color: Color32::WHITE,
+                    color: label_color,
                     ..Default::default()
                 },
             );
-            let mut text_shape = TextShape::new(Pos2::new(529.0, 118.5), Fonts::layout_job(&ctx.fonts(), job));
+            let mut text_shape = TextShape::new(Pos2::new(529.0, 118.5) + offset, Fonts::layout_job(&ctx.fonts(), job));
             painter.add(text_shape);
             if self.selected_gate != LogicGates::NOT {
                 job = LayoutJob::default();
@@ -378,12 +580,12 @@ impl eframe::App for LogikgatterApp {
                     0.0,
                     TextFormat {
                         font_id: FontId::new(14.0, FontFamily::Proportional),
-                        color: Color32::WHITE,
+                        color: label_color,
                         ..Default::default()
                     },
                 );
-                text_shape = TextShape::new(Pos2::new(415.0, 178.5), Fonts::layout_job(&ctx.fonts(), job));
-                text_shape.angle = -3.14/2.0;
+                text_shape = TextShape::new(Pos2::new(415.0, 178.5) + offset, Fonts::layout_job(&ctx.fonts(), job));
+                text_shape.angle = -std::f32::consts::FRAC_PI_2;
                 painter.add(text_shape);
             }
             // KV-Diagramm Markierungen
@@ -396,7 +598,7 @@ impl eframe::App for LogikgatterApp {
                     col_width * 2.0
                 };
                 let input_a_y = 162.5;
-                painter.rect_stroke(Rect::from_two_pos(Pos2::new(input_a_x, input_a_y), Pos2::new(input_a_x + col_width, input_a_y + row_height * 2.0)), 1.0, (2.0, Color32::BLUE));
+                painter.rect_stroke(Rect::from_two_pos(Pos2::new(input_a_x, input_a_y) + offset, Pos2::new(input_a_x + col_width, input_a_y + row_height * 2.0) + offset), 1.0, (2.0, Color32::BLUE));
                 
                 let input_b_x = 435.0 + col_width;
                 let input_b_y = 162.5 + if self.input_b {
@@ -404,7 +606,7 @@ impl eframe::App for LogikgatterApp {
                 } else {
                     row_height
                 };
-                painter.rect_stroke(Rect::from_two_pos(Pos2::new(input_b_x, input_b_y), Pos2::new(input_b_x + col_width * 2.0, input_b_y + row_height)), 1.0, (2.0, Color32::LIGHT_GRAY));
+                painter.rect_stroke(Rect::from_two_pos(Pos2::new(input_b_x, input_b_y) + offset, Pos2::new(input_b_x + col_width * 2.0, input_b_y + row_height) + offset), 1.0, (2.0, Color32::LIGHT_GRAY));
             } else {
                 let col_width = 97.8;
                 let tmp_x = 435.0 + if self.input_a {
@@ -413,47 +615,368 @@ impl eframe::App for LogikgatterApp {
                     col_width
                 };
                 let tmp_y = 162.5;
-                painter.rect_stroke(Rect::from_two_pos(Pos2::new(tmp_x, tmp_y), Pos2::new(tmp_x + col_width, tmp_y + 20.0)), 1.0, (1.0, Color32::LIGHT_BLUE));
+                painter.rect_stroke(Rect::from_two_pos(Pos2::new(tmp_x, tmp_y) + offset, Pos2::new(tmp_x + col_width, tmp_y + 20.0) + offset), 1.0, (1.0, Color32::LIGHT_BLUE));
             }
-            // Farbige Kreise auf dem Schaltplansymbol
-            if self.selected_gate != LogicGates::NOT {
-                painter.circle_filled(Pos2::new(320.25, 144.5), 5.0, if self.input_a {
-                    Color32::GREEN
-                } else {
-                    Color32::RED
-                });
-                painter.circle_filled(Pos2::new(343.0, 144.5), 5.0, if self.input_b {
-                    Color32::GREEN
+            // Farbige Kreise auf dem Schaltplansymbol, auf Wunsch zusätzlich mit 1/0 beschriftet
+            let pin = |pos: Pos2, val: bool| {
+                if theme::patterns() {
+                    painter.circle_filled(pos + offset, 7.0, level_color(val));
+                    painter.text(pos + offset, egui::Align2::CENTER_CENTER, if val { "1" } else { "0" }, FontId::proportional(10.0), Color32::BLACK);
                 } else {
-                    Color32::RED
-                });
+                    painter.circle_filled(pos + offset, 5.0, level_color(val));
+                }
+            };
+            if self.selected_gate != LogicGates::NOT {
+                pin(Pos2::new(320.25, 144.5), self.input_a);
+                pin(Pos2::new(343.0, 144.5), self.input_b);
             } else {
-                painter.circle_filled(Pos2::new(331.6, 144.5), 5.0, if self.input_a {
-                    Color32::GREEN
+                pin(Pos2::new(331.6, 144.5), self.input_a);
+            }
+            pin(Pos2::new(331.6, 258.5), self.output);
+        });
+    }
+}
+
+impl Document {
+    /// Zeilen mit gegeneinander arbeitenden Treibern; das Auswerten aller Zeilen ist zu teuer für jedes Bild
+    fn conflict_rows(&mut self) -> &[usize] {
+        let circuit = &self.circuit_editor.circuit;
+        if self.conflicts.as_ref().is_none_or(|(cached, _)| cached != circuit) {
+            self.conflicts = Some((circuit.clone(), circuit.conflict_rows()));
+        }
+        &self.conflicts.as_ref().unwrap().1
+    }
+
+    fn show_canonical_panel(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Normalformen");
+        let table = match self.view {
+            View::Gate => Ok(truth_table::TruthTable::from_gate(self.selected_gate)),
+            View::Circuit => {
+                let circuit = &self.circuit_editor.circuit;
+                if circuit.output_nodes().is_empty() {
+                    Err("Die Schaltung hat noch keinen Ausgang.".to_string())
+                } else if circuit.input_names().len() > canonical_view::MAX_INPUTS {
+                    Err(format!("Normalformen werden nur für Schaltungen mit höchstens {} Eingängen angezeigt.", canonical_view::MAX_INPUTS))
                 } else {
-                    Color32::RED
+                    Ok(truth_table::TruthTable::from_circuit(circuit))
+                }
+            }
+            View::Expression => match self.expression.parsed() {
+                None => Err("Der Ausdruck ist ungültig.".to_string()),
+                Some((_, vars)) if vars.len() > canonical_view::MAX_INPUTS => {
+                    Err(format!("Normalformen werden nur für Ausdrücke mit höchstens {} Variablen angezeigt.", canonical_view::MAX_INPUTS))
+                }
+                Some((expr, vars)) => Ok(truth_table::TruthTable::from_expr(expr, vars)),
+            },
+            View::Kv => Ok(self.kv.table().clone()),
+            // Die Normalformen würden die Lösung der Aufgabe verraten
+            View::Exercises => Err("In den Übungen werden keine Normalformen angezeigt.".to_string()),
+        };
+        let conflicts = if self.view == View::Circuit && table.is_ok() { self.conflict_rows().to_vec() } else { Vec::new() };
+        if !conflicts.is_empty() {
+            let rows: Vec<String> = conflicts.iter().map(|row| row.to_string()).collect();
+            ui.label(egui::RichText::new(format!(
+                "In den Zeilen {} arbeiten Treiber auf einer Sammelleitung gegeneinander. Der Editor zeigt dort X, die Tabelle nimmt an, dass sich die 1 durchsetzt.",
+                rows.join(", ")
+            )).color(ui.visuals().warn_fg_color));
+        }
+        match table {
+            Ok(table) => canonical_view::show(ui, &table),
+            Err(message) => {
+                ui.label(message);
+            }
+        }
+    }
+}
+
+impl Document {
+    /// Schaltung zur aktuellen Ansicht, z.B. als Ausgangspunkt einer Umformung
+    fn current_circuit(&self) -> Result<circuit::Circuit, String> {
+        match self.view {
+            View::Gate => {
+                let mut circuit = circuit::Circuit::from_gate(self.selected_gate);
+                circuit.set_input_values(&[self.input_a, self.input_b]);
+                Ok(circuit)
+            }
+            View::Circuit => Ok(self.circuit_editor.circuit.clone()),
+            View::Expression => match self.expression.parsed() {
+                None => Err("Der Ausdruck ist ungültig.".to_string()),
+                Some((expr, vars)) => circuit::Circuit::from_exprs(vars, &[("Y".to_string(), expr.clone())]),
+            },
+            View::Kv => {
+                let table = self.kv.table();
+                let outputs: Vec<String> = table.outputs.iter().map(|o| o.name.clone()).collect();
+                synthesis::and_or(&table.inputs, &outputs, self.kv.shared_dnf())
+            }
+            View::Exercises => Err("In den Übungen steht die Umformung nicht zur Verfügung.".to_string()),
+        }
+    }
+
+    /// Inhalt des Tabs; in der Präsentation ohne Werkzeugleiste des Editors
+    fn show(&mut self, ctx: &egui::Context, ui: &mut egui::Ui, gate_images: &HashMap<LogicGates, RetainedImage>, exercises: &mut exercise::ExerciseView, presenting: bool) {
+        ui.push_id(self.id, |ui| match self.view {
+            View::Gate => self.show_gate_view(ctx, ui, gate_images),
+            View::Circuit if presenting => self.circuit_editor.show_presentation(ui),
+            View::Circuit => {
+                self.circuit_editor.show(ui);
+            }
+            View::Expression => {
+                let synthesized = self.expression.show(ui);
+                self.open_synthesized(synthesized);
+            }
+            View::Kv => {
+                let synthesized = self.kv.show(ui);
+                self.open_synthesized(synthesized);
+            }
+            View::Exercises => exercises.show(ui),
+        });
+    }
+
+    fn open_synthesized(&mut self, synthesized: Option<compare_view::Comparison>) {
+        if let Some(mut comparison) = synthesized {
+            // Eine eigene Schaltung im Editor wird zum Vergleich daneben gezeigt
+            if !self.circuit_editor.circuit.nodes.is_empty() {
+                comparison.set_left("Deine Schaltung", self.circuit_editor.circuit.clone());
+            }
+            self.comparison = Some(comparison);
+        }
+    }
+}
+
+impl LogikgatterApp {
+    /// Leiste mit allen Tabs; ein Rechtsklick auf einen Tab erlaubt das Umbenennen
+    fn show_tabs(&mut self, ui: &mut egui::Ui) {
+        let mut close = None;
+        ui.horizontal_wrapped(|ui| {
+            for (i, document) in self.documents.iter_mut().enumerate() {
+                let response = ui.selectable_label(i == self.current, &document.name);
+                if response.clicked() {
+                    self.current = i;
+                }
+                response.context_menu(|ui| {
+                    ui.label("Name:");
+                    ui.text_edit_singleline(&mut document.name);
                 });
+                if ui.small_button("×").on_hover_text("Tab schließen").clicked() {
+                    close = Some(i);
+                }
+                ui.separator();
             }
-            painter.circle_filled(Pos2::new(331.6, 258.5), 5.0, if self.output {
-                Color32::GREEN
-            } else {
-                Color32::RED
+            if ui.button("+").on_hover_text("Neuer Tab").clicked() {
+                let view = self.documents[self.current].view;
+                self.documents.push(Document::new(self.next_document, view, self.default_gate));
+                self.next_document += 1;
+                self.current = self.documents.len() - 1;
+            }
+        });
+        if let Some(i) = close {
+            self.documents.remove(i);
+            if self.documents.is_empty() {
+                self.documents.push(Document::new(self.next_document, View::Gate, self.default_gate));
+                self.next_document += 1;
+            }
+            if self.current > i || self.current == self.documents.len() {
+                self.current -= 1;
+            }
+        }
+    }
+
+    /// Schaltplan der aktuellen Ansicht als Datei, ohne Bildschirmfoto
+    fn show_export_menu(&mut self, ui: &mut egui::Ui) {
+        let circuit = match self.documents[self.current].current_circuit() {
+            Ok(circuit) => circuit,
+            Err(e) => {
+                ui.label(e);
+                return;
+            }
+        };
+        let size = editor::schematic_size(&circuit) * self.export_scale;
+        ui.horizontal(|ui| {
+            ui.label("Auflösung:");
+            for scale in [1.0, 2.0, 3.0, 4.0] {
+                ui.selectable_value(&mut self.export_scale, scale, format!("{}×", scale));
+            }
+        });
+        ui.label(format!("{} × {} Pixel", size.x.round(), size.y.round()));
+        ui.separator();
+        // Nach dem Tab benannt, damit der Export eines anderen Tabs ihn nicht überschreibt
+        let file = |extension| export::file_name(&self.documents[self.current].name, extension);
+        let (svg, png, tex) = (file("svg"), file("png"), file("tex"));
+        let result = if ui.button(format!("Als SVG ({})", svg)).clicked() {
+            Some((svg, Ok(export::svg(&circuit, self.export_scale).into_bytes())))
+        } else if ui.button(format!("Als PNG ({})", png)).clicked() {
+            Some((png, export::png(&circuit, self.export_scale)))
+        } else if ui.button(format!("Als CircuitTikZ ({})", tex)).on_hover_text("Benötigt das LaTeX-Paket circuitikz").clicked() {
+            Some((tex, Ok(export::circuitikz_document(&circuit).into_bytes())))
+        } else {
+            None
+        };
+        if let Some((file, content)) = result {
+            self.export_status = Some(export::write_file(&file, content));
+            ui.close_menu();
+        }
+    }
+
+    /// Farbschema und zusätzliche Kennzeichnung der Pegel für Schüler mit Farbsehschwäche, dazu das Gatter, mit dem
+    /// neue Tabs beginnen
+    fn show_theme_menu(&mut self, ui: &mut egui::Ui) {
+        let mut changed = false;
+        for theme in theme::Theme::ALL {
+            if ui.radio_value(&mut self.theme, theme, theme.label()).changed() {
+                self.patterns = theme.patterns_by_default();
+                changed = true;
+            }
+        }
+        ui.separator();
+        changed |= ui.checkbox(&mut self.patterns, "Pegel zusätzlich mit 1/0 und Linienmustern").changed();
+        if changed {
+            configure_styles(ui.ctx(), self.theme, self.patterns);
+        }
+        ui.separator();
+        ui.label("Gatter in neuen Tabs:");
+        ui.horizontal_wrapped(|ui| {
+            for gate in ALL_GATES {
+                ui.selectable_value(&mut self.default_gate, gate, gate.to_string());
+            }
+        });
+    }
+
+    /// Nur der Inhalt des aktuellen Tabs, ohne Menü, Tabs und Seitenleisten; die Tabs werden wie Folien durchgeblättert
+    fn show_presentation(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
+        let mut exit = false;
+        match self.presentation.command(ctx) {
+            Some(presentation::Command::Next) => self.current = (self.current + 1).min(self.documents.len() - 1),
+            Some(presentation::Command::Previous) => self.current = self.current.saturating_sub(1),
+            Some(presentation::Command::Exit) => exit = true,
+            None => {}
+        }
+        egui::TopBottomPanel::bottom("presentation_footer").show(ctx, |ui| {
+            exit |= self.presentation.show_footer(ui, self.current, self.documents.len());
+        });
+        egui::CentralPanel::default().show(ctx, |ui| {
+            let document = &mut self.documents[self.current];
+            document.show(ctx, ui, &self.gate_images, &mut self.exercises, true);
+        });
+        self.presentation.show_laser(ctx);
+        if exit {
+            self.presentation.stop(ctx, frame);
+            ctx.request_repaint();
+        }
+    }
+}
+
+impl eframe::App for LogikgatterApp {
+    fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
+        if self.presentation.is_active() {
+            self.show_presentation(ctx, frame);
+            return;
+        }
+        if !accessibility::typing(ctx) && ctx.input_mut().consume_key(egui::Modifiers::NONE, egui::Key::F5) {
+            self.presentation.start(ctx, frame);
+        }
+        // Falls Datei nicht gefunden wird, zeige Error Popup an
+        if self.show_error_popup {
+            egui::Window::new("Fehler - Datei nicht gefunden")
+                .open(&mut self.show_error_popup)
+                .resizable(false)
+                .collapsible(false)
+                .show(ctx, |ui| {
+                    ui.label("Die Datei konnte nicht geöffnet werden, da Sie sich nicht im Ordner des Programms befindet.");
+                });
+        }
+        
+        let document = &mut self.documents[self.current];
+        if let Some(comparison) = &document.comparison {
+            let mut open = true;
+            if let Some(circuit) = comparison.show(ctx, &mut open) {
+                document.circuit_editor.load(circuit);
+                document.view = View::Circuit;
+                open = false;
+            }
+            if !open {
+                document.comparison = None;
+            }
+        }
+
+        if self.show_canonical_forms {
+            egui::SidePanel::right("canonical_forms")
+                .default_width(320.0)
+                .show(ctx, |ui| document.show_canonical_panel(ui));
+        }
+
+        egui::CentralPanel::default().show(ctx, |ui| {
+            // Menu Bar mit Hilfe Button
+            menu::bar(ui, |ui| {
+                ui.menu_button("Hilfe", |ui| {
+                    if ui.button("Theorie Logikgatter").clicked() {
+                        open_file(self, "Theorie.pdf");
+                    }
+                    if ui.button("Bedienungsanleitung").clicked() {
+                        open_file(self, "Bedienungsanleitung.pdf");
+                    }
+                });
+                ui.menu_button("Umformen", |ui| {
+                    let document = &mut self.documents[self.current];
+                    let source = document.current_circuit();
+                    for (target, text) in [(LogicGates::NAND, "Nur NAND-Gatter"), (LogicGates::NOR, "Nur NOR-Gatter")] {
+                        let mut button = ui.add_enabled(source.is_ok(), egui::Button::new(text));
+                        if let Err(e) = &source {
+                            button = button.on_disabled_hover_text(e);
+                        }
+                        if button.clicked() {
+                            document.comparison = Some(compare_view::Comparison::universal(source.clone().unwrap(), target));
+                            ui.close_menu();
+                        }
+                    }
+                });
+                ui.menu_button("Exportieren", |ui| self.show_export_menu(ui));
+                ui.menu_button("Darstellung", |ui| self.show_theme_menu(ui));
+                ui.separator();
+                // Auswahl der Ansicht
+                let view = &mut self.documents[self.current].view;
+                ui.selectable_value(view, View::Gate, "Logikgatter");
+                ui.selectable_value(view, View::Circuit, "Schaltung");
+                ui.selectable_value(view, View::Expression, "Ausdruck");
+                ui.selectable_value(view, View::Kv, "KV-Diagramm");
+                ui.selectable_value(view, View::Exercises, "Übungen");
+                ui.separator();
+                ui.toggle_value(&mut self.show_canonical_forms, "Normalformen");
+                if ui.button("Präsentation").on_hover_text("Vollbild mit den Tabs als Folien (F5)").clicked() {
+                    self.presentation.start(ctx, frame);
+                }
+                if let Some(status) = &self.export_status {
+                    ui.separator();
+                    ui.label(status);
+                }
             });
+            self.show_tabs(ui);
+            ui.separator();
+            let document = &mut self.documents[self.current];
+            document.show(ctx, ui, &self.gate_images, &mut self.exercises, false);
         });
     }
+
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        self.settings().save(storage);
+    }
+
+    fn on_close_event(&mut self) -> bool {
+        self.exercises.save();
+        true
+    }
 }
 
 fn main() {
     // Initialisiert Fenster mit einigen Eistellungen
     let options = eframe::NativeOptions {
-        initial_window_size: Some(egui::vec2(640.0, 350.0)),
-        resizable: false,
+        initial_window_size: Some(egui::vec2(960.0, 600.0)),
+        min_window_size: Some(egui::vec2(640.0, 350.0)),
         icon_data: Some(load_icon("assets/logo_small.png")),
         ..Default::default()
     };
     
     eframe::run_native(
-        "Logikgatter Erklärprogramm",
+        settings::APP_NAME,
         options,
         Box::new(|cc| Box::new(LogikgatterApp::new(cc))),
     );
@@ -0,0 +1,368 @@
+// Minimierung nach Quine-McCluskey: Primimplikanten bilden und daraus eine minimale Überdeckung auswählen.
+// Zeilen mit beliebigem Wert ("X") dürfen in Blöcke einbezogen werden, müssen aber nicht überdeckt werden.
+
+use std::collections::BTreeSet;
+
+use crate::expr::{and_of, or_of, Expr};
+use crate::truth_table::{Output, Value};
+
+/// Block im KV-Diagramm: alle Zeilen, die an den nicht maskierten Stellen mit `bits` übereinstimmen.
+/// Bit `n - 1 - i` gehört wie bei der Zeilennummer zum Eingang `i`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Implicant {
+    pub bits: usize,
+    /// Gesetzte Bits sind Eingänge, die im Block beide Werte annehmen und daher wegfallen
+    pub mask: usize,
+}
+
+impl Implicant {
+    pub fn covers(&self, row: usize) -> bool {
+        row & !self.mask == self.bits
+    }
+
+    pub fn literal_count(&self, input_count: usize) -> usize {
+        input_count - self.mask.count_ones() as usize
+    }
+
+    /// Eingänge, die im Block festgelegt sind, und ihr Wert
+    fn fixed_inputs(&self, input_count: usize) -> impl Iterator<Item = (usize, bool)> + '_ {
+        (0..input_count)
+            .map(move |i| (i, 1 << (input_count - 1 - i)))
+            .filter(|(_, bit)| self.mask & bit == 0)
+            .map(|(i, bit)| (i, self.bits & bit != 0))
+    }
+
+    /// UND-Term für die DNF, z.B. `A ∧ ¬C`
+    pub fn product(&self, input_count: usize) -> Expr {
+        and_of(self.fixed_inputs(input_count).map(|(i, v)| literal(i, v)).collect())
+    }
+
+    /// ODER-Term für die KNF aus einem Block von Nullen, z.B. `¬A ∨ C`
+    pub fn sum(&self, input_count: usize) -> Expr {
+        or_of(self.fixed_inputs(input_count).map(|(i, v)| literal(i, !v)).collect())
+    }
+}
+
+fn literal(var: usize, value: bool) -> Expr {
+    if value {
+        Expr::Var(var)
+    } else {
+        Expr::Not(Box::new(Expr::Var(var)))
+    }
+}
+
+/// Alle Primimplikanten der Zeilen `on` (mit den beliebigen Zeilen `dc`), die mindestens eine Zeile aus `on` enthalten
+pub fn prime_implicants(on: &[usize], dc: &[usize]) -> Vec<Implicant> {
+    let mut current: BTreeSet<Implicant> = on.iter().chain(dc).map(|&row| Implicant { bits: row, mask: 0 }).collect();
+    let mut primes = BTreeSet::new();
+    while !current.is_empty() {
+        let mut next = BTreeSet::new();
+        let mut combined = BTreeSet::new();
+        for a in &current {
+            for b in &current {
+                let diff = a.bits ^ b.bits;
+                if a < b && a.mask == b.mask && diff.count_ones() == 1 {
+                    next.insert(Implicant { bits: a.bits & !diff, mask: a.mask | diff });
+                    combined.insert(*a);
+                    combined.insert(*b);
+                }
+            }
+        }
+        primes.extend(current.difference(&combined).copied());
+        current = next;
+    }
+    primes.into_iter().filter(|p| on.iter().any(|&row| p.covers(row))).collect()
+}
+
+/// Kleinste Auswahl von Primimplikanten, die alle Zeilen aus `on` überdeckt:
+/// zuerst möglichst wenige Terme, bei Gleichstand möglichst wenige Literale
+pub fn minimal_cover(on: &[usize], dc: &[usize], input_count: usize) -> Vec<Implicant> {
+    fn search(
+        uncovered: &[usize],
+        primes: &[Implicant],
+        chosen: &mut Vec<Implicant>,
+        best: &mut Option<(usize, usize, Vec<Implicant>)>,
+        input_count: usize,
+    ) {
+        let cost = (chosen.len(), chosen.iter().map(|p| p.literal_count(input_count)).sum::<usize>());
+        if best.as_ref().is_some_and(|(terms, literals, _)| cost >= (*terms, *literals)) {
+            return;
+        }
+        // Zeile mit den wenigsten Möglichkeiten zuerst; gibt es nur eine, ist der Primimplikant wesentlich
+        let Some(row) = uncovered.iter().copied().min_by_key(|&row| primes.iter().filter(|p| p.covers(row)).count()) else {
+            *best = Some((cost.0, cost.1, chosen.clone()));
+            return;
+        };
+        for p in primes.iter().filter(|p| p.covers(row)) {
+            let rest: Vec<usize> = uncovered.iter().copied().filter(|&r| !p.covers(r)).collect();
+            chosen.push(*p);
+            search(&rest, primes, chosen, best, input_count);
+            chosen.pop();
+        }
+    }
+    let primes = prime_implicants(on, dc);
+    let mut best = None;
+    search(on, &primes, &mut Vec::new(), &mut best, input_count);
+    let mut cover = best.map(|(_, _, cover)| cover).unwrap_or_default();
+    cover.sort_by_key(|p| std::cmp::Reverse(p.bits));
+    cover
+}
+
+/// Paare benachbarter Zeilen aus `rows` (sie unterscheiden sich in genau einem Eingang), die in keinem gemeinsamen
+/// Block der Überdeckung liegen. Wechselt dieser Eingang, übernimmt ein anderes Gatter den Wert, und je nach
+/// Laufzeiten tritt dazwischen kurz der falsche Pegel auf (statischer Hazard).
+pub fn hazards(cover: &[Implicant], rows: &[usize]) -> Vec<(usize, usize)> {
+    rows.iter()
+        .flat_map(|&a| rows.iter().filter(move |&&b| a < b && (a ^ b).count_ones() == 1).map(move |&b| (a, b)))
+        .filter(|&(a, b)| !cover.iter().any(|p| p.covers(a) && p.covers(b)))
+        .collect()
+}
+
+/// Minimale Überdeckung, ergänzt um Blöcke für alle Paare benachbarter Zeilen, die noch keinen gemeinsamen haben
+/// (Konsensterme). Gewählt wird jeweils der größte Primimplikant, der beide Zeilen enthält.
+pub fn hazard_free_cover(on: &[usize], dc: &[usize], input_count: usize) -> Vec<Implicant> {
+    let mut cover = minimal_cover(on, dc, input_count);
+    let primes = prime_implicants(on, dc);
+    while let Some(&(a, b)) = hazards(&cover, on).first() {
+        // Der Block aus beiden Zeilen ist ein Implikant, also liegt er in mindestens einem Primimplikanten
+        let prime = primes.iter().filter(|p| p.covers(a) && p.covers(b)).max_by_key(|p| p.mask.count_ones()).unwrap();
+        cover.push(*prime);
+    }
+    cover
+}
+
+/// Minimale KNF eines Ausgangs, gebildet aus Blöcken von Nullen
+pub fn minimal_cnf(output: &Output, input_count: usize) -> (Vec<Implicant>, Expr) {
+    let cover = minimal_cover(&output.maxterms(), &output.dont_cares(), input_count);
+    let expr = and_of(cover.iter().map(|p| p.sum(input_count)).collect());
+    (cover, expr)
+}
+
+/// Gemeinsame Minimierung mehrerer Ausgänge: jeder UND-Term wird nur einmal gebildet,
+/// auch wenn ihn mehrere Ausgänge verwenden
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SharedCover {
+    pub terms: Vec<Implicant>,
+    /// Je Ausgang die Indizes der Terme, die er verodert
+    pub outputs: Vec<Vec<usize>>,
+}
+
+impl SharedCover {
+    /// Fasst einzeln minimierte Ausgänge zusammen; gleiche Terme werden dabei nur einmal aufgeführt
+    pub fn from_covers(covers: &[Vec<Implicant>]) -> Self {
+        let mut terms: Vec<Implicant> = Vec::new();
+        let outputs = covers
+            .iter()
+            .map(|cover| {
+                cover
+                    .iter()
+                    .map(|p| match terms.iter().position(|t| t == p) {
+                        Some(t) => t,
+                        None => {
+                            terms.push(*p);
+                            terms.len() - 1
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+        Self { terms, outputs }
+    }
+
+    pub fn expr(&self, output: usize, input_count: usize) -> Expr {
+        or_of(self.outputs[output].iter().map(|&t| self.terms[t].product(input_count)).collect())
+    }
+
+    /// Ausgänge, die den Term verwenden
+    pub fn users(&self, term: usize) -> Vec<usize> {
+        (0..self.outputs.len()).filter(|&o| self.outputs[o].contains(&term)).collect()
+    }
+}
+
+/// Obergrenze für die Suche nach der besten gemeinsamen Überdeckung; danach gilt die beste bisher gefundene
+const SEARCH_BUDGET: usize = 20_000;
+
+/// Minimale DNF für alle Ausgänge zusammen. Als Kandidaten dienen die Primimplikanten jedes Produkts
+/// mehrerer Ausgänge, denn ein gemeinsamer Term muss für alle seine Ausgänge ein Implikant sein.
+/// Bewertet wird zuerst die Zahl verschiedener Terme, dann die Zahl der Gattereingänge.
+pub fn shared_dnf(outputs: &[Output], input_count: usize) -> SharedCover {
+    let rows = 1 << input_count;
+    let mut candidates = BTreeSet::new();
+    for subset in 1..1usize << outputs.len() {
+        let members: Vec<&Output> = (0..outputs.len()).filter(|o| subset >> o & 1 == 1).map(|o| &outputs[o]).collect();
+        let on: Vec<usize> = (0..rows).filter(|&r| members.iter().all(|o| o.values[r] == Value::One)).collect();
+        let dc: Vec<usize> = (0..rows)
+            .filter(|&r| !on.contains(&r) && members.iter().all(|o| o.values[r] != Value::Zero))
+            .collect();
+        candidates.extend(prime_implicants(&on, &dc));
+    }
+    let candidates: Vec<Implicant> = candidates.into_iter().collect();
+    // valid[c][o]: Kandidat c enthält keine 0 von Ausgang o
+    let valid: Vec<Vec<bool>> = candidates
+        .iter()
+        .map(|c| outputs.iter().map(|o| (0..rows).all(|r| !c.covers(r) || o.values[r] != Value::Zero)).collect())
+        .collect();
+    let pairs: Vec<(usize, usize)> =
+        outputs.iter().enumerate().flat_map(|(o, out)| out.minterms().into_iter().map(move |r| (o, r))).collect();
+
+    /// (Kandidat, Ausgang, der ihn verwendet)
+    type Assignment = (usize, usize);
+    struct Search<'a> {
+        candidates: &'a [Implicant],
+        valid: &'a [Vec<bool>],
+        input_count: usize,
+        budget: usize,
+        best: Option<((usize, usize), Vec<Assignment>)>,
+    }
+    impl Search<'_> {
+        fn cost(&self, chosen: &[Assignment]) -> (usize, usize) {
+            let distinct: BTreeSet<usize> = chosen.iter().map(|&(c, _)| c).collect();
+            let literals: usize = distinct.iter().map(|&c| self.candidates[c].literal_count(self.input_count)).sum();
+            (distinct.len(), literals + chosen.len())
+        }
+
+        /// Kandidaten, mit denen Ausgang `o` die Zeile `r` abdecken kann
+        fn options(&self, o: usize, r: usize) -> Vec<usize> {
+            (0..self.candidates.len()).filter(|&c| self.valid[c][o] && self.candidates[c].covers(r)).collect()
+        }
+
+        fn run(&mut self, uncovered: &[(usize, usize)], chosen: &mut Vec<Assignment>) {
+            let cost = self.cost(chosen);
+            if self.best.as_ref().is_some_and(|(best, _)| cost >= *best || self.budget == 0) {
+                return;
+            }
+            self.budget = self.budget.saturating_sub(1);
+            let Some(&(o, r)) = uncovered.iter().min_by_key(|&&(o, r)| self.options(o, r).len()) else {
+                self.best = Some((cost, chosen.clone()));
+                return;
+            };
+            // Terme, die viel abdecken, zuerst: so ist schon der erste Versuch eine gute Lösung
+            let mut options: Vec<(usize, Vec<(usize, usize)>)> = self
+                .options(o, r)
+                .into_iter()
+                .map(|c| (c, uncovered.iter().copied().filter(|&(o2, r2)| o2 != o || !self.candidates[c].covers(r2)).collect()))
+                .collect();
+            options.sort_by_key(|(c, rest)| (rest.len(), self.candidates[*c].literal_count(self.input_count)));
+            for (c, rest) in options {
+                chosen.push((c, o));
+                self.run(&rest, chosen);
+                chosen.pop();
+            }
+        }
+    }
+
+    let mut search = Search { candidates: &candidates, valid: &valid, input_count, budget: SEARCH_BUDGET, best: None };
+    search.run(&pairs, &mut Vec::new());
+    let chosen = search.best.map(|(_, chosen)| chosen).unwrap_or_default();
+
+    let mut used: Vec<usize> = chosen.iter().map(|&(c, _)| c).collect::<BTreeSet<_>>().into_iter().collect();
+    used.sort_by_key(|&c| std::cmp::Reverse(candidates[c].bits));
+    SharedCover {
+        terms: used.iter().map(|&c| candidates[c]).collect(),
+        outputs: (0..outputs.len())
+            .map(|o| (0..used.len()).filter(|&t| chosen.contains(&(used[t], o))).collect())
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::equivalence::row_inputs;
+    use crate::test_support::Rng;
+
+    fn random_output(rng: &mut Rng, input_count: usize) -> Output {
+        let values = (0..1 << input_count).map(|_| [Value::Zero, Value::One, Value::DontCare][rng.below(3)]).collect();
+        Output { name: "Y".into(), values }
+    }
+
+    /// Alle Blöcke, die keine 0 enthalten
+    fn implicants(output: &Output, input_count: usize) -> Vec<Implicant> {
+        let rows = 1usize << input_count;
+        (0..rows)
+            .flat_map(|mask| (0..rows).filter(move |bits| bits & mask == 0).map(move |bits| Implicant { bits, mask }))
+            .filter(|p| (0..rows).all(|r| !p.covers(r) || output.values[r] != Value::Zero))
+            .collect()
+    }
+
+    /// Prüft, dass der Ausdruck in jeder Zeile ohne X den Wert der Tabelle liefert
+    fn assert_matches(expr: &Expr, output: &Output, input_count: usize) {
+        for (row, value) in output.values.iter().enumerate() {
+            if *value != Value::DontCare {
+                assert_eq!(expr.eval(&row_inputs(row, input_count)), *value == Value::One, "{output:?}, Zeile {row}");
+            }
+        }
+    }
+
+    #[test]
+    fn prime_implicants_are_the_maximal_implicants() {
+        let mut rng = Rng(0x5eed_1234);
+        for input_count in 1..=4 {
+            for _ in 0..100 {
+                let output = random_output(&mut rng, input_count);
+                let all = implicants(&output, input_count);
+                let mut expected: Vec<Implicant> = all
+                    .iter()
+                    .filter(|p| output.minterms().iter().any(|&r| p.covers(r)))
+                    .filter(|p| !all.iter().any(|q| q != *p && q.mask & p.mask == p.mask && q.covers(p.bits)))
+                    .copied()
+                    .collect();
+                let mut primes = prime_implicants(&output.minterms(), &output.dont_cares());
+                expected.sort();
+                primes.sort();
+                assert_eq!(primes, expected, "{output:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn minimal_cover_matches_the_truth_table() {
+        let mut rng = Rng(0xc0ffee);
+        for input_count in 1..=4 {
+            for _ in 0..100 {
+                let output = random_output(&mut rng, input_count);
+                let cover = minimal_cover(&output.minterms(), &output.dont_cares(), input_count);
+                let expr = or_of(cover.iter().map(|p| p.product(input_count)).collect());
+                assert_matches(&expr, &output, input_count);
+                assert_matches(&minimal_cnf(&output, input_count).1, &output, input_count);
+                let hazard_free = hazard_free_cover(&output.minterms(), &output.dont_cares(), input_count);
+                assert!(hazards(&hazard_free, &output.minterms()).is_empty(), "{output:?}");
+                assert_matches(&or_of(hazard_free.iter().map(|p| p.product(input_count)).collect()), &output, input_count);
+            }
+        }
+    }
+
+    #[test]
+    fn minimal_cover_uses_fewest_terms() {
+        let mut rng = Rng(0xabcdef);
+        for _ in 0..100 {
+            let output = random_output(&mut rng, 3);
+            let on = output.minterms();
+            let primes = prime_implicants(&on, &output.dont_cares());
+            let fewest = (0..1usize << primes.len())
+                .filter(|subset| on.iter().all(|&r| (0..primes.len()).any(|p| subset >> p & 1 == 1 && primes[p].covers(r))))
+                .map(|subset| subset.count_ones() as usize)
+                .min()
+                .unwrap();
+            assert_eq!(minimal_cover(&on, &output.dont_cares(), 3).len(), fewest, "{output:?}");
+        }
+    }
+
+    #[test]
+    fn shared_dnf_matches_every_output() {
+        let mut rng = Rng(0xfeed_beef);
+        for input_count in 1..=3 {
+            for _ in 0..50 {
+                let outputs: Vec<Output> = (0..1 + rng.below(3)).map(|_| random_output(&mut rng, input_count)).collect();
+                let shared = shared_dnf(&outputs, input_count);
+                for (o, output) in outputs.iter().enumerate() {
+                    assert_matches(&shared.expr(o, input_count), output, input_count);
+                }
+                // Nie mehr verschiedene Terme als bei getrennter Minimierung
+                let separate: Vec<Vec<Implicant>> =
+                    outputs.iter().map(|o| minimal_cover(&o.minterms(), &o.dont_cares(), input_count)).collect();
+                assert!(shared.terms.len() <= SharedCover::from_covers(&separate).terms.len(), "{outputs:?}");
+            }
+        }
+    }
+}
@@ -0,0 +1,226 @@
+// Peripherie für Ein- und Ausgänge: Taster, DIP-Schalter, Hex-Tastatur, LEDs und 7-Segment-Anzeigen.
+// Hier liegen die Geräteart und das Zeichnen; Ein- und Ausgänge bleiben in der Schaltung gewöhnliche Knoten.
+
+use egui::{Align2, Color32, FontId, Painter, Pos2, Rect, Response, Sense, Stroke, Ui, Vec2};
+use serde::{Deserialize, Serialize};
+
+use crate::equivalence::{row_index, row_inputs};
+
+/// Wie ein Eingang bedient wird
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum InputDevice {
+    /// Feld mit Name und Wert, ein Klick schaltet um bzw. zählt weiter
+    #[default]
+    Switch,
+    /// Taster: tastend nur so lange gedrückt, wie die Maustaste gehalten wird, sonst rastend
+    Button { momentary: bool },
+    /// Reihe einzelner Schiebeschalter, jeder mit eigenem Ausgang
+    Dip,
+    /// 16 Tasten 0 bis F, die Nummer der zuletzt gedrückten Taste liegt als 4-Bit-Bus an
+    HexKeypad,
+}
+
+/// Wie ein Ausgang angezeigt wird
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum OutputDevice {
+    /// Kreis in der Pegelfarbe bzw. Zahlenanzeige für Busse
+    #[default]
+    Lamp,
+    Led(LedColor),
+    /// Segmente a bis g mit je einem Eingang
+    SevenSegment(LedColor),
+}
+
+impl InputDevice {
+    /// Anzahl der Bits, mit der das Gerät angelegt wird
+    pub fn default_width(&self) -> usize {
+        match self {
+            InputDevice::Switch | InputDevice::Button { .. } => 1,
+            InputDevice::Dip | InputDevice::HexKeypad => 4,
+        }
+    }
+}
+
+impl OutputDevice {
+    pub fn default_width(&self) -> usize {
+        match self {
+            OutputDevice::Lamp | OutputDevice::Led(_) => 1,
+            OutputDevice::SevenSegment(_) => SEGMENTS.len(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum LedColor {
+    #[default]
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    White,
+}
+
+pub const ALL_LED_COLORS: [LedColor; 5] = [LedColor::Red, LedColor::Green, LedColor::Yellow, LedColor::Blue, LedColor::White];
+
+impl LedColor {
+    pub fn label(&self) -> &'static str {
+        match self {
+            LedColor::Red => "Rot",
+            LedColor::Green => "Grün",
+            LedColor::Yellow => "Gelb",
+            LedColor::Blue => "Blau",
+            LedColor::White => "Weiß",
+        }
+    }
+
+    pub fn color(&self) -> Color32 {
+        match self {
+            LedColor::Red => Color32::from_rgb(255, 40, 40),
+            LedColor::Green => Color32::from_rgb(40, 230, 60),
+            LedColor::Yellow => Color32::from_rgb(255, 220, 0),
+            LedColor::Blue => Color32::from_rgb(60, 120, 255),
+            LedColor::White => Color32::from_rgb(245, 245, 245),
+        }
+    }
+}
+
+/// Namen der Segmente in der Reihenfolge der Eingänge
+pub const SEGMENTS: [&str; 7] = ["a", "b", "c", "d", "e", "f", "g"];
+
+pub const LED_RADIUS: f32 = 11.0;
+/// Hoch genug, dass die sieben Segmenteingänge nicht zu eng liegen
+pub const SEVEN_SEGMENT_SIZE: Vec2 = Vec2::new(70.0, 128.0);
+const DIP_ROW: f32 = 18.0;
+const DIP_WIDTH: f32 = 44.0;
+const KEY_SIZE: f32 = 20.0;
+pub const KEYPAD_SIZE: Vec2 = Vec2::new(4.0 * KEY_SIZE + 8.0, 4.0 * KEY_SIZE + 8.0);
+pub const BUTTON_SIZE: Vec2 = Vec2::new(36.0, 36.0);
+
+/// Die Schalter liegen untereinander, damit jeder rechts seinen eigenen Ausgang hat
+pub fn dip_size(width: usize) -> Vec2 {
+    Vec2::new(DIP_WIDTH, width as f32 * DIP_ROW + 8.0)
+}
+
+/// Mitte der Zeile des Schalters `i`; dort liegt auch sein Ausgang
+pub fn dip_row_y(rect: Rect, i: usize) -> f32 {
+    rect.top() + 4.0 + (i as f32 + 0.5) * DIP_ROW
+}
+
+/// Ausgeschaltete Leuchtmittel sind nur angedeutet
+fn off_color(color: Color32) -> Color32 {
+    color.linear_multiply(0.15)
+}
+
+pub fn paint_led(painter: &Painter, center: Pos2, color: LedColor, on: bool, stroke: Stroke) {
+    let color = color.color();
+    if on {
+        painter.circle_filled(center, LED_RADIUS + 4.0, color.linear_multiply(0.25));
+    }
+    painter.circle(center, LED_RADIUS, if on { color } else { off_color(color) }, stroke);
+}
+
+/// Segmente a bis g wie auf einer Digitalanzeige: a oben, dann im Uhrzeigersinn, g in der Mitte
+pub fn paint_seven_segment(painter: &Painter, rect: Rect, segments: &[bool], color: LedColor, background: Color32, stroke: Stroke) {
+    painter.rect(rect, 4.0, background, stroke);
+    let color = color.color();
+    let inner = rect.shrink2(Vec2::new(20.0, 16.0));
+    let t = 6.0;
+    let (l, r, top, mid, bottom) = (inner.left(), inner.right(), inner.top(), inner.center().y, inner.bottom());
+    let horizontal = |y: f32| Rect::from_min_max(Pos2::new(l + t / 2.0, y - t / 2.0), Pos2::new(r - t / 2.0, y + t / 2.0));
+    let vertical = |x: f32, y0: f32, y1: f32| Rect::from_min_max(Pos2::new(x - t / 2.0, y0 + t / 2.0), Pos2::new(x + t / 2.0, y1 - t / 2.0));
+    let shapes = [
+        horizontal(top),
+        vertical(r, top, mid),
+        vertical(r, mid, bottom),
+        horizontal(bottom),
+        vertical(l, mid, bottom),
+        vertical(l, top, mid),
+        horizontal(mid),
+    ];
+    for (shape, &on) in shapes.iter().zip(segments) {
+        painter.rect_filled(*shape, 2.0, if on { color } else { off_color(color) });
+    }
+}
+
+/// Ein Schiebeschalter je Bit, das höchstwertige oben; rechts ist an
+pub fn paint_dip(painter: &Painter, rect: Rect, bits: &[bool], stroke: Stroke) {
+    painter.rect(rect, 2.0, Color32::from_rgb(200, 40, 40), stroke);
+    for (i, &on) in bits.iter().enumerate() {
+        let slot = dip_slot(rect, i);
+        painter.rect_filled(slot, 2.0, Color32::from_gray(30));
+        let knob = if on { slot.center().x } else { slot.left() };
+        painter.rect_filled(Rect::from_min_size(Pos2::new(knob, slot.top()), Vec2::new(slot.width() / 2.0, slot.height())), 2.0, Color32::WHITE);
+    }
+}
+
+fn dip_slot(rect: Rect, i: usize) -> Rect {
+    Rect::from_center_size(Pos2::new(rect.center().x, dip_row_y(rect, i)), Vec2::new(DIP_WIDTH - 16.0, DIP_ROW - 6.0))
+}
+
+/// Schalter der DIP-Reihe unter `p`
+pub fn dip_switch_at(rect: Rect, width: usize, p: Pos2) -> Option<usize> {
+    (0..width).find(|&i| dip_slot(rect, i).expand(2.0).contains(p))
+}
+
+fn key_rect(rect: Rect, key: usize) -> Rect {
+    let (row, col) = (key / 4, key % 4);
+    Rect::from_min_size(rect.min + Vec2::new(4.0 + col as f32 * KEY_SIZE, 4.0 + row as f32 * KEY_SIZE), Vec2::splat(KEY_SIZE)).shrink(1.5)
+}
+
+/// Tasten 0 bis F in vier Reihen; die zuletzt gedrückte ist hervorgehoben
+pub fn paint_keypad(painter: &Painter, rect: Rect, value: &[bool], background: Color32, stroke: Stroke) {
+    painter.rect(rect, 4.0, background, stroke);
+    let selected = row_index(value);
+    for key in 0..16 {
+        let fill = if key == selected { Color32::from_rgb(80, 140, 220) } else { Color32::from_gray(70) };
+        painter.rect(key_rect(rect, key), 3.0, fill, Stroke::new(1.0, Color32::from_gray(120)));
+        painter.text(key_rect(rect, key).center(), Align2::CENTER_CENTER, format!("{:X}", key), FontId::monospace(12.0), Color32::WHITE);
+    }
+}
+
+/// Taste der Hex-Tastatur unter `p` als 4-Bit-Wert
+pub fn keypad_key_at(rect: Rect, p: Pos2) -> Option<Vec<bool>> {
+    (0..16).find(|&key| key_rect(rect, key).contains(p)).map(|key| row_inputs(key, 4))
+}
+
+/// Runder Taster; gedrückt erscheint er eingedrückt und in der Pegelfarbe
+pub fn paint_button(painter: &Painter, rect: Rect, pressed: bool, momentary: bool, stroke: Stroke) {
+    painter.rect(rect, 4.0, Color32::from_gray(60), stroke);
+    let radius = rect.height() / 2.0 - 5.0;
+    let fill = if pressed { Color32::from_rgb(220, 60, 60) } else { Color32::from_gray(150) };
+    painter.circle(rect.center(), if pressed { radius - 1.5 } else { radius }, fill, Stroke::new(1.5, Color32::from_gray(30)));
+    if !momentary {
+        // Rastende Taster haben eine Markierung für die eingerastete Stellung
+        painter.circle_filled(rect.right_top() + Vec2::new(-6.0, 6.0), 2.5, if pressed { Color32::YELLOW } else { Color32::from_gray(30) });
+    }
+}
+
+/// Kippschalter mit Beschriftung als Ersatz für eine Checkbox
+pub fn toggle_switch(ui: &mut Ui, on: &mut bool, label: &str) -> Response {
+    let height = ui.spacing().interact_size.y;
+    let switch = Vec2::new(height * 1.8, height);
+    let galley = ui.painter().layout_no_wrap(label.to_string(), FontId::proportional(14.0), ui.visuals().text_color());
+    let (rect, mut response) = ui.allocate_exact_size(Vec2::new(switch.x + 6.0 + galley.size().x, height), Sense::click());
+    if response.clicked() {
+        *on = !*on;
+        response.mark_changed();
+    }
+    response.widget_info(|| egui::WidgetInfo::selected(egui::WidgetType::Checkbox, *on, label));
+    let track = Rect::from_min_size(rect.min, switch);
+    let how_on = ui.ctx().animate_bool(response.id, *on);
+    let painter = ui.painter();
+    let fill = if *on { Color32::from_rgb(40, 160, 60) } else { Color32::from_gray(90) };
+    painter.rect(track, height / 2.0, fill, ui.visuals().widgets.style(&response).bg_stroke);
+    let x = egui::lerp(track.left() + height / 2.0..=track.right() - height / 2.0, how_on);
+    painter.circle_filled(Pos2::new(x, track.center().y), height / 2.0 - 2.0, Color32::WHITE);
+    painter.galley(Pos2::new(track.right() + 6.0, rect.center().y - galley.size().y / 2.0), galley);
+    response
+}
+
+/// LED als Widget, z.B. für den Ausgangspegel eines einzelnen Gatters
+pub fn led(ui: &mut Ui, on: bool, color: LedColor) -> Response {
+    let (rect, response) = ui.allocate_exact_size(Vec2::splat(2.0 * LED_RADIUS + 8.0), Sense::hover());
+    paint_led(ui.painter(), rect.center(), color, on, Stroke::new(1.0, Color32::from_gray(30)));
+    response.widget_info(|| egui::WidgetInfo::labeled(egui::WidgetType::Label, if on { "LED leuchtet" } else { "LED aus" }));
+    response
+}
@@ -0,0 +1,115 @@
+// Präsentationsmodus für den Beamer: Vollbild, vergrößerte Darstellung mit hohem Kontrast und ohne Menüs und
+// Werkzeugleisten. Die Tabs dienen als Folien, die vorher vorbereitet und dann nacheinander gezeigt werden.
+// Ein Laserpointer zieht eine kurz sichtbare Spur hinter dem Mauszeiger her.
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+use egui::{Color32, Key, Modifiers, Pos2, Style, Ui};
+
+use crate::accessibility;
+use crate::theme::Theme;
+
+/// Vergrößerung gegenüber der normalen Darstellung; Schrift und Leitungen wachsen gleichermaßen
+const ZOOM: f32 = 1.6;
+const LASER_COLOR: Color32 = Color32::from_rgb(255, 40, 40);
+/// So lange bleibt die Spur des Laserpointers sichtbar
+const LASER_SECONDS: f64 = 0.6;
+
+pub enum Command {
+    Next,
+    Previous,
+    Exit,
+}
+
+#[derive(Default)]
+pub struct Presentation {
+    /// Stil und Skalierung vor dem Start; gesetzt, solange die Präsentation läuft
+    saved: Option<(Arc<Style>, f32)>,
+    laser: bool,
+    trail: VecDeque<(Pos2, f64)>,
+}
+
+impl Presentation {
+    pub fn is_active(&self) -> bool {
+        self.saved.is_some()
+    }
+
+    pub fn start(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
+        if self.saved.is_none() {
+            self.saved = Some((ctx.style(), ctx.pixels_per_point()));
+            ctx.set_pixels_per_point(ctx.pixels_per_point() * ZOOM);
+            ctx.set_visuals(Theme::HighContrast.visuals());
+            frame.set_fullscreen(true);
+        }
+    }
+
+    pub fn stop(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
+        if let Some((style, pixels_per_point)) = self.saved.take() {
+            ctx.set_style(style);
+            ctx.set_pixels_per_point(pixels_per_point);
+            frame.set_fullscreen(false);
+            self.trail.clear();
+        }
+    }
+
+    /// Pfeiltasten, Bild auf/ab und Leertaste blättern, Esc beendet, L schaltet den Laserpointer um.
+    /// Solange ein Textfeld bearbeitet wird, bleiben die Tasten dort.
+    pub fn command(&mut self, ctx: &egui::Context) -> Option<Command> {
+        if accessibility::typing(ctx) {
+            return None;
+        }
+        let mut input = ctx.input_mut();
+        let mut pressed = |keys: &[Key]| keys.iter().any(|&key| input.consume_key(Modifiers::NONE, key));
+        if pressed(&[Key::ArrowRight, Key::PageDown, Key::Space]) {
+            Some(Command::Next)
+        } else if pressed(&[Key::ArrowLeft, Key::PageUp]) {
+            Some(Command::Previous)
+        } else if pressed(&[Key::Escape, Key::F5]) {
+            Some(Command::Exit)
+        } else {
+            if pressed(&[Key::L]) {
+                self.laser = !self.laser;
+            }
+            None
+        }
+    }
+
+    /// Leiste am unteren Rand mit der Foliennummer und den Tastenkürzeln; gibt zurück, ob beendet werden soll
+    pub fn show_footer(&mut self, ui: &mut Ui, slide: usize, count: usize) -> bool {
+        ui.horizontal(|ui| {
+            ui.strong(format!("Folie {} / {}", slide + 1, count));
+            ui.separator();
+            ui.label("Pfeiltasten: blättern · L: Laserpointer · Esc: beenden");
+            ui.separator();
+            ui.checkbox(&mut self.laser, "Laserpointer");
+            ui.button("Beenden").clicked()
+        })
+        .inner
+    }
+
+    /// Roter Punkt mit verblassender Spur über allen anderen Inhalten
+    pub fn show_laser(&mut self, ctx: &egui::Context) {
+        let now = ctx.input().time;
+        self.trail.retain(|&(_, time)| now - time < LASER_SECONDS);
+        if !self.laser {
+            self.trail.clear();
+            return;
+        }
+        if let Some(pos) = ctx.input().pointer.hover_pos() {
+            self.trail.push_back((pos, now));
+            ctx.output().cursor_icon = egui::CursorIcon::None;
+        }
+        let painter = ctx.layer_painter(egui::LayerId::new(egui::Order::Foreground, egui::Id::new("laser_pointer")));
+        for &(pos, time) in &self.trail {
+            let fade = 1.0 - ((now - time) / LASER_SECONDS) as f32;
+            painter.circle_filled(pos, 3.0 + 5.0 * fade, LASER_COLOR.linear_multiply(fade * 0.6));
+        }
+        if let Some(&(pos, _)) = self.trail.back() {
+            painter.circle_filled(pos, 8.0, LASER_COLOR);
+        }
+        if !self.trail.is_empty() {
+            ctx.request_repaint();
+        }
+    }
+}
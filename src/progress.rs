@@ -0,0 +1,124 @@
+// Lernfortschritt der Schüler in den Übungspaketen, lokal gespeichert und als CSV / JSON exportierbar
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+const PROGRESS_FILE: &str = "fortschritt.json";
+
+/// Datei, in der der Fortschritt aller Schüler dieses Rechners liegt. Sie steht neben den Einstellungen im
+/// Datenverzeichnis des Benutzers, unter Linux `~/.local/share/<Programmname>/fortschritt.json`, und hängt damit
+/// nicht davon ab, aus welchem Ordner das Programm gestartet wird. Nur ohne Datenverzeichnis im aktuellen Ordner.
+pub fn progress_file() -> PathBuf {
+    crate::settings::data_dir().map_or_else(|| PathBuf::from(PROGRESS_FILE), |dir| dir.join(PROGRESS_FILE))
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExerciseProgress {
+    pub pack: String,
+    pub exercise: String,
+    /// Anzahl der Prüfungen
+    pub attempts: u32,
+    pub hints_used: u32,
+    /// Bearbeitungszeit bis zur ersten richtigen Lösung in Sekunden
+    pub seconds: f64,
+    pub solved: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StudentProgress {
+    pub name: String,
+    pub exercises: Vec<ExerciseProgress>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProgressStore {
+    pub students: Vec<StudentProgress>,
+}
+
+impl ProgressStore {
+    /// Lädt den gespeicherten Fortschritt. Fehlt die Datei, wird der Stand einer älteren Version übernommen, die ihn
+    /// im aktuellen Ordner ablegte, sonst mit leerem Stand begonnen.
+    pub fn load(path: &Path) -> Self {
+        fs::read_to_string(path)
+            .or_else(|_| fs::read_to_string(PROGRESS_FILE))
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, self.to_json())
+    }
+
+    pub fn exercise_mut(&mut self, student: &str, pack: &str, exercise: &str) -> &mut ExerciseProgress {
+        let s = match self.students.iter().position(|s| s.name == student) {
+            Some(s) => s,
+            None => {
+                self.students.push(StudentProgress { name: student.to_string(), exercises: Vec::new() });
+                self.students.len() - 1
+            }
+        };
+        let exercises = &mut self.students[s].exercises;
+        let e = match exercises.iter().position(|e| e.pack == pack && e.exercise == exercise) {
+            Some(e) => e,
+            None => {
+                exercises.push(ExerciseProgress { pack: pack.to_string(), exercise: exercise.to_string(), ..Default::default() });
+                exercises.len() - 1
+            }
+        };
+        &mut exercises[e]
+    }
+
+    pub fn exercise(&self, student: &str, pack: &str, exercise: &str) -> Option<&ExerciseProgress> {
+        self.students
+            .iter()
+            .find(|s| s.name == student)?
+            .exercises
+            .iter()
+            .find(|e| e.pack == pack && e.exercise == exercise)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("Fortschritt lässt sich immer serialisieren")
+    }
+
+    /// Eine Zeile je Schüler und Aufgabe, durch Semikolon getrennt (öffnet sich so direkt in Excel / LibreOffice)
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("Name;Übungspaket;Aufgabe;Gelöst;Versuche;Tipps;Zeit (s)\n");
+        for student in &self.students {
+            for e in &student.exercises {
+                csv.push_str(&format!(
+                    "{};{};{};{};{};{};{:.0}\n",
+                    csv_field(&student.name),
+                    csv_field(&e.pack),
+                    csv_field(&e.exercise),
+                    if e.solved { "ja" } else { "nein" },
+                    e.attempts,
+                    e.hints_used,
+                    e.seconds
+                ));
+            }
+        }
+        csv
+    }
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains([';', '"', '\n']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+/// Zeit als `m:ss` für die Anzeige
+pub fn format_duration(seconds: f64) -> String {
+    let seconds = seconds as u64;
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
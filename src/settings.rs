@@ -0,0 +1,69 @@
+// Einstellungen, die beim Beenden gespeichert und beim nächsten Start wiederhergestellt werden: Farbschema, die
+// zuletzt geöffneten Tabs mit ihrem Inhalt (Gatter, Schaltung, Ausdruck und Wahrheitstabelle), das Gatter für neue
+// Tabs sowie die Auflösung für den Export. eframe legt sie zusammen mit Größe und Position des Fensters im Datenverzeichnis des Benutzers ab,
+// unter Linux in `~/.local/share/<Programmname>/app.ron`. Der Übungsfortschritt hat eine eigene Datei (siehe
+// `progress`), die Schaltungen der Übungen gehören dem jeweiligen Schüler und werden nicht gespeichert.
+
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::circuit::Circuit;
+use crate::theme::Theme;
+use crate::truth_table::TruthTable;
+use crate::{LogicGates, View};
+
+/// Name des Programms, nach dem eframe das Datenverzeichnis benennt
+pub const APP_NAME: &str = "Logikgatter Erklärprogramm";
+
+/// Datenverzeichnis, in dem eframe die Einstellungen ablegt; gleich gewählt wie in `eframe::run_native`
+pub fn data_dir() -> Option<PathBuf> {
+    directories_next::ProjectDirs::from("", "", APP_NAME).map(|dirs| dirs.data_dir().to_path_buf())
+}
+
+/// Zuletzt geöffneter Tab
+#[derive(Serialize, Deserialize)]
+pub struct Tab {
+    pub name: String,
+    pub view: View,
+    pub gate: LogicGates,
+    pub input_a: bool,
+    pub input_b: bool,
+    /// Inhalt der übrigen Ansichten; fehlt er, z.B. bei Einstellungen einer älteren Version, bleibt die Vorgabe
+    #[serde(default)]
+    pub circuit: Circuit,
+    #[serde(default)]
+    pub expression: Option<String>,
+    #[serde(default)]
+    pub kv: Option<TruthTable>,
+}
+
+/// Fehlende Felder, z.B. aus einer älteren Version, erhalten ihre Standardwerte
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub theme: Theme,
+    pub patterns: bool,
+    pub export_scale: f32,
+    /// Gatter, mit dem neue Tabs beginnen
+    pub default_gate: LogicGates,
+    pub tabs: Vec<Tab>,
+    /// Index des zuletzt angezeigten Tabs
+    pub current: usize,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self { theme: Theme::Dark, patterns: false, export_scale: 2.0, default_gate: LogicGates::AND, tabs: Vec::new(), current: 0 }
+    }
+}
+
+impl Settings {
+    pub fn load(storage: Option<&dyn eframe::Storage>) -> Self {
+        storage.and_then(|storage| eframe::get_value(storage, eframe::APP_KEY)).unwrap_or_default()
+    }
+
+    pub fn save(&self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(storage, eframe::APP_KEY, self);
+    }
+}
@@ -0,0 +1,246 @@
+// Teilschaltungen: ein Ausschnitt einer Schaltung wird zu einem Baustein mit benannten Anschlüssen
+// zusammengefasst, der sich wie die Bausteine der Bibliothek mehrfach und auch verschachtelt einsetzen lässt
+
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use egui::Pos2;
+use serde::{Deserialize, Serialize};
+
+use crate::bus::{locate, Port};
+use crate::circuit::{Circuit, NodeKind, Source};
+use crate::components::Component;
+use crate::layout;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Subcircuit {
+    pub name: String,
+    /// Kurzzeichen im Schaltsymbol
+    pub symbol: String,
+    /// Innenschaltung; ihre Ein- und Ausgänge sind die Anschlüsse des Bausteins
+    pub circuit: Circuit,
+}
+
+impl Subcircuit {
+    pub fn input_ports(&self) -> Vec<Port> {
+        self.circuit.input_nodes().into_iter().flat_map(|n| self.circuit.nodes[n].kind.output_ports()).collect()
+    }
+
+    pub fn output_ports(&self) -> Vec<Port> {
+        self.circuit.output_nodes().into_iter().flat_map(|n| self.circuit.nodes[n].kind.input_ports()).collect()
+    }
+}
+
+/// Geplantes Zusammenfassen der ausgewählten Knoten; Name, Kurzzeichen und Anschlussnamen können vorher geändert werden
+pub struct Extraction {
+    selected: Vec<usize>,
+    pub name: String,
+    pub symbol: String,
+    /// Quellen außerhalb der Auswahl und Name des Eingangs, über den sie hineinführen. Ein Bus, der ganz
+    /// hineinführt, wird zu einem Eingang mit mehreren Bits.
+    pub inputs: Vec<(Vec<Source>, String)>,
+    /// Ausgänge innerhalb der Auswahl, die außerhalb genutzt werden, und Name des zugehörigen Anschlusses
+    pub outputs: Vec<(Vec<Source>, String)>,
+}
+
+/// Fasst die Bits zu Anschlüssen zusammen: alle Bits eines Busses zu einem, die übrigen einzeln
+fn group(circuit: &Circuit, sources: &[Source]) -> Vec<Vec<Source>> {
+    let mut groups: Vec<Vec<Source>> = Vec::new();
+    for src in sources {
+        if groups.iter().flatten().any(|s| s == src) {
+            continue;
+        }
+        let ports = circuit.nodes[src.node].kind.output_ports();
+        let (port, first) = locate(&ports, src.pin);
+        let bus: Vec<Source> = (first..first + ports[port].width).map(|pin| Source { pin, ..*src }).collect();
+        if bus.len() > 1 && bus.iter().all(|s| sources.contains(s)) {
+            groups.push(bus);
+        } else {
+            groups.push(vec![*src]);
+        }
+    }
+    groups
+}
+
+/// Name eines Ein- oder Ausgangs der Schaltung für einen Anschluss aus `width` Bits ab `pin`
+fn io_name(circuit: &Circuit, node: usize, pin: usize, width: usize) -> Option<String> {
+    let kind = &circuit.nodes[node].kind;
+    let mut ports = match kind {
+        NodeKind::Input { .. } => kind.output_ports(),
+        NodeKind::Output { .. } => kind.input_ports(),
+        _ => return None,
+    };
+    let (port, first) = locate(&ports, pin);
+    let port = ports.swap_remove(port);
+    if width == port.width {
+        Some(port.name)
+    } else if width == 1 {
+        port.bit_names().get(pin - first).cloned()
+    } else {
+        None
+    }
+}
+
+impl Extraction {
+    /// Ermittelt die Anschlüsse der Auswahl. `number` dient für die vorgeschlagenen Namen.
+    pub fn new(circuit: &Circuit, selected: &[usize], number: usize) -> Result<Self, String> {
+        let mut selected = selected.to_vec();
+        selected.sort_unstable();
+        selected.dedup();
+        if selected.iter().any(|&n| matches!(circuit.nodes[n].kind, NodeKind::Input { .. } | NodeKind::Output { .. })) {
+            return Err("Ein- und Ausgänge der Schaltung können nicht Teil einer Teilschaltung sein.".to_string());
+        }
+        let inside = |n: usize| selected.binary_search(&n).is_ok();
+        let height = |bits: &[Source]| circuit.nodes[bits[0].node].pos.y + bits[0].pin as f32;
+
+        let mut sources: Vec<Source> = Vec::new();
+        for &n in &selected {
+            for src in circuit.nodes[n].inputs.iter().flatten() {
+                if !inside(src.node) && !sources.contains(src) {
+                    sources.push(*src);
+                }
+            }
+        }
+        // Führt ein Signal aus der Auswahl hinaus und wieder hinein, entstünde eine Rückkopplung über den Baustein
+        if sources.iter().any(|src| selected.iter().any(|&n| circuit.depends_on(src.node, n))) {
+            return Err("Ein Signal führt aus der Auswahl hinaus und wieder hinein; so entstünde eine Rückkopplung.".to_string());
+        }
+        let mut used: Vec<Source> = Vec::new();
+        for node in circuit.nodes.iter().enumerate().filter(|(n, _)| !inside(*n)).map(|(_, node)| node) {
+            for src in node.inputs.iter().flatten().filter(|src| inside(src.node)) {
+                if !used.contains(src) {
+                    used.push(*src);
+                }
+            }
+        }
+        if used.is_empty() {
+            return Err("Aus der Auswahl führt keine Leitung hinaus, die Teilschaltung hätte keinen Ausgang.".to_string());
+        }
+        let mut inputs = group(circuit, &sources);
+        let mut outputs = group(circuit, &used);
+        inputs.sort_by(|a, b| height(a).total_cmp(&height(b)));
+        outputs.sort_by(|a, b| height(a).total_cmp(&height(b)));
+
+        // Ausgänge heißen wie ein Ausgang der Schaltung, an den sie direkt angeschlossen sind
+        let output_name = |bits: &[Source]| {
+            circuit.nodes.iter().enumerate().find_map(|(n, node)| {
+                let pin = node.inputs.iter().position(|src| *src == Some(bits[0]))?;
+                io_name(circuit, n, pin, bits.len())
+            })
+        };
+        Ok(Self {
+            name: format!("Teilschaltung {}", number),
+            symbol: format!("T{}", number),
+            inputs: inputs
+                .into_iter()
+                .enumerate()
+                .map(|(i, bits)| {
+                    let name = io_name(circuit, bits[0].node, bits[0].pin, bits.len()).unwrap_or_else(|| format!("E{}", i + 1));
+                    (bits, name)
+                })
+                .collect(),
+            outputs: outputs
+                .into_iter()
+                .enumerate()
+                .map(|(i, bits)| {
+                    let name = output_name(&bits).unwrap_or_else(|| format!("Y{}", i + 1));
+                    (bits, name)
+                })
+                .collect(),
+            selected,
+        })
+    }
+
+    /// Prüft die Namen; Anschlüsse einer Seite müssen verschieden heißen
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() || self.symbol.trim().is_empty() {
+            return Err("Name und Kurzzeichen dürfen nicht leer sein.".to_string());
+        }
+        for ports in [&self.inputs, &self.outputs] {
+            for (i, (_, name)) in ports.iter().enumerate() {
+                if name.trim().is_empty() {
+                    return Err("Jeder Anschluss braucht einen Namen.".to_string());
+                }
+                if ports[..i].iter().any(|(_, other)| other == name) {
+                    return Err(format!("Der Anschlussname {} kommt doppelt vor.", name));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Ersetzt die ausgewählten Knoten durch einen Baustein und gibt die neue Teilschaltung zurück
+    pub fn apply(self, circuit: &mut Circuit) -> Rc<Subcircuit> {
+        let inside = |n: usize| self.selected.binary_search(&n).is_ok();
+        // Anschluss und Bit, zu dem eine Quelle gehört
+        let find = |ports: &[(Vec<Source>, String)], src: Source| {
+            ports.iter().enumerate().find_map(|(i, (bits, _))| bits.iter().position(|&s| s == src).map(|k| (i, k)))
+        };
+        let mut inner = Circuit::default();
+        for (bits, name) in &self.inputs {
+            inner.add_node(NodeKind::bus_input(name.clone(), bits.len()), Pos2::ZERO);
+        }
+        let mut map = HashMap::new();
+        for &n in &self.selected {
+            let m = inner.add_node(circuit.nodes[n].kind.clone(), circuit.nodes[n].pos);
+            inner.nodes[m].delay = circuit.nodes[n].delay;
+            map.insert(n, m);
+        }
+        for &n in &self.selected {
+            inner.nodes[map[&n]].inputs = circuit.nodes[n]
+                .inputs
+                .iter()
+                .map(|src| {
+                    src.map(|src| match map.get(&src.node) {
+                        Some(&m) => Source { node: m, ..src },
+                        None => {
+                            let (i, k) = find(&self.inputs, src).unwrap();
+                            Source { node: i, pin: k }
+                        }
+                    })
+                })
+                .collect();
+        }
+        for (bits, name) in &self.outputs {
+            let y = inner.add_node(NodeKind::bus_output(name.clone(), bits.len()), Pos2::ZERO);
+            inner.nodes[y].inputs = bits.iter().map(|src| Some(Source { node: map[&src.node], ..*src })).collect();
+        }
+        layout::arrange(&mut inner);
+        let subcircuit = Rc::new(Subcircuit { name: self.name.trim().to_string(), symbol: self.symbol.trim().to_string(), circuit: inner });
+
+        // Erster Pin jedes Anschlusses am Baustein
+        let offsets = |ports: &[(Vec<Source>, String)]| {
+            ports.iter().scan(0, |pin, (bits, _)| Some(std::mem::replace(pin, *pin + bits.len()))).collect::<Vec<usize>>()
+        };
+        let (input_offsets, output_offsets) = (offsets(&self.inputs), offsets(&self.outputs));
+        // Abnehmer außerhalb: (Knoten, Eingang, Pin des Bausteins)
+        let consumers: Vec<(usize, usize, usize)> = (0..circuit.nodes.len())
+            .filter(|&n| !inside(n))
+            .flat_map(|n| {
+                let (outputs, offsets) = (&self.outputs, &output_offsets);
+                circuit.nodes[n].inputs.iter().enumerate().filter_map(move |(pin, src)| {
+                    src.and_then(|src| find(outputs, src)).map(|(i, k)| (n, pin, offsets[i] + k))
+                })
+            })
+            .collect();
+        let count = self.selected.len() as f32;
+        let center = self.selected.iter().fold(Pos2::ZERO, |acc, &n| acc + circuit.nodes[n].pos.to_vec2() / count);
+        for &n in self.selected.iter().rev() {
+            circuit.remove_node(n);
+        }
+        // Indizes nach dem Entfernen der Auswahl
+        let shift = |n: usize| n - self.selected.iter().filter(|&&s| s < n).count();
+        let block = circuit.add_node(NodeKind::Component(Component::Custom(subcircuit.clone())), center);
+        for ((bits, _), offset) in self.inputs.iter().zip(input_offsets) {
+            for (k, src) in bits.iter().enumerate() {
+                circuit.nodes[block].inputs[offset + k] = Some(Source { node: shift(src.node), ..*src });
+            }
+        }
+        for (n, pin, block_pin) in consumers {
+            let node = &mut circuit.nodes[shift(n)];
+            node.inputs[pin] = Some(Source { node: block, pin: block_pin });
+            node.routes[pin].clear();
+        }
+        subcircuit
+    }
+}
@@ -0,0 +1,177 @@
+// Aufbau zweistufiger Schaltungen aus minimierten Funktionen
+
+use std::collections::HashMap;
+
+use egui::{Pos2, Ui};
+
+use crate::circuit::{Circuit, NodeKind};
+use crate::layout;
+use crate::minimize::{self, SharedCover};
+use crate::truth_table::TruthTable;
+use crate::LogicGates;
+
+/// Bis zu dieser Anzahl an Eingängen wird ein Ausdruck für die Synthese minimiert
+pub const MAX_INPUTS: usize = 6;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Form {
+    AndOr,
+    OrAnd,
+    NandNand,
+}
+
+impl Form {
+    pub const ALL: [Form; 3] = [Form::AndOr, Form::OrAnd, Form::NandNand];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Form::AndOr => "UND-ODER (aus der DNF)",
+            Form::OrAnd => "ODER-UND (aus der KNF)",
+            Form::NandNand => "NAND-NAND",
+        }
+    }
+}
+
+/// Verknüpft die Quellen mit einer Kette aus Gattern mit zwei Eingängen; eine einzelne Quelle bleibt ohne Gatter
+fn chain(circuit: &mut Circuit, gate: LogicGates, sources: &[usize]) -> usize {
+    sources[1..].iter().fold(sources[0], |acc, &src| gate2(circuit, gate, acc, src))
+}
+
+fn gate2(circuit: &mut Circuit, gate: LogicGates, a: usize, b: usize) -> usize {
+    let g = circuit.add_node(NodeKind::Gate(gate), Pos2::ZERO);
+    circuit.nodes[g].inputs = vec![Some(a.into()), Some(b.into())];
+    g
+}
+
+/// NAND über alle Quellen. Die Gatter haben nur zwei Eingänge, daher werden bei mehr Quellen die ersten per UND
+/// zusammengefasst, jeweils ein NAND mit einem NAND als Negation dahinter; eine einzelne Quelle wird negiert.
+fn nand_of(circuit: &mut Circuit, sources: &[usize]) -> usize {
+    let (&last, rest) = sources.split_last().unwrap();
+    let and = match rest.split_first() {
+        None => last,
+        Some((&first, rest)) => rest.iter().fold(first, |acc, &src| {
+            let nand = gate2(circuit, LogicGates::NAND, acc, src);
+            gate2(circuit, LogicGates::NAND, nand, nand)
+        }),
+    };
+    gate2(circuit, LogicGates::NAND, and, last)
+}
+
+fn not_gate(circuit: &mut Circuit, src: usize) -> usize {
+    let g = circuit.add_node(NodeKind::Gate(LogicGates::NOT), Pos2::ZERO);
+    circuit.nodes[g].inputs[0] = Some(src.into());
+    g
+}
+
+/// Zweistufige Schaltung: die erste Stufe bildet die Terme (UND bzw. NAND bei DNF, ODER bei KNF), die zweite
+/// verknüpft sie je Ausgang. Jeder Term und jede Negation eines Eingangs wird nur einmal aufgebaut und von allen
+/// Ausgängen genutzt, die ihn brauchen. Konstante Ausgänge brauchen keine Gatter und fehlen in der Schaltung.
+fn two_level(inputs: &[String], outputs: &[String], cover: &SharedCover, form: Form) -> Result<Circuit, String> {
+    let n = inputs.len();
+    let dnf = form != Form::OrAnd;
+    // Ohne Terme bzw. mit einem Term ohne Literale ist der Ausgang konstant
+    let constant =
+        |o: usize| cover.outputs[o].is_empty() || cover.outputs[o].iter().any(|&t| cover.terms[t].literal_count(n) == 0);
+    if (0..outputs.len()).all(constant) {
+        return Err("Alle Ausgänge sind konstant und brauchen daher keine Gatter.".to_string());
+    }
+
+    let mut circuit = Circuit::default();
+    for name in inputs {
+        circuit.add_node(NodeKind::input(name.clone()), Pos2::ZERO);
+    }
+    let mut negated = HashMap::new();
+    let mut negate = |circuit: &mut Circuit, i: usize| {
+        *negated.entry(i).or_insert_with(|| match form {
+            Form::NandNand => gate2(circuit, LogicGates::NAND, i, i),
+            Form::AndOr | Form::OrAnd => not_gate(circuit, i),
+        })
+    };
+    let mut terms = vec![None; cover.terms.len()];
+    for (o, _) in outputs.iter().enumerate().filter(|&(o, _)| !constant(o)) {
+        for &t in &cover.outputs[o] {
+            if terms[t].is_some() {
+                continue;
+            }
+            let term = &cover.terms[t];
+            // Ein Block aus Nullen liefert in der KNF die negierten Literale
+            let literals: Vec<(usize, bool)> = (0..n)
+                .filter(|i| term.mask >> (n - 1 - i) & 1 == 0)
+                .map(|i| (i, (term.bits >> (n - 1 - i) & 1 == 1) == dnf))
+                .collect();
+            let mut literal = |circuit: &mut Circuit, (i, positive): (usize, bool)| if positive { i } else { negate(circuit, i) };
+            terms[t] = Some(match form {
+                // Das NAND eines einzelnen Literals ist das Literal mit umgekehrtem Vorzeichen
+                Form::NandNand if literals.len() == 1 => literal(&mut circuit, (literals[0].0, !literals[0].1)),
+                Form::NandNand => {
+                    let sources: Vec<usize> = literals.into_iter().map(|l| literal(&mut circuit, l)).collect();
+                    nand_of(&mut circuit, &sources)
+                }
+                Form::AndOr | Form::OrAnd => {
+                    let sources: Vec<usize> = literals.into_iter().map(|l| literal(&mut circuit, l)).collect();
+                    chain(&mut circuit, if dnf { LogicGates::AND } else { LogicGates::OR }, &sources)
+                }
+            });
+        }
+    }
+    for (o, name) in outputs.iter().enumerate().filter(|&(o, _)| !constant(o)) {
+        let sources: Vec<usize> = cover.outputs[o].iter().map(|&t| terms[t].unwrap()).collect();
+        let src = match form {
+            Form::AndOr => chain(&mut circuit, LogicGates::OR, &sources),
+            Form::OrAnd => chain(&mut circuit, LogicGates::AND, &sources),
+            Form::NandNand => nand_of(&mut circuit, &sources),
+        };
+        let y = circuit.add_node(NodeKind::output(name.clone()), Pos2::ZERO);
+        circuit.nodes[y].inputs[0] = Some(src.into());
+    }
+    layout::arrange(&mut circuit);
+    Ok(circuit)
+}
+
+/// UND-ODER-Schaltung zur gemeinsamen DNF aller Ausgänge
+pub fn and_or(inputs: &[String], outputs: &[String], cover: &SharedCover) -> Result<Circuit, String> {
+    two_level(inputs, outputs, cover, Form::AndOr)
+}
+
+/// Erzeugt die Schaltung in der gewünschten Form. `dnf` ist die bereits berechnete gemeinsame DNF der Tabelle.
+pub fn synthesize(table: &TruthTable, dnf: &SharedCover, form: Form) -> Result<Circuit, String> {
+    let outputs: Vec<String> = table.outputs.iter().map(|o| o.name.clone()).collect();
+    match form {
+        Form::AndOr | Form::NandNand => two_level(&table.inputs, &outputs, dnf, form),
+        Form::OrAnd => {
+            let covers: Vec<_> = table.outputs.iter().map(|o| minimize::minimal_cnf(o, table.inputs.len()).0).collect();
+            two_level(&table.inputs, &outputs, &SharedCover::from_covers(&covers), form)
+        }
+    }
+}
+
+/// Wie `synthesize`, aber aus hazardfreien Überdeckungen: benachbarte Einsen (bzw. Nullen bei der KNF) liegen
+/// immer in einem gemeinsamen Term, daher bleibt der Ausgang beim Wechsel eines Eingangs stabil
+pub fn synthesize_hazard_free(table: &TruthTable, form: Form) -> Result<Circuit, String> {
+    let n = table.inputs.len();
+    let outputs: Vec<String> = table.outputs.iter().map(|o| o.name.clone()).collect();
+    match form {
+        Form::OrAnd => {
+            let covers: Vec<_> = table.outputs.iter().map(|o| minimize::hazard_free_cover(&o.maxterms(), &o.dont_cares(), n)).collect();
+            two_level(&table.inputs, &outputs, &SharedCover::from_covers(&covers), form)
+        }
+        Form::AndOr | Form::NandNand => {
+            let covers: Vec<_> = table.outputs.iter().map(|o| minimize::hazard_free_cover(&o.minterms(), &o.dont_cares(), n)).collect();
+            synthesize(table, &SharedCover::from_covers(&covers), form)
+        }
+    }
+}
+
+/// Menü "Schaltung erzeugen"; liefert die gewählte Form
+pub fn menu(ui: &mut Ui) -> Option<Form> {
+    let mut chosen = None;
+    ui.menu_button("Schaltung erzeugen", |ui| {
+        for form in Form::ALL {
+            if ui.button(form.name()).clicked() {
+                chosen = Some(form);
+                ui.close_menu();
+            }
+        }
+    });
+    chosen
+}
@@ -0,0 +1,30 @@
+// Hilfsmittel für die Tests mehrerer Module
+
+use crate::expr::Expr;
+
+/// Reproduzierbare Pseudozufallszahlen (xorshift), damit fehlgeschlagene Fälle sich nachstellen lassen
+pub struct Rng(pub u64);
+
+impl Rng {
+    pub fn below(&mut self, n: usize) -> usize {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 % n as u64) as usize
+    }
+}
+
+/// Zufälliger Ausdruck über `vars` Variablen mit höchstens `depth` Ebenen, auf Wunsch auch mit Konstanten
+pub fn random_expr(rng: &mut Rng, vars: usize, depth: usize, constants: bool) -> Expr {
+    if depth == 0 || rng.below(4) == 0 {
+        return if constants && rng.below(5) == 0 { Expr::Const(rng.below(2) == 1) } else { Expr::Var(rng.below(vars)) };
+    }
+    let op = rng.below(4);
+    let mut sub = || Box::new(random_expr(rng, vars, depth - 1, constants));
+    match op {
+        0 => Expr::Not(sub()),
+        1 => Expr::And(vec![*sub(), *sub()]),
+        2 => Expr::Or(vec![*sub(), *sub()]),
+        _ => Expr::Xor(sub(), sub()),
+    }
+}
@@ -0,0 +1,87 @@
+// Tabellen als Text zum Einfügen in Arbeitsblätter: mit Tabulatoren getrennt (für Textverarbeitung und
+// Tabellenkalkulation), als Markdown-Tabelle oder als LaTeX-Umgebung `tabular`
+
+use crate::latex;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TableFormat {
+    Plain,
+    Markdown,
+    Latex,
+}
+
+impl TableFormat {
+    pub const ALL: [TableFormat; 3] = [TableFormat::Plain, TableFormat::Markdown, TableFormat::Latex];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            TableFormat::Plain => "Text",
+            TableFormat::Markdown => "Markdown",
+            TableFormat::Latex => "LaTeX",
+        }
+    }
+}
+
+pub struct TextTable {
+    pub header: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+    /// Nach so vielen Spalten folgt ein senkrechter Strich, z.B. zwischen Ein- und Ausgängen
+    pub split: usize,
+}
+
+impl TextTable {
+    pub fn format(&self, format: TableFormat) -> String {
+        match format {
+            TableFormat::Plain => self.lines().map(|cells| cells.join("\t") + "\n").collect(),
+            TableFormat::Markdown => {
+                let line = |cells: &[String]| format!("| {} |\n", cells.join(" | "));
+                let mut text = line(&self.header);
+                text += &line(&vec![":-:".to_string(); self.header.len()]);
+                self.rows.iter().for_each(|row| text += &line(row));
+                text
+            }
+            TableFormat::Latex => {
+                let columns = "c".repeat(self.split) + "|" + &"c".repeat(self.header.len() - self.split);
+                let line = |cells: &[String]| format!("  {} \\\\\n", cells.iter().map(|c| latex::escape(c)).collect::<Vec<_>>().join(" & "));
+                let mut text = format!("\\begin{{tabular}}{{{}}}\n", columns);
+                text += &line(&self.header);
+                text += "  \\hline\n";
+                self.rows.iter().for_each(|row| text += &line(row));
+                text + "\\end{tabular}\n"
+            }
+        }
+    }
+
+    /// HTML-Tabelle; die Klasse `split` markiert die erste Spalte nach dem Trennstrich
+    pub fn html(&self) -> String {
+        let line = |tag: &str, cells: &[String]| {
+            let cells: Vec<String> = cells
+                .iter()
+                .enumerate()
+                .map(|(i, cell)| {
+                    let class = if i == self.split { " class=\"split\"" } else { "" };
+                    format!("<{}{}>{}</{}>", tag, class, html_escape(cell), tag)
+                })
+                .collect();
+            format!("<tr>{}</tr>\n", cells.concat())
+        };
+        let mut text = "<table>\n".to_string() + &line("th", &self.header);
+        self.rows.iter().for_each(|row| text += &line("td", row));
+        text + "</table>\n"
+    }
+
+    /// Leert alle Zellen ab Spalte `column`, z.B. für eine Tabelle zum Ausfüllen
+    pub fn blank(mut self, column: usize) -> Self {
+        self.rows.iter_mut().flat_map(|row| row.iter_mut().skip(column)).for_each(String::clear);
+        self
+    }
+
+    fn lines(&self) -> impl Iterator<Item = &Vec<String>> {
+        std::iter::once(&self.header).chain(&self.rows)
+    }
+}
+
+/// Zeichen mit Sonderbedeutung in HTML und SVG
+pub fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
@@ -0,0 +1,85 @@
+// Farbschemata: neben dem dunklen Standard ein helles, ein kontrastreiches und eines mit Farben, die auch bei einer
+// Rot-Grün-Schwäche unterscheidbar sind (Palette nach Okabe und Ito). Zusätzlich lassen sich Pegel unabhängig von
+// der Farbe kennzeichnen: Low-Leitungen gestrichelt, Z gepunktet, Pegelanzeigen mit 1/0 beschriftet.
+
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+use egui::{Color32, Visuals};
+use serde::{Deserialize, Serialize};
+
+/// Die Pegelfarben werden an vielen Stellen ohne Zugriff auf den Kontext gebraucht, daher global
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PATTERNS: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Theme {
+    Dark,
+    Light,
+    HighContrast,
+    ColorBlind,
+}
+
+impl Theme {
+    pub const ALL: [Theme; 4] = [Theme::Dark, Theme::Light, Theme::HighContrast, Theme::ColorBlind];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Theme::Dark => "Dunkel",
+            Theme::Light => "Hell",
+            Theme::HighContrast => "Hoher Kontrast",
+            Theme::ColorBlind => "Farbenblind-freundlich",
+        }
+    }
+
+    pub fn visuals(&self) -> Visuals {
+        match self {
+            Theme::Dark | Theme::ColorBlind => Visuals::dark(),
+            Theme::Light => Visuals::light(),
+            // Schwarzer Hintergrund, weiße Schrift und kräftige Rahmen, damit auch ein blasser Beamer alles zeigt
+            Theme::HighContrast => {
+                let mut visuals = Visuals::dark();
+                visuals.override_text_color = Some(Color32::WHITE);
+                visuals.panel_fill = Color32::BLACK;
+                visuals.window_fill = Color32::BLACK;
+                visuals.extreme_bg_color = Color32::BLACK;
+                visuals.widgets.noninteractive.bg_stroke.color = Color32::WHITE;
+                visuals.widgets.noninteractive.fg_stroke.color = Color32::WHITE;
+                visuals.widgets.inactive.fg_stroke.color = Color32::WHITE;
+                visuals
+            }
+        }
+    }
+
+    /// Farben für Low und High
+    fn levels(&self) -> (Color32, Color32) {
+        match self {
+            Theme::Dark => (Color32::RED, Color32::GREEN),
+            Theme::Light => (Color32::from_rgb(200, 0, 0), Color32::from_rgb(0, 140, 0)),
+            // Blau und Gelb unterscheiden sich auch in der Helligkeit deutlich
+            Theme::HighContrast => (Color32::from_rgb(0, 150, 255), Color32::from_rgb(255, 255, 0)),
+            // Orange und Himmelblau
+            Theme::ColorBlind => (Color32::from_rgb(230, 159, 0), Color32::from_rgb(86, 180, 233)),
+        }
+    }
+
+    /// Bei diesen Schemata ist die Kennzeichnung unabhängig von der Farbe zunächst eingeschaltet
+    pub fn patterns_by_default(&self) -> bool {
+        matches!(self, Theme::HighContrast | Theme::ColorBlind)
+    }
+}
+
+/// Übernimmt Schema und Kennzeichnung für alle folgenden Frames
+pub fn apply(theme: Theme, patterns: bool) {
+    CURRENT.store(Theme::ALL.iter().position(|&t| t == theme).unwrap(), Ordering::Relaxed);
+    PATTERNS.store(patterns, Ordering::Relaxed);
+}
+
+pub fn level_color(high: bool) -> Color32 {
+    let (low, high_color) = Theme::ALL[CURRENT.load(Ordering::Relaxed)].levels();
+    if high { high_color } else { low }
+}
+
+/// Ob Pegel zusätzlich durch Beschriftung und Linienmuster gekennzeichnet werden
+pub fn patterns() -> bool {
+    PATTERNS.load(Ordering::Relaxed)
+}
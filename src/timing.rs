@@ -0,0 +1,410 @@
+// Zeitverhalten: ereignisgesteuerte Simulation mit Gatterlaufzeiten und Zeitdiagramm.
+// Jedes Gatter und jeder Baustein gibt eine Änderung an seinen Eingängen erst nach seiner Laufzeit weiter. Da jede
+// Änderung einzeln weitergereicht wird (Transportverzögerung), bleiben auch kurze Störimpulse (Glitches) sichtbar.
+// Simuliert wird vierwertig, so erscheinen auch abgeschaltete Treiber (Z) und Konflikte auf Sammelleitungen (X).
+
+use std::collections::BTreeMap;
+
+use egui::{Align2, Color32, FontId, Pos2, Rect, RichText, Sense, Stroke, Ui, Vec2};
+
+use crate::circuit::{Circuit, NodeKind};
+use crate::equivalence::row_inputs;
+use crate::level::Level;
+
+/// Laufzeit neuer Gatter und Bausteine in Zeiteinheiten
+pub const DEFAULT_DELAY: u32 = 1;
+pub const MAX_DELAY: u32 = 20;
+/// Mehr Belegungen passen nicht sinnvoll in ein Zeitdiagramm
+const MAX_STEPS: usize = 64;
+const NAME_WIDTH: f32 = 90.0;
+const ROW_HEIGHT: f32 = 26.0;
+const HEADER_HEIGHT: f32 = 22.0;
+
+/// Verlauf eines Signals: Wert zu Beginn und Zeitpunkte, an denen er wechselt
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Signal {
+    pub name: String,
+    pub initial: Level,
+    pub changes: Vec<(u32, Level)>,
+}
+
+impl Signal {
+    pub fn value_at(&self, t: u32) -> Level {
+        self.changes.iter().take_while(|(time, _)| *time <= t).last().map_or(self.initial, |&(_, v)| v)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HazardKind {
+    /// Der Ausgang soll 1 bleiben, fällt aber kurz auf 0
+    Static1,
+    /// Der Ausgang soll 0 bleiben, steigt aber kurz auf 1
+    Static0,
+    /// Der Ausgang soll einmal wechseln, wechselt aber mehrmals
+    Dynamic,
+}
+
+impl HazardKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            HazardKind::Static1 => "Statischer 1-Hazard",
+            HazardKind::Static0 => "Statischer 0-Hazard",
+            HazardKind::Dynamic => "Dynamischer Hazard",
+        }
+    }
+}
+
+/// Störimpuls an einem Ausgang nach einem Wechsel der Eingänge
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hazard {
+    pub kind: HazardKind,
+    /// Index des Ausgangsbits
+    pub output: usize,
+    /// Schritt der Eingangsfolge, nach dem er auftritt
+    pub step: usize,
+    /// Erster und letzter Wechsel des Ausgangs in diesem Schritt
+    pub start: u32,
+    pub end: u32,
+}
+
+/// Ergebnis einer Simulation
+pub struct Trace {
+    pub inputs: Vec<Signal>,
+    pub outputs: Vec<Signal>,
+    /// Ausgänge der Gatter und Bausteine
+    pub internal: Vec<Signal>,
+    /// Zeitpunkte, zu denen eine neue Belegung angelegt wird, und die Belegung
+    pub steps: Vec<(u32, Vec<bool>)>,
+    pub end: u32,
+}
+
+impl Trace {
+    /// Schritte, nach denen ein Ausgang mehr als einmal wechselt
+    pub fn hazards(&self) -> Vec<Hazard> {
+        let mut result = Vec::new();
+        for (output, signal) in self.outputs.iter().enumerate() {
+            for (step, &(start, _)) in self.steps.iter().enumerate().skip(1) {
+                let stop = self.steps.get(step + 1).map_or(self.end + 1, |&(t, _)| t);
+                let changes: Vec<(u32, Level)> = signal.changes.iter().copied().filter(|(t, _)| (start..stop).contains(t)).collect();
+                if changes.len() < 2 {
+                    continue;
+                }
+                let (before, after) = (signal.value_at(start - 1), changes[changes.len() - 1].1);
+                let kind = match (before, after) {
+                    (Level::High, Level::High) => HazardKind::Static1,
+                    (Level::Low, Level::Low) => HazardKind::Static0,
+                    _ => HazardKind::Dynamic,
+                };
+                result.push(Hazard { kind, output, step, start: changes[0].0, end: changes[changes.len() - 1].0 });
+            }
+        }
+        result
+    }
+}
+
+/// Legt die Belegungen der Eingangsbits im Abstand `period` an. Vor der ersten Belegung ist die Schaltung eingeschwungen.
+pub fn simulate(circuit: &Circuit, sequence: &[Vec<bool>], period: u32) -> Trace {
+    let n = circuit.nodes.len();
+    let levels = |bits: &[bool]| bits.iter().map(|&b| Level::from(b)).collect::<Vec<_>>();
+    let initial = circuit.node_levels(&levels(&sequence[0]));
+    let mut values = initial.clone();
+    let mut consumers: Vec<Vec<usize>> = vec![Vec::new(); n];
+    for (c, node) in circuit.nodes.iter().enumerate() {
+        for src in node.inputs.iter().flatten() {
+            if !consumers[src.node].contains(&c) {
+                consumers[src.node].push(c);
+            }
+        }
+    }
+    let steps: Vec<(u32, Vec<bool>)> = sequence.iter().enumerate().map(|(k, bits)| (k as u32 * period, bits.clone())).collect();
+    // Anstehende neue Pegel je Zeitpunkt
+    let mut events: BTreeMap<u32, Vec<(usize, Vec<Level>)>> = BTreeMap::new();
+    for (time, bits) in &steps[1..] {
+        let mut offset = 0;
+        for i in circuit.input_nodes() {
+            let width = circuit.nodes[i].kind.output_count();
+            events.entry(*time).or_default().push((i, levels(&bits[offset..offset + width])));
+            offset += width;
+        }
+    }
+    let mut history: Vec<Vec<(u32, Vec<Level>)>> = vec![Vec::new(); n];
+    let mut last = 0;
+    while let Some((t, batch)) = events.pop_first() {
+        last = t;
+        let mut changed = Vec::new();
+        for (node, new) in batch {
+            if values[node] != new {
+                values[node] = new.clone();
+                history[node].push((t, new));
+                changed.push(node);
+            }
+        }
+        let mut affected: Vec<usize> = changed.iter().flat_map(|&c| consumers[c].iter().copied()).collect();
+        affected.sort_unstable();
+        affected.dedup();
+        for c in affected {
+            // Ausgänge zeigen ihren Pegel sofort an
+            let delay = if matches!(circuit.nodes[c].kind, NodeKind::Output { .. }) { 0 } else { circuit.nodes[c].delay };
+            events.entry(t + delay).or_default().push((c, circuit.compute_levels(c, &values)));
+        }
+    }
+
+    let signal = |name: String, node: usize, pin: usize| {
+        let start = initial[node][pin];
+        let mut changes: Vec<(u32, Level)> = Vec::new();
+        for (t, v) in &history[node] {
+            // Mehrere Wechsel zum selben Zeitpunkt (bei Laufzeit 0) zählen nur mit ihrem Ergebnis
+            if changes.last().is_some_and(|&(time, _)| time == *t) {
+                changes.pop();
+            }
+            if v[pin] != changes.last().map_or(start, |&(_, value)| value) {
+                changes.push((*t, v[pin]));
+            }
+        }
+        Signal { name, initial: start, changes }
+    };
+    let internal = (0..n)
+        .flat_map(|node| match &circuit.nodes[node].kind {
+            NodeKind::Gate(gate) => vec![(format!("{} #{}", gate, node), node, 0)],
+            NodeKind::Component(component) => {
+                let names = component.output_ports().into_iter().flat_map(|port| port.bit_names());
+                names.enumerate().map(|(pin, name)| (format!("{} #{} {}", component.symbol(), node, name), node, pin)).collect()
+            }
+            NodeKind::Tristate(tristate) => vec![(format!("{} #{}", tristate.name(), node), node, 0)],
+            NodeKind::Input { .. } | NodeKind::Output { .. } => Vec::new(),
+        })
+        .map(|(name, node, pin)| signal(name, node, pin))
+        .collect();
+    Trace {
+        inputs: circuit.input_bits().into_iter().zip(circuit.input_names()).map(|(src, name)| signal(name, src.node, src.pin)).collect(),
+        outputs: circuit.output_bits().into_iter().zip(circuit.output_names()).map(|((node, pin), name)| signal(name, node, pin)).collect(),
+        internal,
+        end: (steps.len() as u32 * period).max(last + 1),
+        steps,
+    }
+}
+
+/// Längste Laufzeit von einem Eingang bis zu einem Ausgang
+pub fn critical_path(circuit: &Circuit) -> u32 {
+    let mut arrival = vec![0; circuit.nodes.len()];
+    for n in circuit.topological_order() {
+        let node = &circuit.nodes[n];
+        let latest = node.inputs.iter().flatten().map(|src| arrival[src.node]).max().unwrap_or(0);
+        arrival[n] = match node.kind {
+            NodeKind::Input { .. } => 0,
+            NodeKind::Output { .. } => latest,
+            NodeKind::Gate(_) | NodeKind::Component(_) | NodeKind::Tristate(_) => latest + node.delay,
+        };
+    }
+    circuit.output_nodes().into_iter().map(|n| arrival[n]).max().unwrap_or(0)
+}
+
+/// Reihenfolge, in der die Belegungen angelegt werden
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Order {
+    /// Es wechselt immer nur ein Eingang, so zeigen sich die statischen Hazards
+    #[default]
+    Gray,
+    Counting,
+    /// Selbst eingegebene Zeilennummern, z.B. die Zeilen eines Hazards im KV-Diagramm
+    Rows,
+}
+
+impl Order {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Order::Gray => "Gray-Code (ein Eingang je Schritt)",
+            Order::Counting => "Binär zählend",
+            Order::Rows => "Zeilen:",
+        }
+    }
+}
+
+/// Alle Belegungen (höchstens `MAX_STEPS`) und zum Schluss wieder die erste
+pub fn sequence(gray: bool, bits: usize) -> Vec<Vec<bool>> {
+    let count = 1usize.checked_shl(bits as u32).map_or(MAX_STEPS, |count| count.min(MAX_STEPS));
+    (0..count).chain([0]).map(|i| row_inputs(if gray { i ^ (i >> 1) } else { i }, bits)).collect()
+}
+
+/// Zeilennummern, getrennt durch Leerzeichen oder Kommas
+fn parse_rows(text: &str, bits: usize) -> Result<Vec<Vec<bool>>, String> {
+    let rows = text
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|part| !part.is_empty())
+        .map(|part| match part.parse::<usize>() {
+            Ok(row) if bits >= usize::BITS as usize || row < 1 << bits => Ok(row_inputs(row, bits)),
+            _ => Err(format!("\"{}\" ist keine Zeile der Wahrheitstabelle.", part)),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    match rows.len() {
+        0 => Err("Gib mindestens eine Zeilennummer ein.".to_string()),
+        n if n > MAX_STEPS => Err(format!("Höchstens {} Zeilen.", MAX_STEPS)),
+        _ => Ok(rows),
+    }
+}
+
+fn bit_string(bits: &[bool]) -> String {
+    bits.iter().map(|&b| if b { '1' } else { '0' }).collect()
+}
+
+/// Zeitdiagramm der aktuellen Schaltung in einem eigenen Fenster
+pub struct TimingView {
+    pub open: bool,
+    order: Order,
+    rows: String,
+    /// Zeit zwischen zwei Belegungen
+    period: u32,
+    show_internal: bool,
+}
+
+impl Default for TimingView {
+    fn default() -> Self {
+        Self { open: false, order: Order::default(), rows: "0 1 0".to_string(), period: 10, show_internal: false }
+    }
+}
+
+impl TimingView {
+    pub fn show(&mut self, ctx: &egui::Context, circuit: &Circuit) {
+        let mut open = self.open;
+        egui::Window::new("Zeitdiagramm")
+            .open(&mut open)
+            .default_size(Vec2::new(760.0, 420.0))
+            .show(ctx, |ui| self.show_contents(ui, circuit));
+        self.open &= open;
+    }
+
+    fn show_contents(&mut self, ui: &mut Ui, circuit: &Circuit) {
+        let input_count = circuit.input_names().len();
+        if input_count == 0 || circuit.output_nodes().is_empty() {
+            ui.label("Die Schaltung braucht mindestens einen Eingang und einen Ausgang.");
+            return;
+        }
+        ui.horizontal(|ui| {
+            ui.label("Eingangsfolge:");
+            for order in [Order::Gray, Order::Counting, Order::Rows] {
+                ui.radio_value(&mut self.order, order, order.label());
+            }
+            ui.add_enabled(self.order == Order::Rows, egui::TextEdit::singleline(&mut self.rows).desired_width(120.0))
+                .on_hover_text("Zeilennummern der Wahrheitstabelle, z.B. 3 7 3");
+        });
+        let critical = critical_path(circuit);
+        ui.horizontal(|ui| {
+            ui.label("Abstand der Wechsel:");
+            ui.add(egui::DragValue::new(&mut self.period).clamp_range(1..=200).suffix(" t"));
+            ui.label(format!("Längster Pfad: {} t", critical));
+            ui.separator();
+            ui.checkbox(&mut self.show_internal, "Gatterausgänge anzeigen");
+        });
+        ui.label("Die Laufzeit eines Gatters oder Bausteins stellst du in seinem Kontextmenü ein.");
+        if self.period <= critical {
+            ui.label(RichText::new("⚠ Der Abstand ist nicht länger als der längste Pfad, die Ausgänge schwingen evtl. erst nach dem nächsten Wechsel ein.").color(Color32::YELLOW));
+        }
+        let sequence = match self.order {
+            Order::Gray | Order::Counting => {
+                if 1usize.checked_shl(input_count as u32).is_none_or(|count| count > MAX_STEPS) {
+                    ui.label(format!("Es werden nur die ersten {} Belegungen durchlaufen.", MAX_STEPS));
+                }
+                sequence(self.order == Order::Gray, input_count)
+            }
+            Order::Rows => match parse_rows(&self.rows, input_count) {
+                Ok(rows) => rows,
+                Err(e) => {
+                    ui.label(RichText::new(e).color(Color32::RED));
+                    return;
+                }
+            },
+        };
+        let trace = simulate(circuit, &sequence, self.period);
+        let hazards = trace.hazards();
+        if hazards.is_empty() {
+            ui.label(RichText::new("✔ Kein Ausgang wechselt nach einer Änderung der Eingänge mehr als einmal.").color(Color32::GREEN));
+        }
+        egui::ScrollArea::vertical().id_source("timing_hazards").max_height(80.0).show(ui, |ui| {
+            for h in &hazards {
+                let (from, to) = (&trace.steps[h.step - 1].1, &trace.steps[h.step].1);
+                ui.label(
+                    RichText::new(format!(
+                        "⚠ {} an {} beim Wechsel von {} nach {} (t = {} bis {})",
+                        h.kind.label(),
+                        trace.outputs[h.output].name,
+                        bit_string(from),
+                        bit_string(to),
+                        h.start,
+                        h.end
+                    ))
+                    .color(Color32::from_rgb(255, 120, 80)),
+                );
+            }
+        });
+        ui.separator();
+        egui::ScrollArea::both().id_source("timing_diagram").show(ui, |ui| self.show_diagram(ui, &trace, &hazards));
+    }
+
+    fn show_diagram(&self, ui: &mut Ui, trace: &Trace, hazards: &[Hazard]) {
+        let scale = ((ui.available_width() - NAME_WIDTH - 10.0) / trace.end as f32).max(3.0);
+        // Zeilen: Signal und ggf. Index des Ausgangs
+        let mut rows: Vec<(&Signal, Option<usize>)> = trace.inputs.iter().map(|s| (s, None)).collect();
+        rows.extend(trace.outputs.iter().enumerate().map(|(o, s)| (s, Some(o))));
+        if self.show_internal {
+            rows.extend(trace.internal.iter().map(|s| (s, None)));
+        }
+        let size = Vec2::new(NAME_WIDTH + scale * trace.end as f32 + 10.0, HEADER_HEIGHT + ROW_HEIGHT * rows.len() as f32 + 20.0);
+        let (response, painter) = ui.allocate_painter(size, Sense::hover());
+        let rect = response.rect;
+        let x = |t: u32| rect.left() + NAME_WIDTH + t as f32 * scale;
+        let text_color = ui.visuals().text_color();
+        let grid = Color32::from_gray(90);
+        let bottom = rect.top() + HEADER_HEIGHT + ROW_HEIGHT * rows.len() as f32;
+
+        // Belegungen über den Zeitpunkten, an denen sie angelegt werden
+        let step_width = trace.steps.get(1).map_or(f32::INFINITY, |(t, _)| *t as f32 * scale);
+        for (t, bits) in &trace.steps {
+            painter.line_segment([Pos2::new(x(*t), rect.top() + HEADER_HEIGHT - 4.0), Pos2::new(x(*t), bottom)], Stroke::new(1.0, grid));
+            if step_width >= bits.len() as f32 * 8.0 + 4.0 {
+                painter.text(Pos2::new(x(*t) + 2.0, rect.top() + 2.0), Align2::LEFT_TOP, bit_string(bits), FontId::monospace(11.0), text_color);
+            }
+            painter.text(Pos2::new(x(*t), bottom + 2.0), Align2::CENTER_TOP, t.to_string(), FontId::proportional(10.0), Color32::GRAY);
+        }
+        for (r, (signal, output)) in rows.iter().enumerate() {
+            let top = rect.top() + HEADER_HEIGHT + r as f32 * ROW_HEIGHT;
+            // Z liegt in der Mitte, X füllt die ganze Höhe aus
+            let level = |v: Level| match v {
+                Level::High => top + 5.0,
+                Level::Low => top + ROW_HEIGHT - 5.0,
+                Level::Z | Level::X => top + ROW_HEIGHT / 2.0,
+            };
+            if r == trace.inputs.len() || r == trace.inputs.len() + trace.outputs.len() {
+                painter.line_segment([Pos2::new(rect.left(), top), Pos2::new(rect.right(), top)], Stroke::new(1.0, grid));
+            }
+            painter.text(Pos2::new(rect.left() + 2.0, top + ROW_HEIGHT / 2.0), Align2::LEFT_CENTER, &signal.name, FontId::proportional(13.0), text_color);
+            for h in hazards.iter().filter(|h| Some(h.output) == *output) {
+                let area = Rect::from_min_max(Pos2::new(x(h.start) - 3.0, top + 1.0), Pos2::new(x(h.end) + 3.0, top + ROW_HEIGHT - 1.0));
+                painter.rect_filled(area, 2.0, Color32::from_rgba_unmultiplied(255, 80, 40, 70));
+            }
+            let segment = |from: u32, to: u32, v: Level| {
+                let stroke = Stroke::new(2.0, v.color());
+                if v == Level::X {
+                    let band = Rect::from_min_max(Pos2::new(x(from), top + 5.0), Pos2::new(x(to), top + ROW_HEIGHT - 5.0));
+                    painter.rect_filled(band, 0.0, v.color().linear_multiply(0.3));
+                    painter.line_segment([band.left_top(), band.right_top()], stroke);
+                    painter.line_segment([band.left_bottom(), band.right_bottom()], stroke);
+                } else {
+                    painter.line_segment([Pos2::new(x(from), level(v)), Pos2::new(x(to), level(v))], stroke);
+                }
+            };
+            let mut from = (0, signal.initial);
+            for &(t, v) in &signal.changes {
+                segment(from.0, t, from.1);
+                painter.line_segment([Pos2::new(x(t), level(from.1)), Pos2::new(x(t), level(v))], Stroke::new(1.5, text_color));
+                from = (t, v);
+            }
+            segment(from.0, trace.end, from.1);
+        }
+        // Zeitpunkt unter dem Mauszeiger
+        if let Some(p) = response.hover_pos().filter(|p| p.x >= x(0)) {
+            let t = ((p.x - x(0)) / scale).round() as u32;
+            painter.line_segment([Pos2::new(x(t), rect.top() + HEADER_HEIGHT), Pos2::new(x(t), bottom)], Stroke::new(1.0, Color32::LIGHT_BLUE));
+            painter.text(Pos2::new(x(t) + 3.0, bottom - 2.0), Align2::LEFT_BOTTOM, format!("t = {}", t), FontId::proportional(11.0), Color32::LIGHT_BLUE);
+        }
+    }
+}
@@ -0,0 +1,173 @@
+// Wahrheitstabelle einer Funktion mit einem oder mehreren Ausgängen und ihre kanonischen Normalformen
+
+use serde::{Deserialize, Serialize};
+
+use crate::circuit::Circuit;
+use crate::equivalence::row_inputs;
+use crate::expr::{and_of, or_of, Expr};
+use crate::text_table::TextTable;
+use crate::LogicGates;
+
+/// Eintrag in der Ausgangsspalte; bei `DontCare` ("X") ist der Wert beliebig, etwa weil die
+/// Eingangsbelegung nie vorkommt
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Value {
+    Zero,
+    One,
+    DontCare,
+}
+
+impl From<bool> for Value {
+    fn from(b: bool) -> Self {
+        if b { Value::One } else { Value::Zero }
+    }
+}
+
+impl Value {
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Value::Zero => "0",
+            Value::One => "1",
+            Value::DontCare => "X",
+        }
+    }
+
+    /// Reihenfolge beim Anklicken einer Zelle: 0 → 1 → X → 0
+    pub fn next(&self) -> Self {
+        match self {
+            Value::Zero => Value::One,
+            Value::One => Value::DontCare,
+            Value::DontCare => Value::Zero,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Output {
+    pub name: String,
+    /// Ausgangswert je Tabellenzeile
+    pub values: Vec<Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TruthTable {
+    pub inputs: Vec<String>,
+    pub outputs: Vec<Output>,
+}
+
+impl TruthTable {
+    /// Tabelle, in der alle Ausgänge überall 0 sind
+    pub fn new(inputs: Vec<String>, outputs: Vec<String>) -> Self {
+        let rows = 1 << inputs.len();
+        let outputs = outputs.into_iter().map(|name| Output { name, values: vec![Value::Zero; rows] }).collect();
+        Self { inputs, outputs }
+    }
+
+    /// Zeilen werden wie in `equivalence::row_inputs` nummeriert, der erste Eingang ist das höchstwertige Bit
+    fn build(inputs: Vec<String>, names: Vec<String>, f: impl Fn(&[bool]) -> Vec<bool>) -> Self {
+        let rows: Vec<Vec<bool>> = (0..1 << inputs.len()).map(|row| f(&row_inputs(row, inputs.len()))).collect();
+        let outputs = names
+            .into_iter()
+            .enumerate()
+            .map(|(i, name)| Output { name, values: rows.iter().map(|r| r[i].into()).collect() })
+            .collect();
+        Self { inputs, outputs }
+    }
+
+    pub fn from_gate(gate: LogicGates) -> Self {
+        let inputs = ["A", "B"][..gate.input_count()].iter().map(|s| s.to_string()).collect();
+        Self::build(inputs, vec!["Y".to_string()], |v| vec![gate.compute(v[0], v.len() > 1 && v[1])])
+    }
+
+    pub fn from_expr(expr: &Expr, vars: &[String]) -> Self {
+        Self::build(vars.to_vec(), vec!["Y".to_string()], |v| vec![expr.eval(v)])
+    }
+
+    /// Ein Ausgang je Ausdruck, z.B. für die Referenzfunktionen einer Übung
+    pub fn from_exprs(vars: &[String], outputs: &[(String, Expr)]) -> Self {
+        let names = outputs.iter().map(|(name, _)| name.clone()).collect();
+        Self::build(vars.to_vec(), names, |v| outputs.iter().map(|(_, expr)| expr.eval(v)).collect())
+    }
+
+    pub fn from_circuit(circuit: &Circuit) -> Self {
+        Self::build(circuit.input_names(), circuit.output_names(), |v| circuit.evaluate(v))
+    }
+
+    pub fn row_count(&self) -> usize {
+        1 << self.inputs.len()
+    }
+
+    /// Tabelle zum Kopieren, ohne Zeilennummern; Ein- und Ausgänge sind durch einen Strich getrennt
+    pub fn text_table(&self) -> TextTable {
+        let header = self.inputs.iter().chain(self.outputs.iter().map(|o| &o.name)).cloned().collect();
+        let rows = (0..self.row_count())
+            .map(|row| {
+                let inputs = row_inputs(row, self.inputs.len()).into_iter().map(|v| if v { "1" } else { "0" }.to_string());
+                inputs.chain(self.outputs.iter().map(|o| o.values[row].symbol().to_string())).collect()
+            })
+            .collect();
+        TextTable { header, rows, split: self.inputs.len() }
+    }
+
+    /// Vollkonjunktion der Zeile, z.B. `¬A ∧ B` für Zeile 1 bei zwei Eingängen
+    pub fn minterm(&self, row: usize) -> Expr {
+        let literals = row_inputs(row, self.inputs.len())
+            .into_iter()
+            .enumerate()
+            .map(|(i, v)| if v { Expr::Var(i) } else { Expr::Not(Box::new(Expr::Var(i))) })
+            .collect();
+        and_of(literals)
+    }
+
+    /// Volldisjunktion, die genau in dieser Zeile 0 ist, z.B. `A ∨ ¬B` für Zeile 1
+    pub fn maxterm(&self, row: usize) -> Expr {
+        let literals = row_inputs(row, self.inputs.len())
+            .into_iter()
+            .enumerate()
+            .map(|(i, v)| if v { Expr::Not(Box::new(Expr::Var(i))) } else { Expr::Var(i) })
+            .collect();
+        or_of(literals)
+    }
+
+    /// Kanonische disjunktive Normalform: ODER aller Minterme (ohne die X-Zeilen)
+    pub fn dnf(&self, output: &Output) -> Expr {
+        or_of(output.minterms().into_iter().map(|row| self.minterm(row)).collect())
+    }
+
+    /// Kanonische konjunktive Normalform: UND aller Maxterme
+    pub fn cnf(&self, output: &Output) -> Expr {
+        and_of(output.maxterms().into_iter().map(|row| self.maxterm(row)).collect())
+    }
+}
+
+impl Output {
+    fn rows_with(&self, value: Value) -> Vec<usize> {
+        (0..self.values.len()).filter(|&row| self.values[row] == value).collect()
+    }
+
+    /// Zeilen, in denen der Ausgang 1 ist
+    pub fn minterms(&self) -> Vec<usize> {
+        self.rows_with(Value::One)
+    }
+
+    /// Zeilen, in denen der Ausgang 0 ist
+    pub fn maxterms(&self) -> Vec<usize> {
+        self.rows_with(Value::Zero)
+    }
+
+    /// Zeilen, deren Wert beliebig ist
+    pub fn dont_cares(&self) -> Vec<usize> {
+        self.rows_with(Value::DontCare)
+    }
+
+    /// Wert, den der Ausgang in allen festgelegten Zeilen hat; X-Zeilen zählen nicht
+    pub fn constant(&self) -> Option<bool> {
+        if self.minterms().is_empty() {
+            Some(false)
+        } else if self.maxterms().is_empty() {
+            Some(true)
+        } else {
+            None
+        }
+    }
+}
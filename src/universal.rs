@@ -0,0 +1,143 @@
+// Umformung einer Schaltung in eine gleichwertige Schaltung, die nur aus NAND- bzw. nur aus NOR-Gattern besteht
+
+use std::collections::HashMap;
+
+use egui::Pos2;
+
+use crate::circuit::{Circuit, NodeKind, Source};
+use crate::layout;
+use crate::{LogicGates, ALL_GATES};
+
+struct Builder {
+    circuit: Circuit,
+    /// NAND oder NOR
+    target: LogicGates,
+    /// Bereits gebildete Negationen in beide Richtungen, damit doppelte Negationen entfallen
+    negated: HashMap<Source, Source>,
+}
+
+impl Builder {
+    fn universal(&mut self, a: Source, b: Source) -> Source {
+        let g = self.circuit.add_node(NodeKind::Gate(self.target), Pos2::ZERO);
+        self.circuit.nodes[g].inputs = vec![Some(a), Some(b)];
+        g.into()
+    }
+
+    /// NICHT durch ein Gatter mit verbundenen Eingängen
+    fn not(&mut self, a: Source) -> Source {
+        if let Some(&n) = self.negated.get(&a) {
+            return n;
+        }
+        let n = self.universal(a, a);
+        self.negated.insert(a, n);
+        self.negated.insert(n, a);
+        n
+    }
+
+    fn gate(&mut self, gate: LogicGates, a: Source, b: Source) -> Source {
+        // Die Konstruktionen sind für NAND angegeben. Ersetzt man darin jedes NAND durch NOR,
+        // entsteht die duale Funktion, also z.B. aus der UND-Konstruktion ein ODER.
+        let gate = if self.target == LogicGates::NAND {
+            gate
+        } else {
+            match gate {
+                LogicGates::AND => LogicGates::OR,
+                LogicGates::OR => LogicGates::AND,
+                LogicGates::NAND => LogicGates::NOR,
+                LogicGates::NOR => LogicGates::NAND,
+                LogicGates::XOR => LogicGates::XNOR,
+                LogicGates::XNOR => LogicGates::XOR,
+                LogicGates::NOT => LogicGates::NOT,
+            }
+        };
+        match gate {
+            LogicGates::NOT => self.not(a),
+            LogicGates::NAND => self.universal(a, b),
+            LogicGates::AND => {
+                let n = self.universal(a, b);
+                self.not(n)
+            }
+            LogicGates::OR | LogicGates::NOR => {
+                let (na, nb) = (self.not(a), self.not(b));
+                let or = self.universal(na, nb);
+                if gate == LogicGates::OR { or } else { self.not(or) }
+            }
+            LogicGates::XOR | LogicGates::XNOR => {
+                let n = self.universal(a, b);
+                let (x, y) = (self.universal(a, n), self.universal(b, n));
+                let xor = self.universal(x, y);
+                if gate == LogicGates::XOR { xor } else { self.not(xor) }
+            }
+        }
+    }
+}
+
+/// Formt alle Gatter, von denen ein Ausgang abhängt, in `target` (NAND oder NOR) um.
+/// Gatter ohne Verbindung zu einem Ausgang fallen weg.
+pub fn convert(circuit: &Circuit, target: LogicGates) -> Result<Circuit, String> {
+    assert!(matches!(target, LogicGates::NAND | LogicGates::NOR));
+    // Bausteine werden vorher in ihre Gatter aufgelöst, danach haben nur noch Eingänge mit Bussen mehrere Ausgänge
+    let circuit = &circuit.flattened();
+    if circuit.nodes.iter().any(|node| matches!(node.kind, NodeKind::Tristate(_))) {
+        return Err("Tri-State-Treiber und Sammelleitungen lassen sich nicht aus NAND- oder NOR-Gattern aufbauen.".to_string());
+    }
+    let mut used = vec![false; circuit.nodes.len()];
+    let mut stack = circuit.output_nodes();
+    while let Some(n) = stack.pop() {
+        if !std::mem::replace(&mut used[n], true) {
+            stack.extend(circuit.nodes[n].inputs.iter().flatten().map(|src| src.node));
+        }
+    }
+
+    let mut builder = Builder { circuit: Circuit::default(), target, negated: HashMap::new() };
+    // Neue Quelle je Knoten; die Bits eines Busses folgen darauf
+    let mut map: Vec<Source> = vec![0.into(); circuit.nodes.len()];
+    let mapped = |map: &[Source], src: Source| Source { pin: map[src.node].pin + src.pin, ..map[src.node] };
+    // Ein- und Ausgänge behalten ihre Reihenfolge, damit die Wahrheitstabellen übereinstimmen
+    for n in circuit.input_nodes() {
+        map[n] = builder.circuit.add_node(circuit.nodes[n].kind.clone(), Pos2::ZERO).into();
+    }
+    for n in circuit.topological_order() {
+        let node = &circuit.nodes[n];
+        if let (NodeKind::Gate(gate), true) = (node.kind.clone(), used[n]) {
+            if node.inputs.contains(&None) {
+                return Err("Alle Gattereingänge müssen verbunden sein.".to_string());
+            }
+            let a = mapped(&map, node.inputs[0].unwrap());
+            let b = node.inputs.get(1).map_or(a, |src| mapped(&map, src.unwrap()));
+            map[n] = builder.gate(gate, a, b);
+        }
+    }
+    for n in circuit.output_nodes() {
+        let y = builder.circuit.add_node(circuit.nodes[n].kind.clone(), Pos2::ZERO);
+        builder.circuit.nodes[y].inputs = circuit.nodes[n].inputs.iter().map(|src| src.map(|src| mapped(&map, src))).collect();
+    }
+
+    // Durch das Kürzen doppelter Negationen können Gatter ohne Abnehmer übrig bleiben
+    let mut result = builder.circuit;
+    while let Some(n) = (0..result.nodes.len()).find(|&n| {
+        matches!(result.nodes[n].kind, NodeKind::Gate(_)) && !result.nodes.iter().any(|m| m.inputs.contains(&Some(n.into())))
+    }) {
+        result.remove_node(n);
+    }
+    layout::arrange(&mut result);
+    Ok(result)
+}
+
+/// Anzahl der Gatter insgesamt und je Gatterart, z.B. "5 Gatter (2× AND, 1× OR, 2× NOT)", dazu die Zahl der Bausteine
+pub fn gate_summary(circuit: &Circuit) -> String {
+    let count = |gate: LogicGates| circuit.nodes.iter().filter(|n| n.kind == NodeKind::Gate(gate)).count();
+    let total: usize = ALL_GATES.iter().map(|&g| count(g)).sum();
+    let parts: Vec<String> = ALL_GATES
+        .iter()
+        .filter(|&&g| count(g) > 0)
+        .map(|&g| format!("{}× {}", count(g), g))
+        .collect();
+    let gates = if parts.is_empty() { "0 Gatter".to_string() } else { format!("{} Gatter ({})", total, parts.join(", ")) };
+    let components = match circuit.nodes.iter().filter(|n| matches!(n.kind, NodeKind::Component(_))).count() {
+        0 => return gates,
+        1 => "1 Baustein".to_string(),
+        k => format!("{} Bausteine", k),
+    };
+    if total == 0 { components } else { format!("{} und {}", gates, components) }
+}
@@ -0,0 +1,88 @@
+// Arbeitsblätter zum Ausdrucken: je Aufgabe eines Übungspakets eine leere Wahrheitstabelle, leere KV-Diagramme und
+// Platz für die Ausdrücke, dazu ein getrenntes Lösungsblatt. Erzeugt wird HTML, das sich im Browser drucken oder
+// als PDF speichern lässt.
+
+use crate::exercise::{Exercise, ExercisePack};
+use crate::export;
+use crate::expr::or_of;
+use crate::kv_view::{kv_text_table, MAX_INPUTS};
+use crate::minimize;
+use crate::text_table::html_escape;
+use crate::truth_table::TruthTable;
+
+/// Größere Wahrheitstabellen passen nicht mehr sinnvoll auf ein Blatt
+const MAX_TABLE_INPUTS: usize = 5;
+
+const STYLE: &str = "
+body { font-family: sans-serif; margin: 2em; }
+section { break-inside: avoid; margin-bottom: 2em; }
+.parts { display: flex; flex-wrap: wrap; gap: 2em; align-items: flex-start; }
+table { border-collapse: collapse; }
+th, td { border: 1px solid black; min-width: 2em; height: 1.6em; text-align: center; padding: 0 0.3em; }
+.split { border-left: 3px double black; }
+.line { border-bottom: 1px solid black; height: 2em; margin: 0.5em 0; }
+.name { display: flex; gap: 4em; }
+@media print { body { margin: 0; } }
+";
+
+/// Dateiname ohne Leer- und Sonderzeichen, z.B. `arbeitsblatt-grundlagen.html`
+pub fn file_name(prefix: &str, pack: &ExercisePack) -> String {
+    export::file_name(&format!("{}-{}", prefix, pack.name), "html")
+}
+
+fn exercise_html(number: usize, exercise: &Exercise, answers: bool) -> String {
+    let vars: Vec<String> = exercise.inputs.iter().map(|s| s.to_string()).collect();
+    let outputs: Vec<(String, _)> = exercise.outputs.iter().map(|(name, _)| name.to_string()).zip(exercise.reference()).collect();
+    let table = TruthTable::from_exprs(&vars, &outputs);
+    let n = vars.len();
+    let mut text = format!("<section>\n<h2>{}. {}</h2>\n<p>{}</p>\n<div class=\"parts\">\n", number, html_escape(exercise.title), html_escape(exercise.task));
+    if n <= MAX_TABLE_INPUTS {
+        let truth_table = table.text_table();
+        let truth_table = if answers { truth_table } else { truth_table.blank(n) };
+        text += &format!("<div>\n<h3>Wahrheitstabelle</h3>\n{}</div>\n", truth_table.html());
+    }
+    if n <= MAX_INPUTS {
+        for (o, output) in table.outputs.iter().enumerate() {
+            let kv = kv_text_table(&table, o);
+            let kv = if answers { kv } else { kv.blank(1) };
+            text += &format!("<div>\n<h3>KV-Diagramm {}</h3>\n{}</div>\n", html_escape(&output.name), kv.html());
+        }
+    }
+    text += "</div>\n";
+    if n > MAX_TABLE_INPUTS {
+        text += &format!("<p>Bei {} Eingängen hätte die Wahrheitstabelle {} Zeilen, sie entfällt daher.</p>\n", n, table.row_count());
+    }
+    text += "<h3>Ausdrücke</h3>\n";
+    for (o, (name, reference)) in outputs.iter().enumerate() {
+        if !answers {
+            text += &format!("<div class=\"line\">{} =</div>\n", html_escape(name));
+            continue;
+        }
+        text += &format!("<p>{} = {}</p>\n", html_escape(name), html_escape(&reference.display(&vars).to_string()));
+        if n <= MAX_INPUTS {
+            let output = &table.outputs[o];
+            let cover = minimize::minimal_cover(&output.minterms(), &output.dont_cares(), n);
+            let dnf = or_of(cover.iter().map(|implicant| implicant.product(n)).collect());
+            text += &format!("<p>Minimale DNF: {} = {}</p>\n", html_escape(name), html_escape(&dnf.display(&vars).to_string()));
+        }
+    }
+    text + "</section>\n"
+}
+
+/// Arbeitsblatt zu allen Aufgaben des Pakets; mit `answers` das Lösungsblatt mit ausgefüllten Tabellen und Ausdrücken
+pub fn html(pack: &ExercisePack, answers: bool) -> String {
+    let title = format!("{}: {}", if answers { "Lösungen" } else { "Arbeitsblatt" }, pack.name);
+    let mut text = format!(
+        "<!DOCTYPE html>\n<html lang=\"de\">\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>{}</style>\n</head>\n<body>\n<h1>{}</h1>\n",
+        html_escape(&title),
+        STYLE,
+        html_escape(&title)
+    );
+    if !answers {
+        text += "<div class=\"name\"><p>Name: ______________________</p><p>Datum: ____________</p></div>\n";
+    }
+    for (i, exercise) in pack.exercises.iter().enumerate() {
+        text += &exercise_html(i + 1, exercise, answers);
+    }
+    text + "</body>\n</html>\n"
+}